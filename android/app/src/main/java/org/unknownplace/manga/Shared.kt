@@ -20,8 +20,11 @@ class Shared private constructor() {
                             databaseFile.createNewFile()
                         }
                         val databaseUri = Uri.fromFile(databaseFile)
+                        // pages fetched from the sites, which Android may clear when low on space
+                        val cacheDir = File(SharedContext.context().cacheDir, "http")
                         val config = Config(
-                            databaseUrl = "sqlite://" + databaseUri.path
+                            databaseUrl = "sqlite://" + databaseUri.path,
+                            cacheDir = cacheDir.path,
                         )
                         obj = Manga(config)
                     }
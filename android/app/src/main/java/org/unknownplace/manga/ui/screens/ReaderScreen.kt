@@ -104,12 +104,12 @@ fun ReaderScreen(
                 val firstImageIndex = if (singleFirstPageMode.value && page > 0) page * 2 - 1 else page * 2
 
                 if (page == 0 && singleFirstPageMode.value) {
-                    uiState.images.getOrNull(firstImageIndex)?.let {
+                    uiState.images.getOrNull(firstImageIndex)?.let { image ->
                         AsyncImage(
                             model = ImageRequest.Builder(SharedContext.context())
-                                .data(it)
+                                .data(image.url)
                                 .apply {
-                                    uiState.headers.forEach { (k, v) ->
+                                    image.headers.forEach { (k, v) ->
                                         setHeader(k, v)
                                     }
                                 }
@@ -123,9 +123,9 @@ fun ReaderScreen(
                     if (firstImageIndex < uiState.images.size) {
                         AsyncImage(
                             model = ImageRequest.Builder(SharedContext.context())
-                                .data(uiState.images[firstImageIndex])
+                                .data(uiState.images[firstImageIndex].url)
                                 .apply {
-                                    uiState.headers.forEach { (k, v) ->
+                                    uiState.images[firstImageIndex].headers.forEach { (k, v) ->
                                         setHeader(k, v)
                                     }
                                 }
@@ -138,9 +138,9 @@ fun ReaderScreen(
                     if (firstImageIndex + 1 < uiState.images.size) {
                         AsyncImage(
                             model = ImageRequest.Builder(SharedContext.context())
-                                .data(uiState.images[firstImageIndex + 1])
+                                .data(uiState.images[firstImageIndex + 1].url)
                                 .apply {
-                                    uiState.headers.forEach { (k, v) ->
+                                    uiState.images[firstImageIndex + 1].headers.forEach { (k, v) ->
                                         setHeader(k, v)
                                     }
                                 }
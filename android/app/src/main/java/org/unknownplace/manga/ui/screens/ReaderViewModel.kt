@@ -10,12 +10,12 @@ import kotlinx.coroutines.launch
 import kotlinx.coroutines.withContext
 import org.unknownplace.manga.Shared
 import uniffi.manga.MangaException
+import uniffi.manga.Page
 
 private const val TAG = "ReaderViewModel"
 
 data class ReaderUiState(
-    val images: List<String> = emptyList(),
-    val headers: Map<String, String> = emptyMap(),
+    val images: List<Page> = emptyList(),
 )
 
 class ReaderViewModel : ViewModel() {
@@ -27,28 +27,16 @@ class ReaderViewModel : ViewModel() {
             val core = Shared.instance()
 
             try {
-                val (site, images) = withContext(Dispatchers.IO) {
+                val images = withContext(Dispatchers.IO) {
                     val chapter = core.getChapter(chapterId.toLong())
                     chapter?.let { c ->
                         core.markChapterRead(c.id, true)
 
-                        val site = core.getSite(c.url)
-                        val images = core.getImages(c.url)
-
-                        Pair(site, images)
-                    } ?: run {
-                        Pair(null, emptyList())
-                    }
+                        core.getImages(c.url)
+                    } ?: emptyList()
                 }
 
-                site?.let { s ->
-                    _uiState.update { it.copy(
-                        headers = s.requestHeaders(),
-                        images = images,
-                    ) }
-                } ?: run {
-                    _uiState.update { it.copy(images = images) }
-                }
+                _uiState.update { it.copy(images = images) }
             } catch (e: MangaException) {
 
             }
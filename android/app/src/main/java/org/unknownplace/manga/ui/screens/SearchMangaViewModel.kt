@@ -59,7 +59,7 @@ class SearchMangaViewModel : ViewModel() {
             viewModelScope.launch {
                 try {
                     val res = withContext(Dispatchers.IO) {
-                        val res = site?.search(uiState.value.text)
+                        val res = site?.search(uiState.value.text)?.links
                         Log.d(TAG, "res: ${res}")
                         res
                     }
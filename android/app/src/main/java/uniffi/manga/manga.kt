@@ -644,13 +644,25 @@ internal interface UniffiCallbackInterfaceLoggerMethod0 : com.sun.jna.Callback {
     fun callback(`uniffiHandle`: Long,`text`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
 }
 internal interface UniffiCallbackInterfaceMangaSiteMethod0 : com.sun.jna.Callback {
-    fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+    fun callback(`uniffiHandle`: Long,`listing`: RustBuffer.ByValue,`genre`: RustBuffer.ByValue,`cursor`: RustBuffer.ByValue,`uniffiFutureCallback`: UniffiForeignFutureCompleteRustBuffer,`uniffiCallbackData`: Long,`uniffiOutReturn`: UniffiForeignFuture,)
 }
 internal interface UniffiCallbackInterfaceMangaSiteMethod1 : com.sun.jna.Callback {
     fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
 }
 internal interface UniffiCallbackInterfaceMangaSiteMethod2 : com.sun.jna.Callback {
-    fun callback(`uniffiHandle`: Long,`text`: RustBuffer.ByValue,`uniffiFutureCallback`: UniffiForeignFutureCompleteRustBuffer,`uniffiCallbackData`: Long,`uniffiOutReturn`: UniffiForeignFuture,)
+    fun callback(`uniffiHandle`: Long,`url`: RustBuffer.ByValue,`uniffiFutureCallback`: UniffiForeignFutureCompleteRustBuffer,`uniffiCallbackData`: Long,`uniffiOutReturn`: UniffiForeignFuture,)
+}
+internal interface UniffiCallbackInterfaceMangaSiteMethod3 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceMangaSiteMethod4 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceMangaSiteMethod5 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceMangaSiteMethod6 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`text`: RustBuffer.ByValue,`cursor`: RustBuffer.ByValue,`uniffiFutureCallback`: UniffiForeignFutureCompleteRustBuffer,`uniffiCallbackData`: Long,`uniffiOutReturn`: UniffiForeignFuture,)
 }
 @Structure.FieldOrder("log", "uniffiFree")
 internal open class UniffiVTableCallbackInterfaceLogger(
@@ -668,21 +680,33 @@ internal open class UniffiVTableCallbackInterfaceLogger(
     }
 
 }
-@Structure.FieldOrder("name", "requestHeaders", "search", "uniffiFree")
+@Structure.FieldOrder("browse", "capabilities", "details", "id", "name", "requestHeaders", "search", "uniffiFree")
 internal open class UniffiVTableCallbackInterfaceMangaSite(
-    @JvmField internal var `name`: UniffiCallbackInterfaceMangaSiteMethod0? = null,
-    @JvmField internal var `requestHeaders`: UniffiCallbackInterfaceMangaSiteMethod1? = null,
-    @JvmField internal var `search`: UniffiCallbackInterfaceMangaSiteMethod2? = null,
+    @JvmField internal var `browse`: UniffiCallbackInterfaceMangaSiteMethod0? = null,
+    @JvmField internal var `capabilities`: UniffiCallbackInterfaceMangaSiteMethod1? = null,
+    @JvmField internal var `details`: UniffiCallbackInterfaceMangaSiteMethod2? = null,
+    @JvmField internal var `id`: UniffiCallbackInterfaceMangaSiteMethod3? = null,
+    @JvmField internal var `name`: UniffiCallbackInterfaceMangaSiteMethod4? = null,
+    @JvmField internal var `requestHeaders`: UniffiCallbackInterfaceMangaSiteMethod5? = null,
+    @JvmField internal var `search`: UniffiCallbackInterfaceMangaSiteMethod6? = null,
     @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
 ) : Structure() {
     class UniffiByValue(
-        `name`: UniffiCallbackInterfaceMangaSiteMethod0? = null,
-        `requestHeaders`: UniffiCallbackInterfaceMangaSiteMethod1? = null,
-        `search`: UniffiCallbackInterfaceMangaSiteMethod2? = null,
+        `browse`: UniffiCallbackInterfaceMangaSiteMethod0? = null,
+        `capabilities`: UniffiCallbackInterfaceMangaSiteMethod1? = null,
+        `details`: UniffiCallbackInterfaceMangaSiteMethod2? = null,
+        `id`: UniffiCallbackInterfaceMangaSiteMethod3? = null,
+        `name`: UniffiCallbackInterfaceMangaSiteMethod4? = null,
+        `requestHeaders`: UniffiCallbackInterfaceMangaSiteMethod5? = null,
+        `search`: UniffiCallbackInterfaceMangaSiteMethod6? = null,
         `uniffiFree`: UniffiCallbackInterfaceFree? = null,
-    ): UniffiVTableCallbackInterfaceMangaSite(`name`,`requestHeaders`,`search`,`uniffiFree`,), Structure.ByValue
+    ): UniffiVTableCallbackInterfaceMangaSite(`browse`,`capabilities`,`details`,`id`,`name`,`requestHeaders`,`search`,`uniffiFree`,), Structure.ByValue
 
    internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceMangaSite) {
+        `browse` = other.`browse`
+        `capabilities` = other.`capabilities`
+        `details` = other.`details`
+        `id` = other.`id`
         `name` = other.`name`
         `requestHeaders` = other.`requestHeaders`
         `search` = other.`search`
@@ -776,6 +800,74 @@ internal open class UniffiVTableCallbackInterfaceMangaSite(
 
 
 
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
+
 
 
 
@@ -831,10 +923,24 @@ internal interface UniffiLib : Library {
     ): Unit
     fun uniffi_manga_fn_constructor_manga_new(`config`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
     ): Pointer
+    fun uniffi_manga_fn_method_manga_add_to_library(`ptr`: Pointer,`id`: Long,
+    ): Long
+    fun uniffi_manga_fn_method_manga_check_domain(`ptr`: Pointer,`siteId`: RustBuffer.ByValue,`follow`: Byte,
+    ): Long
+    fun uniffi_manga_fn_method_manga_clear_http_cache(`ptr`: Pointer,
+    ): Long
+    fun uniffi_manga_fn_method_manga_create_category(`ptr`: Pointer,`name`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_delete_category(`ptr`: Pointer,`id`: Long,
+    ): Long
     fun uniffi_manga_fn_method_manga_delete_manga(`ptr`: Pointer,`id`: Long,
     ): Long
+    fun uniffi_manga_fn_method_manga_diagnose_site(`ptr`: Pointer,`siteId`: RustBuffer.ByValue,`sampleQuery`: RustBuffer.ByValue,
+    ): Long
     fun uniffi_manga_fn_method_manga_do_migration(`ptr`: Pointer,
     ): Long
+    fun uniffi_manga_fn_method_manga_download_image(`ptr`: Pointer,`chapterUrl`: RustBuffer.ByValue,`page`: RustBuffer.ByValue,
+    ): Long
     fun uniffi_manga_fn_method_manga_get_chapter(`ptr`: Pointer,`id`: Long,
     ): Long
     fun uniffi_manga_fn_method_manga_get_chapters(`ptr`: Pointer,`url`: RustBuffer.ByValue,
@@ -845,31 +951,85 @@ internal interface UniffiLib : Library {
     ): Long
     fun uniffi_manga_fn_method_manga_get_manga(`ptr`: Pointer,`id`: Long,
     ): Long
+    fun uniffi_manga_fn_method_manga_get_manga_categories(`ptr`: Pointer,`mangaId`: Long,
+    ): Long
     fun uniffi_manga_fn_method_manga_get_site(`ptr`: Pointer,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
     ): RustBuffer.ByValue
-    fun uniffi_manga_fn_method_manga_list_manga(`ptr`: Pointer,
+    fun uniffi_manga_fn_method_manga_import_cookies(`ptr`: Pointer,`contents`: RustBuffer.ByValue,
     ): Long
+    fun uniffi_manga_fn_method_manga_list_categories(`ptr`: Pointer,
+    ): Long
+    fun uniffi_manga_fn_method_manga_list_history(`ptr`: Pointer,
+    ): Long
+    fun uniffi_manga_fn_method_manga_list_manga(`ptr`: Pointer,`categoryId`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_list_sources(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
+    ): RustBuffer.ByValue
     fun uniffi_manga_fn_method_manga_mark_chapter_read(`ptr`: Pointer,`id`: Long,`isRead`: Byte,
     ): Long
+    fun uniffi_manga_fn_method_manga_migrate_source(`ptr`: Pointer,`mangaId`: Long,`link`: RustBuffer.ByValue,`dryRun`: Byte,
+    ): Long
     fun uniffi_manga_fn_method_manga_migration_available(`ptr`: Pointer,
     ): Long
     fun uniffi_manga_fn_method_manga_open_manga(`ptr`: Pointer,`link`: RustBuffer.ByValue,
     ): Long
     fun uniffi_manga_fn_method_manga_open_manga_with_id(`ptr`: Pointer,`id`: Long,
     ): Long
+    fun uniffi_manga_fn_method_manga_prune_history(`ptr`: Pointer,`olderThanSecs`: Long,
+    ): Long
+    fun uniffi_manga_fn_method_manga_refresh_chapters(`ptr`: Pointer,`url`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_remove_from_library(`ptr`: Pointer,`id`: Long,
+    ): Long
+    fun uniffi_manga_fn_method_manga_rename_category(`ptr`: Pointer,`id`: Long,`name`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_reorder_categories(`ptr`: Pointer,`ids`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_reorder_sources(`ptr`: Pointer,`ids`: RustBuffer.ByValue,
+    ): Long
     fun uniffi_manga_fn_method_manga_reset_db(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): Unit
+    fun uniffi_manga_fn_method_manga_resolve_url(`ptr`: Pointer,`url`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_resume_point(`ptr`: Pointer,`mangaId`: Long,
+    ): Long
+    fun uniffi_manga_fn_method_manga_save_progress(`ptr`: Pointer,`chapterId`: Long,`page`: Int,`total`: Int,
+    ): Long
+    fun uniffi_manga_fn_method_manga_search_all(`ptr`: Pointer,`text`: RustBuffer.ByValue,`collapse`: Byte,
+    ): Long
+    fun uniffi_manga_fn_method_manga_set_cookies(`ptr`: Pointer,`siteId`: RustBuffer.ByValue,`cookies`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_set_default_category(`ptr`: Pointer,`id`: Long,
+    ): Long
+    fun uniffi_manga_fn_method_manga_set_manga_categories(`ptr`: Pointer,`mangaId`: Long,`categoryIds`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_set_proxy(`ptr`: Pointer,`proxy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+    ): Unit
+    fun uniffi_manga_fn_method_manga_set_site_proxy(`ptr`: Pointer,`siteId`: RustBuffer.ByValue,`proxy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+    ): Unit
+    fun uniffi_manga_fn_method_manga_set_site_urls(`ptr`: Pointer,`siteId`: RustBuffer.ByValue,`baseUrl`: RustBuffer.ByValue,`cdnUrl`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_manga_set_source_enabled(`ptr`: Pointer,`id`: RustBuffer.ByValue,`enabled`: Byte,
+    ): Long
     fun uniffi_manga_fn_method_manga_supported_sites(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): RustBuffer.ByValue
     fun uniffi_manga_fn_clone_mangasite(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): Pointer
     fun uniffi_manga_fn_free_mangasite(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): Unit
+    fun uniffi_manga_fn_method_mangasite_browse(`ptr`: Pointer,`listing`: RustBuffer.ByValue,`genre`: RustBuffer.ByValue,`cursor`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_mangasite_capabilities(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
+    ): RustBuffer.ByValue
+    fun uniffi_manga_fn_method_mangasite_details(`ptr`: Pointer,`url`: RustBuffer.ByValue,
+    ): Long
+    fun uniffi_manga_fn_method_mangasite_id(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
+    ): RustBuffer.ByValue
     fun uniffi_manga_fn_method_mangasite_name(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): RustBuffer.ByValue
     fun uniffi_manga_fn_method_mangasite_request_headers(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): RustBuffer.ByValue
-    fun uniffi_manga_fn_method_mangasite_search(`ptr`: Pointer,`text`: RustBuffer.ByValue,
+    fun uniffi_manga_fn_method_mangasite_search(`ptr`: Pointer,`text`: RustBuffer.ByValue,`cursor`: RustBuffer.ByValue,
     ): Long
     fun uniffi_manga_fn_func_init_logger(`logger`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
     ): Unit
@@ -989,10 +1149,24 @@ internal interface UniffiLib : Library {
     ): Short
     fun uniffi_manga_checksum_method_logger_log(
     ): Short
+    fun uniffi_manga_checksum_method_manga_add_to_library(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_check_domain(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_clear_http_cache(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_create_category(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_delete_category(
+    ): Short
     fun uniffi_manga_checksum_method_manga_delete_manga(
     ): Short
+    fun uniffi_manga_checksum_method_manga_diagnose_site(
+    ): Short
     fun uniffi_manga_checksum_method_manga_do_migration(
     ): Short
+    fun uniffi_manga_checksum_method_manga_download_image(
+    ): Short
     fun uniffi_manga_checksum_method_manga_get_chapter(
     ): Short
     fun uniffi_manga_checksum_method_manga_get_chapters(
@@ -1003,22 +1177,76 @@ internal interface UniffiLib : Library {
     ): Short
     fun uniffi_manga_checksum_method_manga_get_manga(
     ): Short
+    fun uniffi_manga_checksum_method_manga_get_manga_categories(
+    ): Short
     fun uniffi_manga_checksum_method_manga_get_site(
     ): Short
+    fun uniffi_manga_checksum_method_manga_import_cookies(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_list_categories(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_list_history(
+    ): Short
     fun uniffi_manga_checksum_method_manga_list_manga(
     ): Short
+    fun uniffi_manga_checksum_method_manga_list_sources(
+    ): Short
     fun uniffi_manga_checksum_method_manga_mark_chapter_read(
     ): Short
+    fun uniffi_manga_checksum_method_manga_migrate_source(
+    ): Short
     fun uniffi_manga_checksum_method_manga_migration_available(
     ): Short
     fun uniffi_manga_checksum_method_manga_open_manga(
     ): Short
     fun uniffi_manga_checksum_method_manga_open_manga_with_id(
     ): Short
+    fun uniffi_manga_checksum_method_manga_prune_history(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_refresh_chapters(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_remove_from_library(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_rename_category(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_reorder_categories(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_reorder_sources(
+    ): Short
     fun uniffi_manga_checksum_method_manga_reset_db(
     ): Short
+    fun uniffi_manga_checksum_method_manga_resolve_url(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_resume_point(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_save_progress(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_search_all(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_cookies(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_default_category(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_manga_categories(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_proxy(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_site_proxy(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_site_urls(
+    ): Short
+    fun uniffi_manga_checksum_method_manga_set_source_enabled(
+    ): Short
     fun uniffi_manga_checksum_method_manga_supported_sites(
     ): Short
+    fun uniffi_manga_checksum_method_mangasite_browse(
+    ): Short
+    fun uniffi_manga_checksum_method_mangasite_capabilities(
+    ): Short
+    fun uniffi_manga_checksum_method_mangasite_details(
+    ): Short
+    fun uniffi_manga_checksum_method_mangasite_id(
+    ): Short
     fun uniffi_manga_checksum_method_mangasite_name(
     ): Short
     fun uniffi_manga_checksum_method_mangasite_request_headers(
@@ -1050,12 +1278,33 @@ private fun uniffiCheckApiChecksums(lib: UniffiLib) {
     if (lib.uniffi_manga_checksum_method_logger_log() != 40402.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_add_to_library() != 26483.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_check_domain() != 28236.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_clear_http_cache() != 3660.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_create_category() != 61073.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_delete_category() != 43818.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_delete_manga() != 26840.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_diagnose_site() != 65004.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_do_migration() != 2971.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_download_image() != 63710.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_get_chapter() != 63322.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
@@ -1065,21 +1314,39 @@ private fun uniffiCheckApiChecksums(lib: UniffiLib) {
     if (lib.uniffi_manga_checksum_method_manga_get_chapters_cache() != 17921.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_manga_checksum_method_manga_get_images() != 48486.toShort()) {
+    if (lib.uniffi_manga_checksum_method_manga_get_images() != 46422.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
     if (lib.uniffi_manga_checksum_method_manga_get_manga() != 31586.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_get_manga_categories() != 12069.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_get_site() != 32086.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_manga_checksum_method_manga_list_manga() != 51802.toShort()) {
+    if (lib.uniffi_manga_checksum_method_manga_import_cookies() != 16901.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_list_categories() != 24634.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_list_history() != 39871.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_list_manga() != 62686.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_list_sources() != 7853.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
     if (lib.uniffi_manga_checksum_method_manga_mark_chapter_read() != 19378.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_migrate_source() != 59629.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_migration_available() != 42674.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
@@ -1089,19 +1356,82 @@ private fun uniffiCheckApiChecksums(lib: UniffiLib) {
     if (lib.uniffi_manga_checksum_method_manga_open_manga_with_id() != 62593.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_prune_history() != 32703.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_refresh_chapters() != 3452.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_remove_from_library() != 5730.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_rename_category() != 48849.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_reorder_categories() != 13919.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_reorder_sources() != 36538.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_reset_db() != 176.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_manga_resolve_url() != 57354.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_resume_point() != 52227.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_save_progress() != 58714.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_search_all() != 1724.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_cookies() != 22659.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_default_category() != 40141.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_manga_categories() != 24354.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_proxy() != 28435.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_site_proxy() != 19450.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_site_urls() != 28181.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_manga_set_source_enabled() != 29574.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_manga_supported_sites() != 6638.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_manga_checksum_method_mangasite_browse() != 6091.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_mangasite_capabilities() != 6623.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_mangasite_details() != 46260.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_manga_checksum_method_mangasite_id() != 63866.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_manga_checksum_method_mangasite_name() != 46191.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
     if (lib.uniffi_manga_checksum_method_mangasite_request_headers() != 53345.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_manga_checksum_method_mangasite_search() != 51809.toShort()) {
+    if (lib.uniffi_manga_checksum_method_mangasite_search() != 40010.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
     if (lib.uniffi_manga_checksum_constructor_manga_new() != 25786.toShort()) {
@@ -1187,6 +1517,66 @@ inline fun <T : Disposable?, R> T.use(block: (T) -> R) =
 /** Used to instantiate an interface without an actual pointer, for fakes in tests, mostly. */
 object NoPointer
 
+public object FfiConverterUShort: FfiConverter<UShort, Short> {
+    override fun lift(value: Short): UShort {
+        return value.toUShort()
+    }
+
+    override fun read(buf: ByteBuffer): UShort {
+        return lift(buf.getShort())
+    }
+
+    override fun lower(value: UShort): Short {
+        return value.toShort()
+    }
+
+    override fun allocationSize(value: UShort) = 2UL
+
+    override fun write(value: UShort, buf: ByteBuffer) {
+        buf.putShort(value.toShort())
+    }
+}
+
+public object FfiConverterUInt: FfiConverter<UInt, Int> {
+    override fun lift(value: Int): UInt {
+        return value.toUInt()
+    }
+
+    override fun read(buf: ByteBuffer): UInt {
+        return lift(buf.getInt())
+    }
+
+    override fun lower(value: UInt): Int {
+        return value.toInt()
+    }
+
+    override fun allocationSize(value: UInt) = 4UL
+
+    override fun write(value: UInt, buf: ByteBuffer) {
+        buf.putInt(value.toInt())
+    }
+}
+
+public object FfiConverterULong: FfiConverter<ULong, Long> {
+    override fun lift(value: Long): ULong {
+        return value.toULong()
+    }
+
+    override fun read(buf: ByteBuffer): ULong {
+        return lift(buf.getLong())
+    }
+
+    override fun lower(value: ULong): Long {
+        return value.toLong()
+    }
+
+    override fun allocationSize(value: ULong) = 8UL
+
+    override fun write(value: ULong, buf: ByteBuffer) {
+        buf.putLong(value.toLong())
+    }
+}
+
 public object FfiConverterLong: FfiConverter<Long, Long> {
     override fun lift(value: Long): Long {
         return value
@@ -1301,6 +1691,22 @@ public object FfiConverterString: FfiConverter<String, RustBuffer.ByValue> {
     }
 }
 
+public object FfiConverterByteArray: FfiConverterRustBuffer<ByteArray> {
+    override fun read(buf: ByteBuffer): ByteArray {
+        val len = buf.getInt()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return byteArr
+    }
+    override fun allocationSize(value: ByteArray): ULong {
+        return 4UL + value.size.toULong()
+    }
+    override fun write(value: ByteArray, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        buf.put(value)
+    }
+}
+
 
 // This template implements a class for working with a Rust struct via a Pointer/Arc<T>
 // to the live Rust struct on the other side of the FFI.
@@ -1755,34 +2161,94 @@ public object FfiConverterTypeLogger: FfiConverter<Logger, Pointer> {
 
 public interface MangaInterface {
     
-    suspend fun `deleteManga`(`id`: kotlin.Long)
+    suspend fun `addToLibrary`(`id`: kotlin.Long)
     
-    suspend fun `doMigration`()
+    suspend fun `checkDomain`(`siteId`: kotlin.String, `follow`: kotlin.Boolean): kotlin.String?
     
-    suspend fun `getChapter`(`id`: kotlin.Long): ChapterDb?
+    suspend fun `clearHttpCache`()
     
-    suspend fun `getChapters`(`url`: kotlin.String): List<ChapterDb>
+    suspend fun `createCategory`(`name`: kotlin.String): CategoryDb
     
-    suspend fun `getChaptersCache`(`url`: kotlin.String): List<ChapterDb>
+    suspend fun `deleteCategory`(`id`: kotlin.Long)
     
-    suspend fun `getImages`(`url`: kotlin.String): List<kotlin.String>
+    suspend fun `deleteManga`(`id`: kotlin.Long)
+    
+    suspend fun `diagnoseSite`(`siteId`: kotlin.String, `sampleQuery`: kotlin.String): SiteDiagnosis
+    
+    suspend fun `doMigration`()
+    
+    suspend fun `downloadImage`(`chapterUrl`: kotlin.String, `page`: Page): kotlin.ByteArray
+    
+    suspend fun `getChapter`(`id`: kotlin.Long): ChapterDb?
+    
+    suspend fun `getChapters`(`url`: kotlin.String): List<ChapterDb>
+    
+    suspend fun `getChaptersCache`(`url`: kotlin.String): List<ChapterDb>
+    
+    suspend fun `getImages`(`url`: kotlin.String): List<Page>
     
     suspend fun `getManga`(`id`: kotlin.Long): MangaData?
     
+    suspend fun `getMangaCategories`(`mangaId`: kotlin.Long): List<CategoryDb>
+    
     fun `getSite`(`url`: kotlin.String): MangaSite?
     
-    suspend fun `listManga`(): List<MangaData>
+    suspend fun `importCookies`(`contents`: kotlin.String): kotlin.UInt
+    
+    suspend fun `listCategories`(): List<CategoryDb>
+    
+    suspend fun `listHistory`(): List<MangaData>
+    
+    suspend fun `listManga`(`categoryId`: kotlin.Long? = null): List<MangaData>
+    
+    fun `listSources`(): List<SourceInfo>
     
     suspend fun `markChapterRead`(`id`: kotlin.Long, `isRead`: kotlin.Boolean)
     
+    suspend fun `migrateSource`(`mangaId`: kotlin.Long, `link`: Link, `dryRun`: kotlin.Boolean): SourceMigration
+    
     suspend fun `migrationAvailable`(): kotlin.Boolean
     
     suspend fun `openManga`(`link`: Link): MangaData
     
     suspend fun `openMangaWithId`(`id`: kotlin.Long)
     
+    suspend fun `pruneHistory`(`olderThanSecs`: kotlin.ULong): kotlin.ULong
+    
+    suspend fun `refreshChapters`(`url`: kotlin.String): List<ChapterDb>
+    
+    suspend fun `removeFromLibrary`(`id`: kotlin.Long)
+    
+    suspend fun `renameCategory`(`id`: kotlin.Long, `name`: kotlin.String)
+    
+    suspend fun `reorderCategories`(`ids`: List<kotlin.Long>)
+    
+    suspend fun `reorderSources`(`ids`: List<kotlin.String>)
+    
     fun `resetDb`()
     
+    suspend fun `resolveUrl`(`url`: kotlin.String): ResolvedUrl
+    
+    suspend fun `resumePoint`(`mangaId`: kotlin.Long): ResumePoint?
+    
+    suspend fun `saveProgress`(`chapterId`: kotlin.Long, `page`: kotlin.UInt, `total`: kotlin.UInt): ChapterDb
+    
+    suspend fun `searchAll`(`text`: kotlin.String, `collapse`: kotlin.Boolean): FederatedResults
+    
+    suspend fun `setCookies`(`siteId`: kotlin.String, `cookies`: kotlin.String)
+    
+    suspend fun `setDefaultCategory`(`id`: kotlin.Long)
+    
+    suspend fun `setMangaCategories`(`mangaId`: kotlin.Long, `categoryIds`: List<kotlin.Long>)
+    
+    fun `setProxy`(`proxy`: ProxyConfig?)
+    
+    fun `setSiteProxy`(`siteId`: kotlin.String, `proxy`: ProxyConfig?)
+    
+    suspend fun `setSiteUrls`(`siteId`: kotlin.String, `baseUrl`: kotlin.String?, `cdnUrl`: kotlin.String?)
+    
+    suspend fun `setSourceEnabled`(`id`: kotlin.String, `enabled`: kotlin.Boolean)
+    
     fun `supportedSites`(): List<MangaSite>
     
     companion object
@@ -1877,6 +2343,114 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `addToLibrary`(`id`: kotlin.Long) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_add_to_library(
+                thisPtr,
+                FfiConverterLong.lower(`id`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `checkDomain`(`siteId`: kotlin.String, `follow`: kotlin.Boolean) : kotlin.String? {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_check_domain(
+                thisPtr,
+                FfiConverterString.lower(`siteId`),FfiConverterBoolean.lower(`follow`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterOptionalString.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `clearHttpCache`() {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_clear_http_cache(
+                thisPtr,
+                
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `createCategory`(`name`: kotlin.String) : CategoryDb {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_create_category(
+                thisPtr,
+                FfiConverterString.lower(`name`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeCategoryDb.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `deleteCategory`(`id`: kotlin.Long) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_delete_category(
+                thisPtr,
+                FfiConverterLong.lower(`id`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
     override suspend fun `deleteManga`(`id`: kotlin.Long) {
@@ -1899,6 +2473,27 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `diagnoseSite`(`siteId`: kotlin.String, `sampleQuery`: kotlin.String) : SiteDiagnosis {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_diagnose_site(
+                thisPtr,
+                FfiConverterString.lower(`siteId`),FfiConverterString.lower(`sampleQuery`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeSiteDiagnosis.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
     override suspend fun `doMigration`() {
@@ -1921,6 +2516,27 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `downloadImage`(`chapterUrl`: kotlin.String, `page`: Page) : kotlin.ByteArray {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_download_image(
+                thisPtr,
+                FfiConverterString.lower(`chapterUrl`),FfiConverterTypePage.lower(`page`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterByteArray.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
     override suspend fun `getChapter`(`id`: kotlin.Long) : ChapterDb? {
@@ -1986,7 +2602,7 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `getImages`(`url`: kotlin.String) : List<kotlin.String> {
+    override suspend fun `getImages`(`url`: kotlin.String) : List<Page> {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
             UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_get_images(
@@ -1998,7 +2614,7 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
         { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
         { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
         // lift function
-        { FfiConverterSequenceString.lift(it) },
+        { FfiConverterSequenceTypePage.lift(it) },
         // Error FFI converter
         MangaException.ErrorHandler,
     )
@@ -2026,6 +2642,27 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `getMangaCategories`(`mangaId`: kotlin.Long) : List<CategoryDb> {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_get_manga_categories(
+                thisPtr,
+                FfiConverterLong.lower(`mangaId`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterSequenceTypeCategoryDb.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
     @Throws(MangaException::class)override fun `getSite`(`url`: kotlin.String): MangaSite? {
             return FfiConverterOptionalTypeMangaSite.lift(
     callWithPointer {
@@ -2041,19 +2678,19 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `listManga`() : List<MangaData> {
+    override suspend fun `importCookies`(`contents`: kotlin.String) : kotlin.UInt {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_list_manga(
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_import_cookies(
                 thisPtr,
-                
+                FfiConverterString.lower(`contents`),
             )
         },
-        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
-        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
-        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_u32(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_u32(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_u32(future) },
         // lift function
-        { FfiConverterSequenceTypeMangaData.lift(it) },
+        { FfiConverterUInt.lift(it) },
         // Error FFI converter
         MangaException.ErrorHandler,
     )
@@ -2062,20 +2699,19 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `markChapterRead`(`id`: kotlin.Long, `isRead`: kotlin.Boolean) {
+    override suspend fun `listCategories`() : List<CategoryDb> {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_mark_chapter_read(
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_list_categories(
                 thisPtr,
-                FfiConverterLong.lower(`id`),FfiConverterBoolean.lower(`isRead`),
+                
             )
         },
-        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
-        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
-        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
         // lift function
-        { Unit },
-        
+        { FfiConverterSequenceTypeCategoryDb.lift(it) },
         // Error FFI converter
         MangaException.ErrorHandler,
     )
@@ -2084,19 +2720,19 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `migrationAvailable`() : kotlin.Boolean {
+    override suspend fun `listHistory`() : List<MangaData> {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_migration_available(
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_list_history(
                 thisPtr,
                 
             )
         },
-        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_i8(future, callback, continuation) },
-        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_i8(future, continuation) },
-        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_i8(future) },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
         // lift function
-        { FfiConverterBoolean.lift(it) },
+        { FfiConverterSequenceTypeMangaData.lift(it) },
         // Error FFI converter
         MangaException.ErrorHandler,
     )
@@ -2105,33 +2741,45 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `openManga`(`link`: Link) : MangaData {
+    override suspend fun `listManga`(`categoryId`: kotlin.Long?) : List<MangaData> {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_open_manga(
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_list_manga(
                 thisPtr,
-                FfiConverterTypeLink.lower(`link`),
+                FfiConverterOptionalLong.lower(`categoryId`),
             )
         },
         { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
         { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
         { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
         // lift function
-        { FfiConverterTypeMangaData.lift(it) },
+        { FfiConverterSequenceTypeMangaData.lift(it) },
         // Error FFI converter
         MangaException.ErrorHandler,
     )
     }
 
+    override fun `listSources`(): List<SourceInfo> {
+            return FfiConverterSequenceTypeSourceInfo.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_list_sources(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
     
     @Throws(MangaException::class)
     @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `openMangaWithId`(`id`: kotlin.Long) {
+    override suspend fun `markChapterRead`(`id`: kotlin.Long, `isRead`: kotlin.Boolean) {
         return uniffiRustCallAsync(
         callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_open_manga_with_id(
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_mark_chapter_read(
                 thisPtr,
-                FfiConverterLong.lower(`id`),
+                FfiConverterLong.lower(`id`),FfiConverterBoolean.lower(`isRead`),
             )
         },
         { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
@@ -2146,66 +2794,499 @@ open class Manga: Disposable, AutoCloseable, MangaInterface {
     }
 
     
-    @Throws(MangaException::class)override fun `resetDb`()
-        = 
-    callWithPointer {
-    uniffiRustCallWithError(MangaException) { _status ->
-    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_reset_db(
-        it, _status)
-}
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `migrateSource`(`mangaId`: kotlin.Long, `link`: Link, `dryRun`: kotlin.Boolean) : SourceMigration {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_migrate_source(
+                thisPtr,
+                FfiConverterLong.lower(`mangaId`),FfiConverterTypeLink.lower(`link`),FfiConverterBoolean.lower(`dryRun`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeSourceMigration.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
     }
-    
-    
 
-    override fun `supportedSites`(): List<MangaSite> {
-            return FfiConverterSequenceTypeMangaSite.lift(
-    callWithPointer {
-    uniffiRustCall() { _status ->
-    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_supported_sites(
-        it, _status)
-}
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `migrationAvailable`() : kotlin.Boolean {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_migration_available(
+                thisPtr,
+                
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_i8(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_i8(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_i8(future) },
+        // lift function
+        { FfiConverterBoolean.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
     }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `openManga`(`link`: Link) : MangaData {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_open_manga(
+                thisPtr,
+                FfiConverterTypeLink.lower(`link`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeMangaData.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
     )
     }
+
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `openMangaWithId`(`id`: kotlin.Long) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_open_manga_with_id(
+                thisPtr,
+                FfiConverterLong.lower(`id`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `pruneHistory`(`olderThanSecs`: kotlin.ULong) : kotlin.ULong {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_prune_history(
+                thisPtr,
+                FfiConverterULong.lower(`olderThanSecs`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_u64(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_u64(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_u64(future) },
+        // lift function
+        { FfiConverterULong.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
     
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `refreshChapters`(`url`: kotlin.String) : List<ChapterDb> {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_refresh_chapters(
+                thisPtr,
+                FfiConverterString.lower(`url`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterSequenceTypeChapterDb.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
     
-    companion object
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `removeFromLibrary`(`id`: kotlin.Long) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_remove_from_library(
+                thisPtr,
+                FfiConverterLong.lower(`id`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
     
-}
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `renameCategory`(`id`: kotlin.Long, `name`: kotlin.String) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_rename_category(
+                thisPtr,
+                FfiConverterLong.lower(`id`),FfiConverterString.lower(`name`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
-public object FfiConverterTypeManga: FfiConverter<Manga, Pointer> {
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `reorderCategories`(`ids`: List<kotlin.Long>) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_reorder_categories(
+                thisPtr,
+                FfiConverterSequenceLong.lower(`ids`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
-    override fun lower(value: Manga): Pointer {
-        return value.uniffiClonePointer()
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `reorderSources`(`ids`: List<kotlin.String>) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_reorder_sources(
+                thisPtr,
+                FfiConverterSequenceString.lower(`ids`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
     }
 
-    override fun lift(value: Pointer): Manga {
-        return Manga(value)
+    
+    @Throws(MangaException::class)override fun `resetDb`()
+        = 
+    callWithPointer {
+    uniffiRustCallWithError(MangaException) { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_reset_db(
+        it, _status)
+}
     }
+    
+    
 
-    override fun read(buf: ByteBuffer): Manga {
-        // The Rust code always writes pointers as 8 bytes, and will
-        // fail to compile if they don't fit.
-        return lift(Pointer(buf.getLong()))
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `resolveUrl`(`url`: kotlin.String) : ResolvedUrl {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_resolve_url(
+                thisPtr,
+                FfiConverterString.lower(`url`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeResolvedUrl.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
     }
 
-    override fun allocationSize(value: Manga) = 8UL
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `resumePoint`(`mangaId`: kotlin.Long) : ResumePoint? {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_resume_point(
+                thisPtr,
+                FfiConverterLong.lower(`mangaId`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterOptionalTypeResumePoint.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
-    override fun write(value: Manga, buf: ByteBuffer) {
-        // The Rust code always expects pointers written as 8 bytes,
-        // and will fail to compile if they don't fit.
-        buf.putLong(Pointer.nativeValue(lower(value)))
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `saveProgress`(`chapterId`: kotlin.Long, `page`: kotlin.UInt, `total`: kotlin.UInt) : ChapterDb {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_save_progress(
+                thisPtr,
+                FfiConverterLong.lower(`chapterId`),FfiConverterUInt.lower(`page`),FfiConverterUInt.lower(`total`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeChapterDb.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
     }
-}
 
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `searchAll`(`text`: kotlin.String, `collapse`: kotlin.Boolean) : FederatedResults {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_search_all(
+                thisPtr,
+                FfiConverterString.lower(`text`),FfiConverterBoolean.lower(`collapse`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeFederatedResults.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
 
-// This template implements a class for working with a Rust struct via a Pointer/Arc<T>
-// to the live Rust struct on the other side of the FFI.
-//
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `setCookies`(`siteId`: kotlin.String, `cookies`: kotlin.String) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_cookies(
+                thisPtr,
+                FfiConverterString.lower(`siteId`),FfiConverterString.lower(`cookies`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `setDefaultCategory`(`id`: kotlin.Long) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_default_category(
+                thisPtr,
+                FfiConverterLong.lower(`id`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `setMangaCategories`(`mangaId`: kotlin.Long, `categoryIds`: List<kotlin.Long>) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_manga_categories(
+                thisPtr,
+                FfiConverterLong.lower(`mangaId`),FfiConverterSequenceLong.lower(`categoryIds`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)override fun `setProxy`(`proxy`: ProxyConfig?)
+        = 
+    callWithPointer {
+    uniffiRustCallWithError(MangaException) { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_proxy(
+        it, FfiConverterOptionalTypeProxyConfig.lower(`proxy`),_status)
+}
+    }
+    
+    
+
+    
+    @Throws(MangaException::class)override fun `setSiteProxy`(`siteId`: kotlin.String, `proxy`: ProxyConfig?)
+        = 
+    callWithPointer {
+    uniffiRustCallWithError(MangaException) { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_site_proxy(
+        it, FfiConverterString.lower(`siteId`),FfiConverterOptionalTypeProxyConfig.lower(`proxy`),_status)
+}
+    }
+    
+    
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `setSiteUrls`(`siteId`: kotlin.String, `baseUrl`: kotlin.String?, `cdnUrl`: kotlin.String?) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_site_urls(
+                thisPtr,
+                FfiConverterString.lower(`siteId`),FfiConverterOptionalString.lower(`baseUrl`),FfiConverterOptionalString.lower(`cdnUrl`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `setSourceEnabled`(`id`: kotlin.String, `enabled`: kotlin.Boolean) {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_set_source_enabled(
+                thisPtr,
+                FfiConverterString.lower(`id`),FfiConverterBoolean.lower(`enabled`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    override fun `supportedSites`(): List<MangaSite> {
+            return FfiConverterSequenceTypeMangaSite.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_manga_supported_sites(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+    
+    companion object
+    
+}
+
+public object FfiConverterTypeManga: FfiConverter<Manga, Pointer> {
+
+    override fun lower(value: Manga): Pointer {
+        return value.uniffiClonePointer()
+    }
+
+    override fun lift(value: Pointer): Manga {
+        return Manga(value)
+    }
+
+    override fun read(buf: ByteBuffer): Manga {
+        // The Rust code always writes pointers as 8 bytes, and will
+        // fail to compile if they don't fit.
+        return lift(Pointer(buf.getLong()))
+    }
+
+    override fun allocationSize(value: Manga) = 8UL
+
+    override fun write(value: Manga, buf: ByteBuffer) {
+        // The Rust code always expects pointers written as 8 bytes,
+        // and will fail to compile if they don't fit.
+        buf.putLong(Pointer.nativeValue(lower(value)))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a Pointer/Arc<T>
+// to the live Rust struct on the other side of the FFI.
+//
 // Each instance implements core operations for working with the Rust `Arc<T>` and the
 // Kotlin Pointer to work with the live Rust struct on the other side of the FFI.
 //
@@ -2301,459 +3382,2512 @@ public object FfiConverterTypeManga: FfiConverter<Manga, Pointer> {
 //
 
 
-public interface MangaSiteInterface {
-    
-    fun `name`(): kotlin.String
-    
-    fun `requestHeaders`(): Map<kotlin.String, kotlin.String>
-    
-    suspend fun `search`(`text`: kotlin.String): List<Link>
-    
-    companion object
+public interface MangaSiteInterface {
+    
+    suspend fun `browse`(`listing`: Listing, `genre`: kotlin.String?, `cursor`: kotlin.String?): SearchPage
+    
+    fun `capabilities`(): SiteCapabilities
+    
+    suspend fun `details`(`url`: kotlin.String): MangaDetails
+    
+    fun `id`(): kotlin.String
+    
+    fun `name`(): kotlin.String
+    
+    fun `requestHeaders`(): Map<kotlin.String, kotlin.String>
+    
+    suspend fun `search`(`text`: kotlin.String, `cursor`: kotlin.String? = null): SearchPage
+    
+    companion object
+}
+
+open class MangaSite: Disposable, AutoCloseable, MangaSiteInterface {
+
+    constructor(pointer: Pointer) {
+        this.pointer = pointer
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(pointer))
+    }
+
+    /**
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noPointer: NoPointer) {
+        this.pointer = null
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(pointer))
+    }
+
+    protected val pointer: Pointer?
+    protected val cleanable: UniffiCleaner.Cleanable
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithPointer(block: (ptr: Pointer) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the pointer being freed concurrently.
+        try {
+            return block(this.uniffiClonePointer())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val pointer: Pointer?) : Runnable {
+        override fun run() {
+            pointer?.let { ptr ->
+                uniffiRustCall { status ->
+                    UniffiLib.INSTANCE.uniffi_manga_fn_free_mangasite(ptr, status)
+                }
+            }
+        }
+    }
+
+    fun uniffiClonePointer(): Pointer {
+        return uniffiRustCall() { status ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_clone_mangasite(pointer!!, status)
+        }
+    }
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `browse`(`listing`: Listing, `genre`: kotlin.String?, `cursor`: kotlin.String?) : SearchPage {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_browse(
+                thisPtr,
+                FfiConverterTypeListing.lower(`listing`),FfiConverterOptionalString.lower(`genre`),FfiConverterOptionalString.lower(`cursor`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeSearchPage.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    override fun `capabilities`(): SiteCapabilities {
+            return FfiConverterTypeSiteCapabilities.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_capabilities(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `details`(`url`: kotlin.String) : MangaDetails {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_details(
+                thisPtr,
+                FfiConverterString.lower(`url`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeMangaDetails.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    override fun `id`(): kotlin.String {
+            return FfiConverterString.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_id(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
+    override fun `name`(): kotlin.String {
+            return FfiConverterString.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_name(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
+    override fun `requestHeaders`(): Map<kotlin.String, kotlin.String> {
+            return FfiConverterMapStringString.lift(
+    callWithPointer {
+    uniffiRustCall() { _status ->
+    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_request_headers(
+        it, _status)
+}
+    }
+    )
+    }
+    
+
+    
+    @Throws(MangaException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `search`(`text`: kotlin.String, `cursor`: kotlin.String?) : SearchPage {
+        return uniffiRustCallAsync(
+        callWithPointer { thisPtr ->
+            UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_search(
+                thisPtr,
+                FfiConverterString.lower(`text`),FfiConverterOptionalString.lower(`cursor`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeSearchPage.lift(it) },
+        // Error FFI converter
+        MangaException.ErrorHandler,
+    )
+    }
+
+    
+
+    
+    
+    companion object
+    
+}
+
+public object FfiConverterTypeMangaSite: FfiConverter<MangaSite, Pointer> {
+
+    override fun lower(value: MangaSite): Pointer {
+        return value.uniffiClonePointer()
+    }
+
+    override fun lift(value: Pointer): MangaSite {
+        return MangaSite(value)
+    }
+
+    override fun read(buf: ByteBuffer): MangaSite {
+        // The Rust code always writes pointers as 8 bytes, and will
+        // fail to compile if they don't fit.
+        return lift(Pointer(buf.getLong()))
+    }
+
+    override fun allocationSize(value: MangaSite) = 8UL
+
+    override fun write(value: MangaSite, buf: ByteBuffer) {
+        // The Rust code always expects pointers written as 8 bytes,
+        // and will fail to compile if they don't fit.
+        buf.putLong(Pointer.nativeValue(lower(value)))
+    }
+}
+
+
+
+data class CategoryDb (
+    var `id`: kotlin.Long, 
+    var `name`: kotlin.String, 
+    var `position`: kotlin.Long, 
+    var `isDefault`: kotlin.Boolean, 
+    var `mangaCount`: kotlin.Long
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeCategoryDb: FfiConverterRustBuffer<CategoryDb> {
+    override fun read(buf: ByteBuffer): CategoryDb {
+        return CategoryDb(
+            FfiConverterLong.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterLong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: CategoryDb) = (
+            FfiConverterLong.allocationSize(value.`id`) +
+            FfiConverterString.allocationSize(value.`name`) +
+            FfiConverterLong.allocationSize(value.`position`) +
+            FfiConverterBoolean.allocationSize(value.`isDefault`) +
+            FfiConverterLong.allocationSize(value.`mangaCount`)
+    )
+
+    override fun write(value: CategoryDb, buf: ByteBuffer) {
+            FfiConverterLong.write(value.`id`, buf)
+            FfiConverterString.write(value.`name`, buf)
+            FfiConverterLong.write(value.`position`, buf)
+            FfiConverterBoolean.write(value.`isDefault`, buf)
+            FfiConverterLong.write(value.`mangaCount`, buf)
+    }
+}
+
+
+
+data class ChapterDb (
+    var `id`: kotlin.Long, 
+    var `manga`: kotlin.Long, 
+    var `title`: kotlin.String, 
+    var `titleNumber`: kotlin.Double, 
+    var `url`: kotlin.String, 
+    var `isRead`: kotlin.Long, 
+    var `createdAt`: kotlin.Long, 
+    var `updatedAt`: kotlin.Long, 
+    var `chapterKey`: kotlin.String?, 
+    var `lastPage`: kotlin.Long?, 
+    var `totalPages`: kotlin.Long?, 
+    var `readAt`: kotlin.Long?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeChapterDb: FfiConverterRustBuffer<ChapterDb> {
+    override fun read(buf: ByteBuffer): ChapterDb {
+        return ChapterDb(
+            FfiConverterLong.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterDouble.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalLong.read(buf),
+            FfiConverterOptionalLong.read(buf),
+            FfiConverterOptionalLong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ChapterDb) = (
+            FfiConverterLong.allocationSize(value.`id`) +
+            FfiConverterLong.allocationSize(value.`manga`) +
+            FfiConverterString.allocationSize(value.`title`) +
+            FfiConverterDouble.allocationSize(value.`titleNumber`) +
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterLong.allocationSize(value.`isRead`) +
+            FfiConverterLong.allocationSize(value.`createdAt`) +
+            FfiConverterLong.allocationSize(value.`updatedAt`) +
+            FfiConverterOptionalString.allocationSize(value.`chapterKey`) +
+            FfiConverterOptionalLong.allocationSize(value.`lastPage`) +
+            FfiConverterOptionalLong.allocationSize(value.`totalPages`) +
+            FfiConverterOptionalLong.allocationSize(value.`readAt`)
+    )
+
+    override fun write(value: ChapterDb, buf: ByteBuffer) {
+            FfiConverterLong.write(value.`id`, buf)
+            FfiConverterLong.write(value.`manga`, buf)
+            FfiConverterString.write(value.`title`, buf)
+            FfiConverterDouble.write(value.`titleNumber`, buf)
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterLong.write(value.`isRead`, buf)
+            FfiConverterLong.write(value.`createdAt`, buf)
+            FfiConverterLong.write(value.`updatedAt`, buf)
+            FfiConverterOptionalString.write(value.`chapterKey`, buf)
+            FfiConverterOptionalLong.write(value.`lastPage`, buf)
+            FfiConverterOptionalLong.write(value.`totalPages`, buf)
+            FfiConverterOptionalLong.write(value.`readAt`, buf)
+    }
+}
+
+
+
+data class ChapterMatch (
+    var `number`: kotlin.Double?, 
+    var `old`: ChapterDb?, 
+    var `new`: Link?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeChapterMatch: FfiConverterRustBuffer<ChapterMatch> {
+    override fun read(buf: ByteBuffer): ChapterMatch {
+        return ChapterMatch(
+            FfiConverterOptionalDouble.read(buf),
+            FfiConverterOptionalTypeChapterDb.read(buf),
+            FfiConverterOptionalTypeLink.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ChapterMatch) = (
+            FfiConverterOptionalDouble.allocationSize(value.`number`) +
+            FfiConverterOptionalTypeChapterDb.allocationSize(value.`old`) +
+            FfiConverterOptionalTypeLink.allocationSize(value.`new`)
+    )
+
+    override fun write(value: ChapterMatch, buf: ByteBuffer) {
+            FfiConverterOptionalDouble.write(value.`number`, buf)
+            FfiConverterOptionalTypeChapterDb.write(value.`old`, buf)
+            FfiConverterOptionalTypeLink.write(value.`new`, buf)
+    }
+}
+
+
+
+data class Config (
+    var `databaseUrl`: kotlin.String, 
+    var `sitesDir`: kotlin.String? = null, 
+    var `searchTimeoutSecs`: kotlin.ULong = 15uL, 
+    var `rateLimits`: Map<kotlin.String, RateLimit>? = null, 
+    var `retry`: RetryPolicy? = null, 
+    var `proxy`: ProxyConfig? = null, 
+    var `siteProxies`: Map<kotlin.String, ProxyConfig>? = null, 
+    var `cacheDir`: kotlin.String? = null, 
+    var `cacheTtlSecs`: kotlin.ULong = 300uL, 
+    var `cacheMaxBytes`: kotlin.ULong = 52428800uL
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeConfig: FfiConverterRustBuffer<Config> {
+    override fun read(buf: ByteBuffer): Config {
+        return Config(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterOptionalMapStringTypeRateLimit.read(buf),
+            FfiConverterOptionalTypeRetryPolicy.read(buf),
+            FfiConverterOptionalTypeProxyConfig.read(buf),
+            FfiConverterOptionalMapStringTypeProxyConfig.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: Config) = (
+            FfiConverterString.allocationSize(value.`databaseUrl`) +
+            FfiConverterOptionalString.allocationSize(value.`sitesDir`) +
+            FfiConverterULong.allocationSize(value.`searchTimeoutSecs`) +
+            FfiConverterOptionalMapStringTypeRateLimit.allocationSize(value.`rateLimits`) +
+            FfiConverterOptionalTypeRetryPolicy.allocationSize(value.`retry`) +
+            FfiConverterOptionalTypeProxyConfig.allocationSize(value.`proxy`) +
+            FfiConverterOptionalMapStringTypeProxyConfig.allocationSize(value.`siteProxies`) +
+            FfiConverterOptionalString.allocationSize(value.`cacheDir`) +
+            FfiConverterULong.allocationSize(value.`cacheTtlSecs`) +
+            FfiConverterULong.allocationSize(value.`cacheMaxBytes`)
+    )
+
+    override fun write(value: Config, buf: ByteBuffer) {
+            FfiConverterString.write(value.`databaseUrl`, buf)
+            FfiConverterOptionalString.write(value.`sitesDir`, buf)
+            FfiConverterULong.write(value.`searchTimeoutSecs`, buf)
+            FfiConverterOptionalMapStringTypeRateLimit.write(value.`rateLimits`, buf)
+            FfiConverterOptionalTypeRetryPolicy.write(value.`retry`, buf)
+            FfiConverterOptionalTypeProxyConfig.write(value.`proxy`, buf)
+            FfiConverterOptionalMapStringTypeProxyConfig.write(value.`siteProxies`, buf)
+            FfiConverterOptionalString.write(value.`cacheDir`, buf)
+            FfiConverterULong.write(value.`cacheTtlSecs`, buf)
+            FfiConverterULong.write(value.`cacheMaxBytes`, buf)
+    }
+}
+
+
+
+data class Descramble (
+    var `scheme`: kotlin.String, 
+    var `params`: Map<kotlin.String, kotlin.String>
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeDescramble: FfiConverterRustBuffer<Descramble> {
+    override fun read(buf: ByteBuffer): Descramble {
+        return Descramble(
+            FfiConverterString.read(buf),
+            FfiConverterMapStringString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: Descramble) = (
+            FfiConverterString.allocationSize(value.`scheme`) +
+            FfiConverterMapStringString.allocationSize(value.`params`)
+    )
+
+    override fun write(value: Descramble, buf: ByteBuffer) {
+            FfiConverterString.write(value.`scheme`, buf)
+            FfiConverterMapStringString.write(value.`params`, buf)
+    }
+}
+
+
+
+data class FederatedResults (
+    var `sites`: List<SiteResults>, 
+    var `merged`: List<MergedTitle>
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeFederatedResults: FfiConverterRustBuffer<FederatedResults> {
+    override fun read(buf: ByteBuffer): FederatedResults {
+        return FederatedResults(
+            FfiConverterSequenceTypeSiteResults.read(buf),
+            FfiConverterSequenceTypeMergedTitle.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: FederatedResults) = (
+            FfiConverterSequenceTypeSiteResults.allocationSize(value.`sites`) +
+            FfiConverterSequenceTypeMergedTitle.allocationSize(value.`merged`)
+    )
+
+    override fun write(value: FederatedResults, buf: ByteBuffer) {
+            FfiConverterSequenceTypeSiteResults.write(value.`sites`, buf)
+            FfiConverterSequenceTypeMergedTitle.write(value.`merged`, buf)
+    }
+}
+
+
+
+data class Link (
+    var `text`: kotlin.String, 
+    var `url`: kotlin.String, 
+    var `image`: kotlin.String?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeLink: FfiConverterRustBuffer<Link> {
+    override fun read(buf: ByteBuffer): Link {
+        return Link(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: Link) = (
+            FfiConverterString.allocationSize(value.`text`) +
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterOptionalString.allocationSize(value.`image`)
+    )
+
+    override fun write(value: Link, buf: ByteBuffer) {
+            FfiConverterString.write(value.`text`, buf)
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterOptionalString.write(value.`image`, buf)
+    }
+}
+
+
+
+data class MangaData (
+    var `id`: kotlin.Long, 
+    var `title`: kotlin.String, 
+    var `url`: kotlin.String, 
+    var `domain`: kotlin.String, 
+    var `image`: kotlin.String?, 
+    var `createdAt`: kotlin.Long, 
+    var `updatedAt`: kotlin.Long, 
+    var `details`: MangaDetails, 
+    var `inLibrary`: kotlin.Boolean
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeMangaData: FfiConverterRustBuffer<MangaData> {
+    override fun read(buf: ByteBuffer): MangaData {
+        return MangaData(
+            FfiConverterLong.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterTypeMangaDetails.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: MangaData) = (
+            FfiConverterLong.allocationSize(value.`id`) +
+            FfiConverterString.allocationSize(value.`title`) +
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterString.allocationSize(value.`domain`) +
+            FfiConverterOptionalString.allocationSize(value.`image`) +
+            FfiConverterLong.allocationSize(value.`createdAt`) +
+            FfiConverterLong.allocationSize(value.`updatedAt`) +
+            FfiConverterTypeMangaDetails.allocationSize(value.`details`) +
+            FfiConverterBoolean.allocationSize(value.`inLibrary`)
+    )
+
+    override fun write(value: MangaData, buf: ByteBuffer) {
+            FfiConverterLong.write(value.`id`, buf)
+            FfiConverterString.write(value.`title`, buf)
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterString.write(value.`domain`, buf)
+            FfiConverterOptionalString.write(value.`image`, buf)
+            FfiConverterLong.write(value.`createdAt`, buf)
+            FfiConverterLong.write(value.`updatedAt`, buf)
+            FfiConverterTypeMangaDetails.write(value.`details`, buf)
+            FfiConverterBoolean.write(value.`inLibrary`, buf)
+    }
+}
+
+
+
+data class MangaDetails (
+    var `authors`: List<kotlin.String>, 
+    var `artists`: List<kotlin.String>, 
+    var `genres`: List<kotlin.String>, 
+    var `status`: PublicationStatus, 
+    var `altTitles`: List<kotlin.String>, 
+    var `description`: kotlin.String?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeMangaDetails: FfiConverterRustBuffer<MangaDetails> {
+    override fun read(buf: ByteBuffer): MangaDetails {
+        return MangaDetails(
+            FfiConverterSequenceString.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterTypePublicationStatus.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: MangaDetails) = (
+            FfiConverterSequenceString.allocationSize(value.`authors`) +
+            FfiConverterSequenceString.allocationSize(value.`artists`) +
+            FfiConverterSequenceString.allocationSize(value.`genres`) +
+            FfiConverterTypePublicationStatus.allocationSize(value.`status`) +
+            FfiConverterSequenceString.allocationSize(value.`altTitles`) +
+            FfiConverterOptionalString.allocationSize(value.`description`)
+    )
+
+    override fun write(value: MangaDetails, buf: ByteBuffer) {
+            FfiConverterSequenceString.write(value.`authors`, buf)
+            FfiConverterSequenceString.write(value.`artists`, buf)
+            FfiConverterSequenceString.write(value.`genres`, buf)
+            FfiConverterTypePublicationStatus.write(value.`status`, buf)
+            FfiConverterSequenceString.write(value.`altTitles`, buf)
+            FfiConverterOptionalString.write(value.`description`, buf)
+    }
+}
+
+
+
+data class MergedTitle (
+    var `title`: kotlin.String, 
+    var `image`: kotlin.String?, 
+    var `sources`: List<SiteLink>
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeMergedTitle: FfiConverterRustBuffer<MergedTitle> {
+    override fun read(buf: ByteBuffer): MergedTitle {
+        return MergedTitle(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterSequenceTypeSiteLink.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: MergedTitle) = (
+            FfiConverterString.allocationSize(value.`title`) +
+            FfiConverterOptionalString.allocationSize(value.`image`) +
+            FfiConverterSequenceTypeSiteLink.allocationSize(value.`sources`)
+    )
+
+    override fun write(value: MergedTitle, buf: ByteBuffer) {
+            FfiConverterString.write(value.`title`, buf)
+            FfiConverterOptionalString.write(value.`image`, buf)
+            FfiConverterSequenceTypeSiteLink.write(value.`sources`, buf)
+    }
+}
+
+
+
+data class MissingAttr (
+    var `selector`: kotlin.String, 
+    var `attr`: kotlin.String, 
+    var `count`: kotlin.ULong
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeMissingAttr: FfiConverterRustBuffer<MissingAttr> {
+    override fun read(buf: ByteBuffer): MissingAttr {
+        return MissingAttr(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: MissingAttr) = (
+            FfiConverterString.allocationSize(value.`selector`) +
+            FfiConverterString.allocationSize(value.`attr`) +
+            FfiConverterULong.allocationSize(value.`count`)
+    )
+
+    override fun write(value: MissingAttr, buf: ByteBuffer) {
+            FfiConverterString.write(value.`selector`, buf)
+            FfiConverterString.write(value.`attr`, buf)
+            FfiConverterULong.write(value.`count`, buf)
+    }
+}
+
+
+
+data class Page (
+    var `index`: kotlin.UInt, 
+    var `url`: kotlin.String, 
+    var `headers`: Map<kotlin.String, kotlin.String>, 
+    var `width`: kotlin.UInt?, 
+    var `height`: kotlin.UInt?, 
+    var `alternates`: List<kotlin.String>, 
+    var `descramble`: Descramble?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypePage: FfiConverterRustBuffer<Page> {
+    override fun read(buf: ByteBuffer): Page {
+        return Page(
+            FfiConverterUInt.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterMapStringString.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterOptionalTypeDescramble.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: Page) = (
+            FfiConverterUInt.allocationSize(value.`index`) +
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterMapStringString.allocationSize(value.`headers`) +
+            FfiConverterOptionalUInt.allocationSize(value.`width`) +
+            FfiConverterOptionalUInt.allocationSize(value.`height`) +
+            FfiConverterSequenceString.allocationSize(value.`alternates`) +
+            FfiConverterOptionalTypeDescramble.allocationSize(value.`descramble`)
+    )
+
+    override fun write(value: Page, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`index`, buf)
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterMapStringString.write(value.`headers`, buf)
+            FfiConverterOptionalUInt.write(value.`width`, buf)
+            FfiConverterOptionalUInt.write(value.`height`, buf)
+            FfiConverterSequenceString.write(value.`alternates`, buf)
+            FfiConverterOptionalTypeDescramble.write(value.`descramble`, buf)
+    }
+}
+
+
+
+data class ProxyConfig (
+    var `url`: kotlin.String, 
+    var `username`: kotlin.String? = null, 
+    var `password`: kotlin.String? = null, 
+    var `noProxy`: List<kotlin.String> = listOf()
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeProxyConfig: FfiConverterRustBuffer<ProxyConfig> {
+    override fun read(buf: ByteBuffer): ProxyConfig {
+        return ProxyConfig(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterSequenceString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ProxyConfig) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterOptionalString.allocationSize(value.`username`) +
+            FfiConverterOptionalString.allocationSize(value.`password`) +
+            FfiConverterSequenceString.allocationSize(value.`noProxy`)
+    )
+
+    override fun write(value: ProxyConfig, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterOptionalString.write(value.`username`, buf)
+            FfiConverterOptionalString.write(value.`password`, buf)
+            FfiConverterSequenceString.write(value.`noProxy`, buf)
+    }
+}
+
+
+
+data class RateLimit (
+    var `requestsPerSec`: kotlin.Double = 2.0, 
+    var `burst`: kotlin.UInt = 4u, 
+    var `maxConcurrent`: kotlin.UInt = 2u
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeRateLimit: FfiConverterRustBuffer<RateLimit> {
+    override fun read(buf: ByteBuffer): RateLimit {
+        return RateLimit(
+            FfiConverterDouble.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RateLimit) = (
+            FfiConverterDouble.allocationSize(value.`requestsPerSec`) +
+            FfiConverterUInt.allocationSize(value.`burst`) +
+            FfiConverterUInt.allocationSize(value.`maxConcurrent`)
+    )
+
+    override fun write(value: RateLimit, buf: ByteBuffer) {
+            FfiConverterDouble.write(value.`requestsPerSec`, buf)
+            FfiConverterUInt.write(value.`burst`, buf)
+            FfiConverterUInt.write(value.`maxConcurrent`, buf)
+    }
+}
+
+
+
+data class RequestReport (
+    var `url`: kotlin.String, 
+    var `status`: kotlin.UShort, 
+    var `bytes`: kotlin.ULong, 
+    var `elapsedMs`: kotlin.ULong
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeRequestReport: FfiConverterRustBuffer<RequestReport> {
+    override fun read(buf: ByteBuffer): RequestReport {
+        return RequestReport(
+            FfiConverterString.read(buf),
+            FfiConverterUShort.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RequestReport) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterUShort.allocationSize(value.`status`) +
+            FfiConverterULong.allocationSize(value.`bytes`) +
+            FfiConverterULong.allocationSize(value.`elapsedMs`)
+    )
+
+    override fun write(value: RequestReport, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterUShort.write(value.`status`, buf)
+            FfiConverterULong.write(value.`bytes`, buf)
+            FfiConverterULong.write(value.`elapsedMs`, buf)
+    }
+}
+
+
+
+data class ResolvedUrl (
+    var `siteId`: kotlin.String, 
+    var `kind`: UrlKind, 
+    var `manga`: MangaData, 
+    var `chapter`: ChapterDb?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeResolvedUrl: FfiConverterRustBuffer<ResolvedUrl> {
+    override fun read(buf: ByteBuffer): ResolvedUrl {
+        return ResolvedUrl(
+            FfiConverterString.read(buf),
+            FfiConverterTypeUrlKind.read(buf),
+            FfiConverterTypeMangaData.read(buf),
+            FfiConverterOptionalTypeChapterDb.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ResolvedUrl) = (
+            FfiConverterString.allocationSize(value.`siteId`) +
+            FfiConverterTypeUrlKind.allocationSize(value.`kind`) +
+            FfiConverterTypeMangaData.allocationSize(value.`manga`) +
+            FfiConverterOptionalTypeChapterDb.allocationSize(value.`chapter`)
+    )
+
+    override fun write(value: ResolvedUrl, buf: ByteBuffer) {
+            FfiConverterString.write(value.`siteId`, buf)
+            FfiConverterTypeUrlKind.write(value.`kind`, buf)
+            FfiConverterTypeMangaData.write(value.`manga`, buf)
+            FfiConverterOptionalTypeChapterDb.write(value.`chapter`, buf)
+    }
+}
+
+
+
+data class ResumePoint (
+    var `chapter`: ChapterDb, 
+    var `page`: kotlin.UInt
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeResumePoint: FfiConverterRustBuffer<ResumePoint> {
+    override fun read(buf: ByteBuffer): ResumePoint {
+        return ResumePoint(
+            FfiConverterTypeChapterDb.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ResumePoint) = (
+            FfiConverterTypeChapterDb.allocationSize(value.`chapter`) +
+            FfiConverterUInt.allocationSize(value.`page`)
+    )
+
+    override fun write(value: ResumePoint, buf: ByteBuffer) {
+            FfiConverterTypeChapterDb.write(value.`chapter`, buf)
+            FfiConverterUInt.write(value.`page`, buf)
+    }
+}
+
+
+
+data class RetryPolicy (
+    var `maxAttempts`: kotlin.UInt = 3u, 
+    var `initialBackoffMs`: kotlin.ULong = 500uL, 
+    var `maxBackoffMs`: kotlin.ULong = 10000uL, 
+    var `deadlineSecs`: kotlin.ULong = 60uL
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeRetryPolicy: FfiConverterRustBuffer<RetryPolicy> {
+    override fun read(buf: ByteBuffer): RetryPolicy {
+        return RetryPolicy(
+            FfiConverterUInt.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RetryPolicy) = (
+            FfiConverterUInt.allocationSize(value.`maxAttempts`) +
+            FfiConverterULong.allocationSize(value.`initialBackoffMs`) +
+            FfiConverterULong.allocationSize(value.`maxBackoffMs`) +
+            FfiConverterULong.allocationSize(value.`deadlineSecs`)
+    )
+
+    override fun write(value: RetryPolicy, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`maxAttempts`, buf)
+            FfiConverterULong.write(value.`initialBackoffMs`, buf)
+            FfiConverterULong.write(value.`maxBackoffMs`, buf)
+            FfiConverterULong.write(value.`deadlineSecs`, buf)
+    }
+}
+
+
+
+data class SearchPage (
+    var `links`: List<Link>, 
+    var `nextCursor`: kotlin.String?, 
+    var `hasMore`: kotlin.Boolean
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSearchPage: FfiConverterRustBuffer<SearchPage> {
+    override fun read(buf: ByteBuffer): SearchPage {
+        return SearchPage(
+            FfiConverterSequenceTypeLink.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SearchPage) = (
+            FfiConverterSequenceTypeLink.allocationSize(value.`links`) +
+            FfiConverterOptionalString.allocationSize(value.`nextCursor`) +
+            FfiConverterBoolean.allocationSize(value.`hasMore`)
+    )
+
+    override fun write(value: SearchPage, buf: ByteBuffer) {
+            FfiConverterSequenceTypeLink.write(value.`links`, buf)
+            FfiConverterOptionalString.write(value.`nextCursor`, buf)
+            FfiConverterBoolean.write(value.`hasMore`, buf)
+    }
+}
+
+
+
+data class SelectorReport (
+    var `selector`: kotlin.String, 
+    var `matches`: kotlin.ULong
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSelectorReport: FfiConverterRustBuffer<SelectorReport> {
+    override fun read(buf: ByteBuffer): SelectorReport {
+        return SelectorReport(
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SelectorReport) = (
+            FfiConverterString.allocationSize(value.`selector`) +
+            FfiConverterULong.allocationSize(value.`matches`)
+    )
+
+    override fun write(value: SelectorReport, buf: ByteBuffer) {
+            FfiConverterString.write(value.`selector`, buf)
+            FfiConverterULong.write(value.`matches`, buf)
+    }
+}
+
+
+
+data class SiteCapabilities (
+    var `listings`: List<Listing>, 
+    var `genreFilter`: kotlin.Boolean
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSiteCapabilities: FfiConverterRustBuffer<SiteCapabilities> {
+    override fun read(buf: ByteBuffer): SiteCapabilities {
+        return SiteCapabilities(
+            FfiConverterSequenceTypeListing.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SiteCapabilities) = (
+            FfiConverterSequenceTypeListing.allocationSize(value.`listings`) +
+            FfiConverterBoolean.allocationSize(value.`genreFilter`)
+    )
+
+    override fun write(value: SiteCapabilities, buf: ByteBuffer) {
+            FfiConverterSequenceTypeListing.write(value.`listings`, buf)
+            FfiConverterBoolean.write(value.`genreFilter`, buf)
+    }
+}
+
+
+
+data class SiteDiagnosis (
+    var `siteId`: kotlin.String, 
+    var `stages`: List<StageReport>
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSiteDiagnosis: FfiConverterRustBuffer<SiteDiagnosis> {
+    override fun read(buf: ByteBuffer): SiteDiagnosis {
+        return SiteDiagnosis(
+            FfiConverterString.read(buf),
+            FfiConverterSequenceTypeStageReport.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SiteDiagnosis) = (
+            FfiConverterString.allocationSize(value.`siteId`) +
+            FfiConverterSequenceTypeStageReport.allocationSize(value.`stages`)
+    )
+
+    override fun write(value: SiteDiagnosis, buf: ByteBuffer) {
+            FfiConverterString.write(value.`siteId`, buf)
+            FfiConverterSequenceTypeStageReport.write(value.`stages`, buf)
+    }
+}
+
+
+
+data class SiteLink (
+    var `siteId`: kotlin.String, 
+    var `url`: kotlin.String
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSiteLink: FfiConverterRustBuffer<SiteLink> {
+    override fun read(buf: ByteBuffer): SiteLink {
+        return SiteLink(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SiteLink) = (
+            FfiConverterString.allocationSize(value.`siteId`) +
+            FfiConverterString.allocationSize(value.`url`)
+    )
+
+    override fun write(value: SiteLink, buf: ByteBuffer) {
+            FfiConverterString.write(value.`siteId`, buf)
+            FfiConverterString.write(value.`url`, buf)
+    }
+}
+
+
+
+data class SiteResults (
+    var `siteId`: kotlin.String, 
+    var `siteName`: kotlin.String, 
+    var `links`: List<Link>, 
+    var `nextCursor`: kotlin.String?, 
+    var `hasMore`: kotlin.Boolean, 
+    var `error`: kotlin.String?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSiteResults: FfiConverterRustBuffer<SiteResults> {
+    override fun read(buf: ByteBuffer): SiteResults {
+        return SiteResults(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterSequenceTypeLink.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SiteResults) = (
+            FfiConverterString.allocationSize(value.`siteId`) +
+            FfiConverterString.allocationSize(value.`siteName`) +
+            FfiConverterSequenceTypeLink.allocationSize(value.`links`) +
+            FfiConverterOptionalString.allocationSize(value.`nextCursor`) +
+            FfiConverterBoolean.allocationSize(value.`hasMore`) +
+            FfiConverterOptionalString.allocationSize(value.`error`)
+    )
+
+    override fun write(value: SiteResults, buf: ByteBuffer) {
+            FfiConverterString.write(value.`siteId`, buf)
+            FfiConverterString.write(value.`siteName`, buf)
+            FfiConverterSequenceTypeLink.write(value.`links`, buf)
+            FfiConverterOptionalString.write(value.`nextCursor`, buf)
+            FfiConverterBoolean.write(value.`hasMore`, buf)
+            FfiConverterOptionalString.write(value.`error`, buf)
+    }
+}
+
+
+
+data class SourceInfo (
+    var `id`: kotlin.String, 
+    var `name`: kotlin.String, 
+    var `enabled`: kotlin.Boolean, 
+    var `position`: kotlin.Long, 
+    var `baseUrl`: kotlin.String, 
+    var `cdnUrl`: kotlin.String?, 
+    var `urlsOverridden`: kotlin.Boolean
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSourceInfo: FfiConverterRustBuffer<SourceInfo> {
+    override fun read(buf: ByteBuffer): SourceInfo {
+        return SourceInfo(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SourceInfo) = (
+            FfiConverterString.allocationSize(value.`id`) +
+            FfiConverterString.allocationSize(value.`name`) +
+            FfiConverterBoolean.allocationSize(value.`enabled`) +
+            FfiConverterLong.allocationSize(value.`position`) +
+            FfiConverterString.allocationSize(value.`baseUrl`) +
+            FfiConverterOptionalString.allocationSize(value.`cdnUrl`) +
+            FfiConverterBoolean.allocationSize(value.`urlsOverridden`)
+    )
+
+    override fun write(value: SourceInfo, buf: ByteBuffer) {
+            FfiConverterString.write(value.`id`, buf)
+            FfiConverterString.write(value.`name`, buf)
+            FfiConverterBoolean.write(value.`enabled`, buf)
+            FfiConverterLong.write(value.`position`, buf)
+            FfiConverterString.write(value.`baseUrl`, buf)
+            FfiConverterOptionalString.write(value.`cdnUrl`, buf)
+            FfiConverterBoolean.write(value.`urlsOverridden`, buf)
+    }
+}
+
+
+
+data class SourceMigration (
+    var `mangaId`: kotlin.Long, 
+    var `chapters`: List<ChapterMatch>, 
+    var `skipped`: List<Link>, 
+    var `applied`: kotlin.Boolean
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeSourceMigration: FfiConverterRustBuffer<SourceMigration> {
+    override fun read(buf: ByteBuffer): SourceMigration {
+        return SourceMigration(
+            FfiConverterLong.read(buf),
+            FfiConverterSequenceTypeChapterMatch.read(buf),
+            FfiConverterSequenceTypeLink.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SourceMigration) = (
+            FfiConverterLong.allocationSize(value.`mangaId`) +
+            FfiConverterSequenceTypeChapterMatch.allocationSize(value.`chapters`) +
+            FfiConverterSequenceTypeLink.allocationSize(value.`skipped`) +
+            FfiConverterBoolean.allocationSize(value.`applied`)
+    )
+
+    override fun write(value: SourceMigration, buf: ByteBuffer) {
+            FfiConverterLong.write(value.`mangaId`, buf)
+            FfiConverterSequenceTypeChapterMatch.write(value.`chapters`, buf)
+            FfiConverterSequenceTypeLink.write(value.`skipped`, buf)
+            FfiConverterBoolean.write(value.`applied`, buf)
+    }
+}
+
+
+
+data class StageReport (
+    var `stage`: DiagnosticStage, 
+    var `input`: kotlin.String, 
+    var `requests`: List<RequestReport>, 
+    var `selectors`: List<SelectorReport>, 
+    var `missing`: List<MissingAttr>, 
+    var `results`: kotlin.ULong, 
+    var `elapsedMs`: kotlin.ULong, 
+    var `error`: kotlin.String?
+) {
+    
+    companion object
+}
+
+public object FfiConverterTypeStageReport: FfiConverterRustBuffer<StageReport> {
+    override fun read(buf: ByteBuffer): StageReport {
+        return StageReport(
+            FfiConverterTypeDiagnosticStage.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterSequenceTypeRequestReport.read(buf),
+            FfiConverterSequenceTypeSelectorReport.read(buf),
+            FfiConverterSequenceTypeMissingAttr.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: StageReport) = (
+            FfiConverterTypeDiagnosticStage.allocationSize(value.`stage`) +
+            FfiConverterString.allocationSize(value.`input`) +
+            FfiConverterSequenceTypeRequestReport.allocationSize(value.`requests`) +
+            FfiConverterSequenceTypeSelectorReport.allocationSize(value.`selectors`) +
+            FfiConverterSequenceTypeMissingAttr.allocationSize(value.`missing`) +
+            FfiConverterULong.allocationSize(value.`results`) +
+            FfiConverterULong.allocationSize(value.`elapsedMs`) +
+            FfiConverterOptionalString.allocationSize(value.`error`)
+    )
+
+    override fun write(value: StageReport, buf: ByteBuffer) {
+            FfiConverterTypeDiagnosticStage.write(value.`stage`, buf)
+            FfiConverterString.write(value.`input`, buf)
+            FfiConverterSequenceTypeRequestReport.write(value.`requests`, buf)
+            FfiConverterSequenceTypeSelectorReport.write(value.`selectors`, buf)
+            FfiConverterSequenceTypeMissingAttr.write(value.`missing`, buf)
+            FfiConverterULong.write(value.`results`, buf)
+            FfiConverterULong.write(value.`elapsedMs`, buf)
+            FfiConverterOptionalString.write(value.`error`, buf)
+    }
+}
+
+
+
+
+enum class DiagnosticStage {
+    
+    SEARCH,
+    CHAPTERS,
+    IMAGES;
+    companion object
+}
+
+
+public object FfiConverterTypeDiagnosticStage: FfiConverterRustBuffer<DiagnosticStage> {
+    override fun read(buf: ByteBuffer) = try {
+        DiagnosticStage.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: DiagnosticStage) = 4UL
+
+    override fun write(value: DiagnosticStage, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+
+enum class Listing {
+    
+    LATEST,
+    POPULAR;
+    companion object
+}
+
+
+public object FfiConverterTypeListing: FfiConverterRustBuffer<Listing> {
+    override fun read(buf: ByteBuffer) = try {
+        Listing.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: Listing) = 4UL
+
+    override fun write(value: Listing, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+
+
+sealed class MangaException: kotlin.Exception() {
+    
+    class InternalException(
+        
+        val `msg`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "msg=${ `msg` }"
+    }
+    
+    class NetworkException(
+        
+        val `msg`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "msg=${ `msg` }"
+    }
+    
+    class MigrateException(
+        
+        val `msg`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "msg=${ `msg` }"
+    }
+    
+    class SiteUnsupported(
+        
+        val `url`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "url=${ `url` }"
+    }
+    
+    class UnknownSite(
+        
+        val `siteId`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "siteId=${ `siteId` }"
+    }
+    
+    class MangaNotFound(
+        
+        val `mangaId`: kotlin.Long?, 
+        
+        val `url`: kotlin.String?
+        ) : MangaException() {
+        override val message
+            get() = "mangaId=${ `mangaId` }, url=${ `url` }"
+    }
+    
+    class ChapterNotFound(
+        
+        val `chapterId`: kotlin.Long
+        ) : MangaException() {
+        override val message
+            get() = "chapterId=${ `chapterId` }"
+    }
+    
+    class PageOutOfRange(
+        
+        val `page`: kotlin.UInt, 
+        
+        val `total`: kotlin.UInt
+        ) : MangaException() {
+        override val message
+            get() = "page=${ `page` }, total=${ `total` }"
+    }
+    
+    class CategoryNotFound(
+        
+        val `categoryId`: kotlin.Long
+        ) : MangaException() {
+        override val message
+            get() = "categoryId=${ `categoryId` }"
+    }
+    
+    class EmptyCategoryName(
+        ) : MangaException() {
+        override val message
+            get() = ""
+    }
+    
+    class CategoryExists(
+        
+        val `name`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "name=${ `name` }"
+    }
+    
+    class DefaultCategory(
+        
+        val `name`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "name=${ `name` }"
+    }
+    
+    class HttpStatus(
+        
+        val `siteId`: kotlin.String, 
+        
+        val `url`: kotlin.String, 
+        
+        val `status`: kotlin.UShort
+        ) : MangaException() {
+        override val message
+            get() = "siteId=${ `siteId` }, url=${ `url` }, status=${ `status` }"
+    }
+    
+    class Blocked(
+        
+        val `siteId`: kotlin.String, 
+        
+        val `url`: kotlin.String, 
+        
+        val `status`: kotlin.UShort
+        ) : MangaException() {
+        override val message
+            get() = "siteId=${ `siteId` }, url=${ `url` }, status=${ `status` }"
+    }
+    
+    class ParseFailed(
+        
+        val `siteId`: kotlin.String, 
+        
+        val `url`: kotlin.String, 
+        
+        val `msg`: kotlin.String
+        ) : MangaException() {
+        override val message
+            get() = "siteId=${ `siteId` }, url=${ `url` }, msg=${ `msg` }"
+    }
+    
+    class RateLimited(
+        
+        val `siteId`: kotlin.String, 
+        
+        val `url`: kotlin.String, 
+        
+        val `retryAfterSecs`: kotlin.ULong?
+        ) : MangaException() {
+        override val message
+            get() = "siteId=${ `siteId` }, url=${ `url` }, retryAfterSecs=${ `retryAfterSecs` }"
+    }
+    
+
+    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<MangaException> {
+        override fun lift(error_buf: RustBuffer.ByValue): MangaException = FfiConverterTypeMangaError.lift(error_buf)
+    }
+
+    
+}
+
+public object FfiConverterTypeMangaError : FfiConverterRustBuffer<MangaException> {
+    override fun read(buf: ByteBuffer): MangaException {
+        
+
+        return when(buf.getInt()) {
+            1 -> MangaException.InternalException(
+                FfiConverterString.read(buf),
+                )
+            2 -> MangaException.NetworkException(
+                FfiConverterString.read(buf),
+                )
+            3 -> MangaException.MigrateException(
+                FfiConverterString.read(buf),
+                )
+            4 -> MangaException.SiteUnsupported(
+                FfiConverterString.read(buf),
+                )
+            5 -> MangaException.UnknownSite(
+                FfiConverterString.read(buf),
+                )
+            6 -> MangaException.MangaNotFound(
+                FfiConverterOptionalLong.read(buf),
+                FfiConverterOptionalString.read(buf),
+                )
+            7 -> MangaException.ChapterNotFound(
+                FfiConverterLong.read(buf),
+                )
+            8 -> MangaException.PageOutOfRange(
+                FfiConverterUInt.read(buf),
+                FfiConverterUInt.read(buf),
+                )
+            9 -> MangaException.CategoryNotFound(
+                FfiConverterLong.read(buf),
+                )
+            10 -> MangaException.EmptyCategoryName()
+            11 -> MangaException.CategoryExists(
+                FfiConverterString.read(buf),
+                )
+            12 -> MangaException.DefaultCategory(
+                FfiConverterString.read(buf),
+                )
+            13 -> MangaException.HttpStatus(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                FfiConverterUShort.read(buf),
+                )
+            14 -> MangaException.Blocked(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                FfiConverterUShort.read(buf),
+                )
+            15 -> MangaException.ParseFailed(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            16 -> MangaException.RateLimited(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                FfiConverterOptionalULong.read(buf),
+                )
+            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: MangaException): ULong {
+        return when(value) {
+            is MangaException.InternalException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`msg`)
+            )
+            is MangaException.NetworkException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`msg`)
+            )
+            is MangaException.MigrateException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`msg`)
+            )
+            is MangaException.SiteUnsupported -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+            )
+            is MangaException.UnknownSite -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`siteId`)
+            )
+            is MangaException.MangaNotFound -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterOptionalLong.allocationSize(value.`mangaId`)
+                + FfiConverterOptionalString.allocationSize(value.`url`)
+            )
+            is MangaException.ChapterNotFound -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterLong.allocationSize(value.`chapterId`)
+            )
+            is MangaException.PageOutOfRange -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterUInt.allocationSize(value.`page`)
+                + FfiConverterUInt.allocationSize(value.`total`)
+            )
+            is MangaException.CategoryNotFound -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterLong.allocationSize(value.`categoryId`)
+            )
+            is MangaException.EmptyCategoryName -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is MangaException.CategoryExists -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`name`)
+            )
+            is MangaException.DefaultCategory -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`name`)
+            )
+            is MangaException.HttpStatus -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`siteId`)
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterUShort.allocationSize(value.`status`)
+            )
+            is MangaException.Blocked -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`siteId`)
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterUShort.allocationSize(value.`status`)
+            )
+            is MangaException.ParseFailed -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`siteId`)
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterString.allocationSize(value.`msg`)
+            )
+            is MangaException.RateLimited -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`siteId`)
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterOptionalULong.allocationSize(value.`retryAfterSecs`)
+            )
+        }
+    }
+
+    override fun write(value: MangaException, buf: ByteBuffer) {
+        when(value) {
+            is MangaException.InternalException -> {
+                buf.putInt(1)
+                FfiConverterString.write(value.`msg`, buf)
+                Unit
+            }
+            is MangaException.NetworkException -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`msg`, buf)
+                Unit
+            }
+            is MangaException.MigrateException -> {
+                buf.putInt(3)
+                FfiConverterString.write(value.`msg`, buf)
+                Unit
+            }
+            is MangaException.SiteUnsupported -> {
+                buf.putInt(4)
+                FfiConverterString.write(value.`url`, buf)
+                Unit
+            }
+            is MangaException.UnknownSite -> {
+                buf.putInt(5)
+                FfiConverterString.write(value.`siteId`, buf)
+                Unit
+            }
+            is MangaException.MangaNotFound -> {
+                buf.putInt(6)
+                FfiConverterOptionalLong.write(value.`mangaId`, buf)
+                FfiConverterOptionalString.write(value.`url`, buf)
+                Unit
+            }
+            is MangaException.ChapterNotFound -> {
+                buf.putInt(7)
+                FfiConverterLong.write(value.`chapterId`, buf)
+                Unit
+            }
+            is MangaException.PageOutOfRange -> {
+                buf.putInt(8)
+                FfiConverterUInt.write(value.`page`, buf)
+                FfiConverterUInt.write(value.`total`, buf)
+                Unit
+            }
+            is MangaException.CategoryNotFound -> {
+                buf.putInt(9)
+                FfiConverterLong.write(value.`categoryId`, buf)
+                Unit
+            }
+            is MangaException.EmptyCategoryName -> {
+                buf.putInt(10)
+                Unit
+            }
+            is MangaException.CategoryExists -> {
+                buf.putInt(11)
+                FfiConverterString.write(value.`name`, buf)
+                Unit
+            }
+            is MangaException.DefaultCategory -> {
+                buf.putInt(12)
+                FfiConverterString.write(value.`name`, buf)
+                Unit
+            }
+            is MangaException.HttpStatus -> {
+                buf.putInt(13)
+                FfiConverterString.write(value.`siteId`, buf)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterUShort.write(value.`status`, buf)
+                Unit
+            }
+            is MangaException.Blocked -> {
+                buf.putInt(14)
+                FfiConverterString.write(value.`siteId`, buf)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterUShort.write(value.`status`, buf)
+                Unit
+            }
+            is MangaException.ParseFailed -> {
+                buf.putInt(15)
+                FfiConverterString.write(value.`siteId`, buf)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterString.write(value.`msg`, buf)
+                Unit
+            }
+            is MangaException.RateLimited -> {
+                buf.putInt(16)
+                FfiConverterString.write(value.`siteId`, buf)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterOptionalULong.write(value.`retryAfterSecs`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+
+}
+
+
+
+
+enum class PublicationStatus {
+    
+    UNKNOWN,
+    ONGOING,
+    COMPLETED,
+    HIATUS;
+    companion object
+}
+
+
+public object FfiConverterTypePublicationStatus: FfiConverterRustBuffer<PublicationStatus> {
+    override fun read(buf: ByteBuffer) = try {
+        PublicationStatus.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: PublicationStatus) = 4UL
+
+    override fun write(value: PublicationStatus, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+
+enum class UrlKind {
+    
+    MANGA,
+    CHAPTER;
+    companion object
+}
+
+
+public object FfiConverterTypeUrlKind: FfiConverterRustBuffer<UrlKind> {
+    override fun read(buf: ByteBuffer) = try {
+        UrlKind.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: UrlKind) = 4UL
+
+    override fun write(value: UrlKind, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+
+public object FfiConverterOptionalUInt: FfiConverterRustBuffer<kotlin.UInt?> {
+    override fun read(buf: ByteBuffer): kotlin.UInt? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterUInt.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.UInt?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterUInt.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.UInt?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterUInt.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalULong: FfiConverterRustBuffer<kotlin.ULong?> {
+    override fun read(buf: ByteBuffer): kotlin.ULong? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterULong.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.ULong?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterULong.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.ULong?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterULong.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalLong: FfiConverterRustBuffer<kotlin.Long?> {
+    override fun read(buf: ByteBuffer): kotlin.Long? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterLong.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.Long?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterLong.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.Long?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterLong.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalDouble: FfiConverterRustBuffer<kotlin.Double?> {
+    override fun read(buf: ByteBuffer): kotlin.Double? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterDouble.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.Double?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterDouble.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.Double?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterDouble.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalString: FfiConverterRustBuffer<kotlin.String?> {
+    override fun read(buf: ByteBuffer): kotlin.String? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterString.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.String?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterString.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.String?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterString.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalTypeMangaSite: FfiConverterRustBuffer<MangaSite?> {
+    override fun read(buf: ByteBuffer): MangaSite? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeMangaSite.read(buf)
+    }
+
+    override fun allocationSize(value: MangaSite?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeMangaSite.allocationSize(value)
+        }
+    }
+
+    override fun write(value: MangaSite?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeMangaSite.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalTypeChapterDb: FfiConverterRustBuffer<ChapterDb?> {
+    override fun read(buf: ByteBuffer): ChapterDb? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeChapterDb.read(buf)
+    }
+
+    override fun allocationSize(value: ChapterDb?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeChapterDb.allocationSize(value)
+        }
+    }
+
+    override fun write(value: ChapterDb?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeChapterDb.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalTypeDescramble: FfiConverterRustBuffer<Descramble?> {
+    override fun read(buf: ByteBuffer): Descramble? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeDescramble.read(buf)
+    }
+
+    override fun allocationSize(value: Descramble?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeDescramble.allocationSize(value)
+        }
+    }
+
+    override fun write(value: Descramble?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeDescramble.write(value, buf)
+        }
+    }
 }
 
-open class MangaSite: Disposable, AutoCloseable, MangaSiteInterface {
 
-    constructor(pointer: Pointer) {
-        this.pointer = pointer
-        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(pointer))
+
+
+public object FfiConverterOptionalTypeLink: FfiConverterRustBuffer<Link?> {
+    override fun read(buf: ByteBuffer): Link? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeLink.read(buf)
     }
 
-    /**
-     * This constructor can be used to instantiate a fake object. Only used for tests. Any
-     * attempt to actually use an object constructed this way will fail as there is no
-     * connected Rust object.
-     */
-    @Suppress("UNUSED_PARAMETER")
-    constructor(noPointer: NoPointer) {
-        this.pointer = null
-        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(pointer))
+    override fun allocationSize(value: Link?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeLink.allocationSize(value)
+        }
     }
 
-    protected val pointer: Pointer?
-    protected val cleanable: UniffiCleaner.Cleanable
+    override fun write(value: Link?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeLink.write(value, buf)
+        }
+    }
+}
 
-    private val wasDestroyed = AtomicBoolean(false)
-    private val callCounter = AtomicLong(1)
 
-    override fun destroy() {
-        // Only allow a single call to this method.
-        // TODO: maybe we should log a warning if called more than once?
-        if (this.wasDestroyed.compareAndSet(false, true)) {
-            // This decrement always matches the initial count of 1 given at creation time.
-            if (this.callCounter.decrementAndGet() == 0L) {
-                cleanable.clean()
-            }
+
+
+public object FfiConverterOptionalTypeMangaData: FfiConverterRustBuffer<MangaData?> {
+    override fun read(buf: ByteBuffer): MangaData? {
+        if (buf.get().toInt() == 0) {
+            return null
         }
+        return FfiConverterTypeMangaData.read(buf)
     }
 
-    @Synchronized
-    override fun close() {
-        this.destroy()
+    override fun allocationSize(value: MangaData?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeMangaData.allocationSize(value)
+        }
     }
 
-    internal inline fun <R> callWithPointer(block: (ptr: Pointer) -> R): R {
-        // Check and increment the call counter, to keep the object alive.
-        // This needs a compare-and-set retry loop in case of concurrent updates.
-        do {
-            val c = this.callCounter.get()
-            if (c == 0L) {
-                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
-            }
-            if (c == Long.MAX_VALUE) {
-                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
-            }
-        } while (! this.callCounter.compareAndSet(c, c + 1L))
-        // Now we can safely do the method call without the pointer being freed concurrently.
-        try {
-            return block(this.uniffiClonePointer())
-        } finally {
-            // This decrement always matches the increment we performed above.
-            if (this.callCounter.decrementAndGet() == 0L) {
-                cleanable.clean()
-            }
+    override fun write(value: MangaData?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeMangaData.write(value, buf)
         }
     }
+}
 
-    // Use a static inner class instead of a closure so as not to accidentally
-    // capture `this` as part of the cleanable's action.
-    private class UniffiCleanAction(private val pointer: Pointer?) : Runnable {
-        override fun run() {
-            pointer?.let { ptr ->
-                uniffiRustCall { status ->
-                    UniffiLib.INSTANCE.uniffi_manga_fn_free_mangasite(ptr, status)
-                }
-            }
+
+
+
+public object FfiConverterOptionalTypeProxyConfig: FfiConverterRustBuffer<ProxyConfig?> {
+    override fun read(buf: ByteBuffer): ProxyConfig? {
+        if (buf.get().toInt() == 0) {
+            return null
         }
+        return FfiConverterTypeProxyConfig.read(buf)
     }
 
-    fun uniffiClonePointer(): Pointer {
-        return uniffiRustCall() { status ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_clone_mangasite(pointer!!, status)
+    override fun allocationSize(value: ProxyConfig?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeProxyConfig.allocationSize(value)
         }
     }
 
-    override fun `name`(): kotlin.String {
-            return FfiConverterString.lift(
-    callWithPointer {
-    uniffiRustCall() { _status ->
-    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_name(
-        it, _status)
+    override fun write(value: ProxyConfig?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeProxyConfig.write(value, buf)
+        }
+    }
 }
+
+
+
+
+public object FfiConverterOptionalTypeResumePoint: FfiConverterRustBuffer<ResumePoint?> {
+    override fun read(buf: ByteBuffer): ResumePoint? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeResumePoint.read(buf)
     }
-    )
+
+    override fun allocationSize(value: ResumePoint?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeResumePoint.allocationSize(value)
+        }
     }
-    
 
-    override fun `requestHeaders`(): Map<kotlin.String, kotlin.String> {
-            return FfiConverterMapStringString.lift(
-    callWithPointer {
-    uniffiRustCall() { _status ->
-    UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_request_headers(
-        it, _status)
+    override fun write(value: ResumePoint?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeResumePoint.write(value, buf)
+        }
+    }
 }
+
+
+
+
+public object FfiConverterOptionalTypeRetryPolicy: FfiConverterRustBuffer<RetryPolicy?> {
+    override fun read(buf: ByteBuffer): RetryPolicy? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeRetryPolicy.read(buf)
     }
-    )
+
+    override fun allocationSize(value: RetryPolicy?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeRetryPolicy.allocationSize(value)
+        }
     }
-    
 
-    
-    @Throws(MangaException::class)
-    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
-    override suspend fun `search`(`text`: kotlin.String) : List<Link> {
-        return uniffiRustCallAsync(
-        callWithPointer { thisPtr ->
-            UniffiLib.INSTANCE.uniffi_manga_fn_method_mangasite_search(
-                thisPtr,
-                FfiConverterString.lower(`text`),
-            )
-        },
-        { future, callback, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_poll_rust_buffer(future, callback, continuation) },
-        { future, continuation -> UniffiLib.INSTANCE.ffi_manga_rust_future_complete_rust_buffer(future, continuation) },
-        { future -> UniffiLib.INSTANCE.ffi_manga_rust_future_free_rust_buffer(future) },
-        // lift function
-        { FfiConverterSequenceTypeLink.lift(it) },
-        // Error FFI converter
-        MangaException.ErrorHandler,
-    )
+    override fun write(value: RetryPolicy?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeRetryPolicy.write(value, buf)
+        }
     }
+}
 
-    
 
-    
-    
-    companion object
-    
+
+
+public object FfiConverterOptionalMapStringTypeProxyConfig: FfiConverterRustBuffer<Map<kotlin.String, ProxyConfig>?> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, ProxyConfig>? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterMapStringTypeProxyConfig.read(buf)
+    }
+
+    override fun allocationSize(value: Map<kotlin.String, ProxyConfig>?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterMapStringTypeProxyConfig.allocationSize(value)
+        }
+    }
+
+    override fun write(value: Map<kotlin.String, ProxyConfig>?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterMapStringTypeProxyConfig.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterOptionalMapStringTypeRateLimit: FfiConverterRustBuffer<Map<kotlin.String, RateLimit>?> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, RateLimit>? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterMapStringTypeRateLimit.read(buf)
+    }
+
+    override fun allocationSize(value: Map<kotlin.String, RateLimit>?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterMapStringTypeRateLimit.allocationSize(value)
+        }
+    }
+
+    override fun write(value: Map<kotlin.String, RateLimit>?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterMapStringTypeRateLimit.write(value, buf)
+        }
+    }
+}
+
+
+
+
+public object FfiConverterSequenceLong: FfiConverterRustBuffer<List<kotlin.Long>> {
+    override fun read(buf: ByteBuffer): List<kotlin.Long> {
+        val len = buf.getInt()
+        return List<kotlin.Long>(len) {
+            FfiConverterLong.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<kotlin.Long>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterLong.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<kotlin.Long>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterLong.write(it, buf)
+        }
+    }
 }
 
-public object FfiConverterTypeMangaSite: FfiConverter<MangaSite, Pointer> {
 
-    override fun lower(value: MangaSite): Pointer {
-        return value.uniffiClonePointer()
-    }
 
-    override fun lift(value: Pointer): MangaSite {
-        return MangaSite(value)
-    }
 
-    override fun read(buf: ByteBuffer): MangaSite {
-        // The Rust code always writes pointers as 8 bytes, and will
-        // fail to compile if they don't fit.
-        return lift(Pointer(buf.getLong()))
+public object FfiConverterSequenceString: FfiConverterRustBuffer<List<kotlin.String>> {
+    override fun read(buf: ByteBuffer): List<kotlin.String> {
+        val len = buf.getInt()
+        return List<kotlin.String>(len) {
+            FfiConverterString.read(buf)
+        }
     }
 
-    override fun allocationSize(value: MangaSite) = 8UL
+    override fun allocationSize(value: List<kotlin.String>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterString.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: MangaSite, buf: ByteBuffer) {
-        // The Rust code always expects pointers written as 8 bytes,
-        // and will fail to compile if they don't fit.
-        buf.putLong(Pointer.nativeValue(lower(value)))
+    override fun write(value: List<kotlin.String>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterString.write(it, buf)
+        }
     }
 }
 
 
 
-data class ChapterDb (
-    var `id`: kotlin.Long, 
-    var `manga`: kotlin.Long, 
-    var `title`: kotlin.String, 
-    var `titleNumber`: kotlin.Double, 
-    var `url`: kotlin.String, 
-    var `isRead`: kotlin.Long, 
-    var `createdAt`: kotlin.Long, 
-    var `updatedAt`: kotlin.Long
-) {
-    
-    companion object
-}
 
-public object FfiConverterTypeChapterDb: FfiConverterRustBuffer<ChapterDb> {
-    override fun read(buf: ByteBuffer): ChapterDb {
-        return ChapterDb(
-            FfiConverterLong.read(buf),
-            FfiConverterLong.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterDouble.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterLong.read(buf),
-            FfiConverterLong.read(buf),
-            FfiConverterLong.read(buf),
-        )
+public object FfiConverterSequenceTypeMangaSite: FfiConverterRustBuffer<List<MangaSite>> {
+    override fun read(buf: ByteBuffer): List<MangaSite> {
+        val len = buf.getInt()
+        return List<MangaSite>(len) {
+            FfiConverterTypeMangaSite.read(buf)
+        }
     }
 
-    override fun allocationSize(value: ChapterDb) = (
-            FfiConverterLong.allocationSize(value.`id`) +
-            FfiConverterLong.allocationSize(value.`manga`) +
-            FfiConverterString.allocationSize(value.`title`) +
-            FfiConverterDouble.allocationSize(value.`titleNumber`) +
-            FfiConverterString.allocationSize(value.`url`) +
-            FfiConverterLong.allocationSize(value.`isRead`) +
-            FfiConverterLong.allocationSize(value.`createdAt`) +
-            FfiConverterLong.allocationSize(value.`updatedAt`)
-    )
+    override fun allocationSize(value: List<MangaSite>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeMangaSite.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: ChapterDb, buf: ByteBuffer) {
-            FfiConverterLong.write(value.`id`, buf)
-            FfiConverterLong.write(value.`manga`, buf)
-            FfiConverterString.write(value.`title`, buf)
-            FfiConverterDouble.write(value.`titleNumber`, buf)
-            FfiConverterString.write(value.`url`, buf)
-            FfiConverterLong.write(value.`isRead`, buf)
-            FfiConverterLong.write(value.`createdAt`, buf)
-            FfiConverterLong.write(value.`updatedAt`, buf)
+    override fun write(value: List<MangaSite>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeMangaSite.write(it, buf)
+        }
     }
 }
 
 
 
-data class Config (
-    var `databaseUrl`: kotlin.String
-) {
-    
-    companion object
-}
 
-public object FfiConverterTypeConfig: FfiConverterRustBuffer<Config> {
-    override fun read(buf: ByteBuffer): Config {
-        return Config(
-            FfiConverterString.read(buf),
-        )
+public object FfiConverterSequenceTypeCategoryDb: FfiConverterRustBuffer<List<CategoryDb>> {
+    override fun read(buf: ByteBuffer): List<CategoryDb> {
+        val len = buf.getInt()
+        return List<CategoryDb>(len) {
+            FfiConverterTypeCategoryDb.read(buf)
+        }
     }
 
-    override fun allocationSize(value: Config) = (
-            FfiConverterString.allocationSize(value.`databaseUrl`)
-    )
+    override fun allocationSize(value: List<CategoryDb>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeCategoryDb.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: Config, buf: ByteBuffer) {
-            FfiConverterString.write(value.`databaseUrl`, buf)
+    override fun write(value: List<CategoryDb>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeCategoryDb.write(it, buf)
+        }
     }
 }
 
 
 
-data class Link (
-    var `text`: kotlin.String, 
-    var `url`: kotlin.String, 
-    var `image`: kotlin.String?
-) {
-    
-    companion object
-}
 
-public object FfiConverterTypeLink: FfiConverterRustBuffer<Link> {
-    override fun read(buf: ByteBuffer): Link {
-        return Link(
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterOptionalString.read(buf),
-        )
+public object FfiConverterSequenceTypeChapterDb: FfiConverterRustBuffer<List<ChapterDb>> {
+    override fun read(buf: ByteBuffer): List<ChapterDb> {
+        val len = buf.getInt()
+        return List<ChapterDb>(len) {
+            FfiConverterTypeChapterDb.read(buf)
+        }
     }
 
-    override fun allocationSize(value: Link) = (
-            FfiConverterString.allocationSize(value.`text`) +
-            FfiConverterString.allocationSize(value.`url`) +
-            FfiConverterOptionalString.allocationSize(value.`image`)
-    )
+    override fun allocationSize(value: List<ChapterDb>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeChapterDb.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: Link, buf: ByteBuffer) {
-            FfiConverterString.write(value.`text`, buf)
-            FfiConverterString.write(value.`url`, buf)
-            FfiConverterOptionalString.write(value.`image`, buf)
+    override fun write(value: List<ChapterDb>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeChapterDb.write(it, buf)
+        }
     }
 }
 
 
 
-data class MangaData (
-    var `id`: kotlin.Long, 
-    var `title`: kotlin.String, 
-    var `url`: kotlin.String, 
-    var `domain`: kotlin.String, 
-    var `image`: kotlin.String?, 
-    var `createdAt`: kotlin.Long, 
-    var `updatedAt`: kotlin.Long
-) {
-    
-    companion object
-}
 
-public object FfiConverterTypeMangaData: FfiConverterRustBuffer<MangaData> {
-    override fun read(buf: ByteBuffer): MangaData {
-        return MangaData(
-            FfiConverterLong.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterOptionalString.read(buf),
-            FfiConverterLong.read(buf),
-            FfiConverterLong.read(buf),
-        )
+public object FfiConverterSequenceTypeChapterMatch: FfiConverterRustBuffer<List<ChapterMatch>> {
+    override fun read(buf: ByteBuffer): List<ChapterMatch> {
+        val len = buf.getInt()
+        return List<ChapterMatch>(len) {
+            FfiConverterTypeChapterMatch.read(buf)
+        }
     }
 
-    override fun allocationSize(value: MangaData) = (
-            FfiConverterLong.allocationSize(value.`id`) +
-            FfiConverterString.allocationSize(value.`title`) +
-            FfiConverterString.allocationSize(value.`url`) +
-            FfiConverterString.allocationSize(value.`domain`) +
-            FfiConverterOptionalString.allocationSize(value.`image`) +
-            FfiConverterLong.allocationSize(value.`createdAt`) +
-            FfiConverterLong.allocationSize(value.`updatedAt`)
-    )
+    override fun allocationSize(value: List<ChapterMatch>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeChapterMatch.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: MangaData, buf: ByteBuffer) {
-            FfiConverterLong.write(value.`id`, buf)
-            FfiConverterString.write(value.`title`, buf)
-            FfiConverterString.write(value.`url`, buf)
-            FfiConverterString.write(value.`domain`, buf)
-            FfiConverterOptionalString.write(value.`image`, buf)
-            FfiConverterLong.write(value.`createdAt`, buf)
-            FfiConverterLong.write(value.`updatedAt`, buf)
+    override fun write(value: List<ChapterMatch>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeChapterMatch.write(it, buf)
+        }
     }
 }
 
 
 
 
+public object FfiConverterSequenceTypeLink: FfiConverterRustBuffer<List<Link>> {
+    override fun read(buf: ByteBuffer): List<Link> {
+        val len = buf.getInt()
+        return List<Link>(len) {
+            FfiConverterTypeLink.read(buf)
+        }
+    }
 
-sealed class MangaException: kotlin.Exception() {
-    
-    class InternalException(
-        
-        val `msg`: kotlin.String
-        ) : MangaException() {
-        override val message
-            get() = "msg=${ `msg` }"
+    override fun allocationSize(value: List<Link>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeLink.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    class NetworkException(
-        
-        val `msg`: kotlin.String
-        ) : MangaException() {
-        override val message
-            get() = "msg=${ `msg` }"
+
+    override fun write(value: List<Link>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeLink.write(it, buf)
+        }
     }
-    
-    class MigrateException(
-        
-        val `msg`: kotlin.String
-        ) : MangaException() {
-        override val message
-            get() = "msg=${ `msg` }"
+}
+
+
+
+
+public object FfiConverterSequenceTypeMangaData: FfiConverterRustBuffer<List<MangaData>> {
+    override fun read(buf: ByteBuffer): List<MangaData> {
+        val len = buf.getInt()
+        return List<MangaData>(len) {
+            FfiConverterTypeMangaData.read(buf)
+        }
     }
-    
 
-    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<MangaException> {
-        override fun lift(error_buf: RustBuffer.ByValue): MangaException = FfiConverterTypeMangaError.lift(error_buf)
+    override fun allocationSize(value: List<MangaData>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeMangaData.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
-    
+    override fun write(value: List<MangaData>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeMangaData.write(it, buf)
+        }
+    }
 }
 
-public object FfiConverterTypeMangaError : FfiConverterRustBuffer<MangaException> {
-    override fun read(buf: ByteBuffer): MangaException {
-        
 
-        return when(buf.getInt()) {
-            1 -> MangaException.InternalException(
-                FfiConverterString.read(buf),
-                )
-            2 -> MangaException.NetworkException(
-                FfiConverterString.read(buf),
-                )
-            3 -> MangaException.MigrateException(
-                FfiConverterString.read(buf),
-                )
-            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
-        }
-    }
 
-    override fun allocationSize(value: MangaException): ULong {
-        return when(value) {
-            is MangaException.InternalException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`msg`)
-            )
-            is MangaException.NetworkException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`msg`)
-            )
-            is MangaException.MigrateException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`msg`)
-            )
+
+public object FfiConverterSequenceTypeMergedTitle: FfiConverterRustBuffer<List<MergedTitle>> {
+    override fun read(buf: ByteBuffer): List<MergedTitle> {
+        val len = buf.getInt()
+        return List<MergedTitle>(len) {
+            FfiConverterTypeMergedTitle.read(buf)
         }
     }
 
-    override fun write(value: MangaException, buf: ByteBuffer) {
-        when(value) {
-            is MangaException.InternalException -> {
-                buf.putInt(1)
-                FfiConverterString.write(value.`msg`, buf)
-                Unit
-            }
-            is MangaException.NetworkException -> {
-                buf.putInt(2)
-                FfiConverterString.write(value.`msg`, buf)
-                Unit
-            }
-            is MangaException.MigrateException -> {
-                buf.putInt(3)
-                FfiConverterString.write(value.`msg`, buf)
-                Unit
-            }
-        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    override fun allocationSize(value: List<MergedTitle>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeMergedTitle.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
+    override fun write(value: List<MergedTitle>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeMergedTitle.write(it, buf)
+        }
+    }
 }
 
 
 
 
-public object FfiConverterOptionalString: FfiConverterRustBuffer<kotlin.String?> {
-    override fun read(buf: ByteBuffer): kotlin.String? {
-        if (buf.get().toInt() == 0) {
-            return null
+public object FfiConverterSequenceTypeMissingAttr: FfiConverterRustBuffer<List<MissingAttr>> {
+    override fun read(buf: ByteBuffer): List<MissingAttr> {
+        val len = buf.getInt()
+        return List<MissingAttr>(len) {
+            FfiConverterTypeMissingAttr.read(buf)
         }
-        return FfiConverterString.read(buf)
     }
 
-    override fun allocationSize(value: kotlin.String?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterString.allocationSize(value)
-        }
+    override fun allocationSize(value: List<MissingAttr>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeMissingAttr.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
-    override fun write(value: kotlin.String?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterString.write(value, buf)
+    override fun write(value: List<MissingAttr>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeMissingAttr.write(it, buf)
         }
     }
 }
@@ -2761,28 +5895,24 @@ public object FfiConverterOptionalString: FfiConverterRustBuffer<kotlin.String?>
 
 
 
-public object FfiConverterOptionalTypeMangaSite: FfiConverterRustBuffer<MangaSite?> {
-    override fun read(buf: ByteBuffer): MangaSite? {
-        if (buf.get().toInt() == 0) {
-            return null
+public object FfiConverterSequenceTypePage: FfiConverterRustBuffer<List<Page>> {
+    override fun read(buf: ByteBuffer): List<Page> {
+        val len = buf.getInt()
+        return List<Page>(len) {
+            FfiConverterTypePage.read(buf)
         }
-        return FfiConverterTypeMangaSite.read(buf)
     }
 
-    override fun allocationSize(value: MangaSite?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterTypeMangaSite.allocationSize(value)
-        }
+    override fun allocationSize(value: List<Page>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypePage.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
-    override fun write(value: MangaSite?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterTypeMangaSite.write(value, buf)
+    override fun write(value: List<Page>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypePage.write(it, buf)
         }
     }
 }
@@ -2790,28 +5920,24 @@ public object FfiConverterOptionalTypeMangaSite: FfiConverterRustBuffer<MangaSit
 
 
 
-public object FfiConverterOptionalTypeChapterDb: FfiConverterRustBuffer<ChapterDb?> {
-    override fun read(buf: ByteBuffer): ChapterDb? {
-        if (buf.get().toInt() == 0) {
-            return null
+public object FfiConverterSequenceTypeRequestReport: FfiConverterRustBuffer<List<RequestReport>> {
+    override fun read(buf: ByteBuffer): List<RequestReport> {
+        val len = buf.getInt()
+        return List<RequestReport>(len) {
+            FfiConverterTypeRequestReport.read(buf)
         }
-        return FfiConverterTypeChapterDb.read(buf)
     }
 
-    override fun allocationSize(value: ChapterDb?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterTypeChapterDb.allocationSize(value)
-        }
+    override fun allocationSize(value: List<RequestReport>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeRequestReport.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
-    override fun write(value: ChapterDb?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterTypeChapterDb.write(value, buf)
+    override fun write(value: List<RequestReport>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeRequestReport.write(it, buf)
         }
     }
 }
@@ -2819,28 +5945,24 @@ public object FfiConverterOptionalTypeChapterDb: FfiConverterRustBuffer<ChapterD
 
 
 
-public object FfiConverterOptionalTypeMangaData: FfiConverterRustBuffer<MangaData?> {
-    override fun read(buf: ByteBuffer): MangaData? {
-        if (buf.get().toInt() == 0) {
-            return null
+public object FfiConverterSequenceTypeSelectorReport: FfiConverterRustBuffer<List<SelectorReport>> {
+    override fun read(buf: ByteBuffer): List<SelectorReport> {
+        val len = buf.getInt()
+        return List<SelectorReport>(len) {
+            FfiConverterTypeSelectorReport.read(buf)
         }
-        return FfiConverterTypeMangaData.read(buf)
     }
 
-    override fun allocationSize(value: MangaData?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterTypeMangaData.allocationSize(value)
-        }
+    override fun allocationSize(value: List<SelectorReport>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeSelectorReport.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
 
-    override fun write(value: MangaData?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterTypeMangaData.write(value, buf)
+    override fun write(value: List<SelectorReport>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeSelectorReport.write(it, buf)
         }
     }
 }
@@ -2848,24 +5970,24 @@ public object FfiConverterOptionalTypeMangaData: FfiConverterRustBuffer<MangaDat
 
 
 
-public object FfiConverterSequenceString: FfiConverterRustBuffer<List<kotlin.String>> {
-    override fun read(buf: ByteBuffer): List<kotlin.String> {
+public object FfiConverterSequenceTypeSiteLink: FfiConverterRustBuffer<List<SiteLink>> {
+    override fun read(buf: ByteBuffer): List<SiteLink> {
         val len = buf.getInt()
-        return List<kotlin.String>(len) {
-            FfiConverterString.read(buf)
+        return List<SiteLink>(len) {
+            FfiConverterTypeSiteLink.read(buf)
         }
     }
 
-    override fun allocationSize(value: List<kotlin.String>): ULong {
+    override fun allocationSize(value: List<SiteLink>): ULong {
         val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterString.allocationSize(it) }.sum()
+        val sizeForItems = value.map { FfiConverterTypeSiteLink.allocationSize(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
-    override fun write(value: List<kotlin.String>, buf: ByteBuffer) {
+    override fun write(value: List<SiteLink>, buf: ByteBuffer) {
         buf.putInt(value.size)
         value.iterator().forEach {
-            FfiConverterString.write(it, buf)
+            FfiConverterTypeSiteLink.write(it, buf)
         }
     }
 }
@@ -2873,24 +5995,24 @@ public object FfiConverterSequenceString: FfiConverterRustBuffer<List<kotlin.Str
 
 
 
-public object FfiConverterSequenceTypeMangaSite: FfiConverterRustBuffer<List<MangaSite>> {
-    override fun read(buf: ByteBuffer): List<MangaSite> {
+public object FfiConverterSequenceTypeSiteResults: FfiConverterRustBuffer<List<SiteResults>> {
+    override fun read(buf: ByteBuffer): List<SiteResults> {
         val len = buf.getInt()
-        return List<MangaSite>(len) {
-            FfiConverterTypeMangaSite.read(buf)
+        return List<SiteResults>(len) {
+            FfiConverterTypeSiteResults.read(buf)
         }
     }
 
-    override fun allocationSize(value: List<MangaSite>): ULong {
+    override fun allocationSize(value: List<SiteResults>): ULong {
         val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterTypeMangaSite.allocationSize(it) }.sum()
+        val sizeForItems = value.map { FfiConverterTypeSiteResults.allocationSize(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
-    override fun write(value: List<MangaSite>, buf: ByteBuffer) {
+    override fun write(value: List<SiteResults>, buf: ByteBuffer) {
         buf.putInt(value.size)
         value.iterator().forEach {
-            FfiConverterTypeMangaSite.write(it, buf)
+            FfiConverterTypeSiteResults.write(it, buf)
         }
     }
 }
@@ -2898,24 +6020,24 @@ public object FfiConverterSequenceTypeMangaSite: FfiConverterRustBuffer<List<Man
 
 
 
-public object FfiConverterSequenceTypeChapterDb: FfiConverterRustBuffer<List<ChapterDb>> {
-    override fun read(buf: ByteBuffer): List<ChapterDb> {
+public object FfiConverterSequenceTypeSourceInfo: FfiConverterRustBuffer<List<SourceInfo>> {
+    override fun read(buf: ByteBuffer): List<SourceInfo> {
         val len = buf.getInt()
-        return List<ChapterDb>(len) {
-            FfiConverterTypeChapterDb.read(buf)
+        return List<SourceInfo>(len) {
+            FfiConverterTypeSourceInfo.read(buf)
         }
     }
 
-    override fun allocationSize(value: List<ChapterDb>): ULong {
+    override fun allocationSize(value: List<SourceInfo>): ULong {
         val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterTypeChapterDb.allocationSize(it) }.sum()
+        val sizeForItems = value.map { FfiConverterTypeSourceInfo.allocationSize(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
-    override fun write(value: List<ChapterDb>, buf: ByteBuffer) {
+    override fun write(value: List<SourceInfo>, buf: ByteBuffer) {
         buf.putInt(value.size)
         value.iterator().forEach {
-            FfiConverterTypeChapterDb.write(it, buf)
+            FfiConverterTypeSourceInfo.write(it, buf)
         }
     }
 }
@@ -2923,24 +6045,24 @@ public object FfiConverterSequenceTypeChapterDb: FfiConverterRustBuffer<List<Cha
 
 
 
-public object FfiConverterSequenceTypeLink: FfiConverterRustBuffer<List<Link>> {
-    override fun read(buf: ByteBuffer): List<Link> {
+public object FfiConverterSequenceTypeStageReport: FfiConverterRustBuffer<List<StageReport>> {
+    override fun read(buf: ByteBuffer): List<StageReport> {
         val len = buf.getInt()
-        return List<Link>(len) {
-            FfiConverterTypeLink.read(buf)
+        return List<StageReport>(len) {
+            FfiConverterTypeStageReport.read(buf)
         }
     }
 
-    override fun allocationSize(value: List<Link>): ULong {
+    override fun allocationSize(value: List<StageReport>): ULong {
         val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterTypeLink.allocationSize(it) }.sum()
+        val sizeForItems = value.map { FfiConverterTypeStageReport.allocationSize(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
-    override fun write(value: List<Link>, buf: ByteBuffer) {
+    override fun write(value: List<StageReport>, buf: ByteBuffer) {
         buf.putInt(value.size)
         value.iterator().forEach {
-            FfiConverterTypeLink.write(it, buf)
+            FfiConverterTypeStageReport.write(it, buf)
         }
     }
 }
@@ -2948,24 +6070,24 @@ public object FfiConverterSequenceTypeLink: FfiConverterRustBuffer<List<Link>> {
 
 
 
-public object FfiConverterSequenceTypeMangaData: FfiConverterRustBuffer<List<MangaData>> {
-    override fun read(buf: ByteBuffer): List<MangaData> {
+public object FfiConverterSequenceTypeListing: FfiConverterRustBuffer<List<Listing>> {
+    override fun read(buf: ByteBuffer): List<Listing> {
         val len = buf.getInt()
-        return List<MangaData>(len) {
-            FfiConverterTypeMangaData.read(buf)
+        return List<Listing>(len) {
+            FfiConverterTypeListing.read(buf)
         }
     }
 
-    override fun allocationSize(value: List<MangaData>): ULong {
+    override fun allocationSize(value: List<Listing>): ULong {
         val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterTypeMangaData.allocationSize(it) }.sum()
+        val sizeForItems = value.map { FfiConverterTypeListing.allocationSize(it) }.sum()
         return sizeForLength + sizeForItems
     }
 
-    override fun write(value: List<MangaData>, buf: ByteBuffer) {
+    override fun write(value: List<Listing>, buf: ByteBuffer) {
         buf.putInt(value.size)
         value.iterator().forEach {
-            FfiConverterTypeMangaData.write(it, buf)
+            FfiConverterTypeListing.write(it, buf)
         }
     }
 }
@@ -3007,6 +6129,76 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
 
 
 
+public object FfiConverterMapStringTypeProxyConfig: FfiConverterRustBuffer<Map<kotlin.String, ProxyConfig>> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, ProxyConfig> {
+        val len = buf.getInt()
+        return buildMap<kotlin.String, ProxyConfig>(len) {
+            repeat(len) {
+                val k = FfiConverterString.read(buf)
+                val v = FfiConverterTypeProxyConfig.read(buf)
+                this[k] = v
+            }
+        }
+    }
+
+    override fun allocationSize(value: Map<kotlin.String, ProxyConfig>): ULong {
+        val spaceForMapSize = 4UL
+        val spaceForChildren = value.map { (k, v) ->
+            FfiConverterString.allocationSize(k) +
+            FfiConverterTypeProxyConfig.allocationSize(v)
+        }.sum()
+        return spaceForMapSize + spaceForChildren
+    }
+
+    override fun write(value: Map<kotlin.String, ProxyConfig>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        // The parens on `(k, v)` here ensure we're calling the right method,
+        // which is important for compatibility with older android devices.
+        // Ref https://blog.danlew.net/2017/03/16/kotlin-puzzler-whose-line-is-it-anyways/
+        value.forEach { (k, v) ->
+            FfiConverterString.write(k, buf)
+            FfiConverterTypeProxyConfig.write(v, buf)
+        }
+    }
+}
+
+
+
+public object FfiConverterMapStringTypeRateLimit: FfiConverterRustBuffer<Map<kotlin.String, RateLimit>> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, RateLimit> {
+        val len = buf.getInt()
+        return buildMap<kotlin.String, RateLimit>(len) {
+            repeat(len) {
+                val k = FfiConverterString.read(buf)
+                val v = FfiConverterTypeRateLimit.read(buf)
+                this[k] = v
+            }
+        }
+    }
+
+    override fun allocationSize(value: Map<kotlin.String, RateLimit>): ULong {
+        val spaceForMapSize = 4UL
+        val spaceForChildren = value.map { (k, v) ->
+            FfiConverterString.allocationSize(k) +
+            FfiConverterTypeRateLimit.allocationSize(v)
+        }.sum()
+        return spaceForMapSize + spaceForChildren
+    }
+
+    override fun write(value: Map<kotlin.String, RateLimit>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        // The parens on `(k, v)` here ensure we're calling the right method,
+        // which is important for compatibility with older android devices.
+        // Ref https://blog.danlew.net/2017/03/16/kotlin-puzzler-whose-line-is-it-anyways/
+        value.forEach { (k, v) ->
+            FfiConverterString.write(k, buf)
+            FfiConverterTypeRateLimit.write(v, buf)
+        }
+    }
+}
+
+
+
 
 
 
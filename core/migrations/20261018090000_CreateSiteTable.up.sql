@@ -0,0 +1,6 @@
+-- Add up migration script here
+CREATE TABLE site(
+  id TEXT NOT NULL PRIMARY KEY,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  position INTEGER NOT NULL
+);
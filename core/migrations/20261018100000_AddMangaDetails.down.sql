@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP INDEX idx_manga_alt_title_manga;
+DROP TABLE manga_alt_title;
+DROP INDEX idx_manga_genre_manga;
+DROP TABLE manga_genre;
+DROP INDEX idx_manga_person_manga;
+DROP TABLE manga_person;
+ALTER TABLE manga DROP COLUMN status;
+ALTER TABLE manga DROP COLUMN description;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+ALTER TABLE manga ADD COLUMN description TEXT;
+ALTER TABLE manga ADD COLUMN status TEXT NOT NULL DEFAULT 'unknown';
+
+CREATE TABLE manga_person(
+  manga INTEGER NOT NULL, -- manga.id
+  name TEXT NOT NULL,
+  role TEXT NOT NULL, -- 'author' or 'artist'
+  position INTEGER NOT NULL
+);
+
+CREATE INDEX idx_manga_person_manga ON manga_person (manga);
+
+CREATE TABLE manga_genre(
+  manga INTEGER NOT NULL, -- manga.id
+  name TEXT NOT NULL,
+  position INTEGER NOT NULL
+);
+
+CREATE INDEX idx_manga_genre_manga ON manga_genre (manga);
+
+CREATE TABLE manga_alt_title(
+  manga INTEGER NOT NULL, -- manga.id
+  title TEXT NOT NULL,
+  position INTEGER NOT NULL
+);
+
+CREATE INDEX idx_manga_alt_title_manga ON manga_alt_title (manga);
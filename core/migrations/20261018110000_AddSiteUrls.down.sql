@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE site DROP COLUMN cdn_url;
+ALTER TABLE site DROP COLUMN base_url;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE site ADD COLUMN base_url TEXT;
+ALTER TABLE site ADD COLUMN cdn_url TEXT;
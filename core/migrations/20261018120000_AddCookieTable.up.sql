@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE TABLE cookie(
+  domain TEXT NOT NULL,
+  path TEXT NOT NULL,
+  name TEXT NOT NULL,
+  data TEXT NOT NULL, -- the cookie as JSON
+  PRIMARY KEY (domain, path, name)
+);
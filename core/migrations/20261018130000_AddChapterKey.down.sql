@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX idx_chapter_manga_key;
+ALTER TABLE chapter DROP COLUMN chapter_key;
@@ -0,0 +1,13 @@
+-- Add up migration script here
+ALTER TABLE chapter ADD COLUMN chapter_key TEXT; -- MangaSite::chapter_key
+
+-- the url path without the query, or the `id` parameter of jmanga's
+-- chapter urls
+UPDATE chapter SET chapter_key = substr(url, instr(substr(url, 9), '/') + 8);
+UPDATE chapter SET chapter_key = substr(chapter_key, 1, instr(chapter_key, '?') - 1)
+  WHERE instr(chapter_key, '?') > 0;
+UPDATE chapter SET chapter_key = rtrim(chapter_key, '/');
+UPDATE chapter SET chapter_key = substr(url, instr(url, '&id=') + 4)
+  WHERE url LIKE '%/json/chapter?%' AND instr(url, '&id=') > 0;
+
+CREATE INDEX idx_chapter_manga_key ON chapter (manga, chapter_key);
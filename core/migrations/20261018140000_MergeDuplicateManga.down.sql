@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- merged manga can't be told apart again
@@ -0,0 +1,39 @@
+-- Add up migration script here
+-- manga whose urls only differ in scheme, case of the host, fragment or
+-- trailing slash are merged into the one opened last
+CREATE TEMP TABLE manga_url_key AS
+WITH rest AS (
+  SELECT id, updated_at, substr(url, instr(url, '://') + 3) AS r FROM manga
+), no_fragment AS (
+  SELECT id, updated_at,
+    CASE WHEN instr(r, '#') > 0 THEN substr(r, 1, instr(r, '#') - 1) ELSE r END AS r
+  FROM rest
+)
+SELECT id, updated_at,
+  lower(substr(r, 1, instr(r || '/', '/') - 1)) || rtrim(substr(r, instr(r || '/', '/')), '/') AS key
+FROM no_fragment;
+
+CREATE TEMP TABLE manga_merge AS
+SELECT d.id AS dup, (
+  SELECT k.id FROM manga_url_key k WHERE k.key = d.key ORDER BY k.updated_at DESC, k.id LIMIT 1
+) AS keep
+FROM manga_url_key d;
+DELETE FROM manga_merge WHERE dup = keep;
+
+-- a chapter read in either copy stays read
+UPDATE chapter SET is_read = 1
+WHERE is_read = 0 AND EXISTS (
+  SELECT 1 FROM chapter c JOIN manga_merge m ON c.manga = m.dup
+  WHERE m.keep = chapter.manga AND c.title = chapter.title AND c.is_read = 1
+);
+UPDATE OR IGNORE chapter SET manga = (SELECT keep FROM manga_merge WHERE dup = chapter.manga)
+WHERE manga IN (SELECT dup FROM manga_merge);
+
+DELETE FROM chapter WHERE manga IN (SELECT dup FROM manga_merge);
+DELETE FROM manga_person WHERE manga IN (SELECT dup FROM manga_merge);
+DELETE FROM manga_genre WHERE manga IN (SELECT dup FROM manga_merge);
+DELETE FROM manga_alt_title WHERE manga IN (SELECT dup FROM manga_merge);
+DELETE FROM manga WHERE id IN (SELECT dup FROM manga_merge);
+
+DROP TABLE manga_merge;
+DROP TABLE manga_url_key;
@@ -0,0 +1,18 @@
+-- Add up migration script here
+CREATE TABLE category(
+  id INTEGER NOT NULL PRIMARY KEY,
+  name TEXT NOT NULL UNIQUE,
+  position INTEGER NOT NULL,
+  is_default INTEGER NOT NULL DEFAULT 0 -- new manga are put in this one
+);
+
+CREATE TABLE manga_category(
+  manga INTEGER NOT NULL, -- manga.id
+  category INTEGER NOT NULL, -- category.id
+  PRIMARY KEY (manga, category)
+);
+CREATE INDEX idx_manga_category_category ON manga_category (category);
+
+INSERT INTO category (name, position, is_default) VALUES ('Default', 0, 1);
+INSERT INTO manga_category (manga, category)
+  SELECT manga.id, category.id FROM manga, category WHERE category.is_default = 1;
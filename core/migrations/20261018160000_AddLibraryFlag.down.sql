@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX idx_manga_in_library;
+ALTER TABLE manga DROP COLUMN in_library;
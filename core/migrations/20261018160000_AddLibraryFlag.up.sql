@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- manga only opened from search results stay out of the library; every manga
+-- stored so far was shown in it, so they are kept there
+ALTER TABLE manga ADD COLUMN in_library INTEGER NOT NULL DEFAULT 0;
+UPDATE manga SET in_library = 1;
+CREATE INDEX idx_manga_in_library ON manga (in_library, updated_at);
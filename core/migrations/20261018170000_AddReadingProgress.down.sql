@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE chapter DROP COLUMN read_at;
+ALTER TABLE chapter DROP COLUMN total_pages;
+ALTER TABLE chapter DROP COLUMN last_page;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE chapter ADD COLUMN last_page INTEGER; -- index of the last page reached
+ALTER TABLE chapter ADD COLUMN total_pages INTEGER;
+ALTER TABLE chapter ADD COLUMN read_at INTEGER; -- when a page was last reached
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX idx_chapter_manga_key;
+CREATE INDEX idx_chapter_manga_key ON chapter (manga, chapter_key);
+CREATE UNIQUE INDEX idx_chapter_manga_title ON chapter (manga, title);
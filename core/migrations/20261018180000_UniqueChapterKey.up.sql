@@ -0,0 +1,29 @@
+-- Add up migration script here
+-- jmanga chapters are keyed by the `id` query parameter, see
+-- JmangaOrg::chapter_key, wherever it is in the query
+UPDATE chapter SET chapter_key = (
+  SELECT substr(after, 1, instr(after, '&') - 1) FROM (
+    SELECT substr(q, instr(q, '&id=') + 4) AS after FROM (
+      SELECT '&' || replace(substr(chapter.url, instr(chapter.url, '?') + 1), '#', '&') || '&' AS q
+    )
+  )
+)
+WHERE url LIKE '%/json/chapter?%'
+  AND '&' || substr(url, instr(url, '?') + 1) LIKE '%&id=%';
+
+-- one row per key: the oldest, read if any of them was
+UPDATE chapter SET is_read = 1
+  WHERE is_read = 0 AND chapter_key IS NOT NULL AND EXISTS (
+    SELECT 1 FROM chapter c
+      WHERE c.manga = chapter.manga AND c.chapter_key = chapter.chapter_key AND c.is_read = 1
+  );
+DELETE FROM chapter
+  WHERE chapter_key IS NOT NULL AND EXISTS (
+    SELECT 1 FROM chapter c
+      WHERE c.manga = chapter.manga AND c.chapter_key = chapter.chapter_key AND c.id < chapter.id
+  );
+
+-- chapters are told apart by key; titles may repeat, like two "番外編"
+DROP INDEX idx_chapter_manga_title;
+DROP INDEX idx_chapter_manga_key;
+CREATE UNIQUE INDEX idx_chapter_manga_key ON chapter (manga, chapter_key);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE manga DROP COLUMN details_updated_at;
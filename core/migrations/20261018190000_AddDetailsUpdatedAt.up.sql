@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE manga ADD COLUMN details_updated_at INTEGER; -- when the details were last fetched
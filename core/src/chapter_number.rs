@@ -0,0 +1,246 @@
+//! Chapter numbers read from chapter titles.
+//!
+//! Titles come in many shapes: `第250.5話`, `Chapter 12`, `Vol.3 第12話`,
+//! `第十二話`, full-width `第１２話`, or no number at all like `番外編`. The
+//! chapter number is taken from the most explicit part of the title, and a
+//! volume is never mistaken for it. Chapters left without one are numbered
+//! after the chapter preceding them in the site's list.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Gap between a numbered chapter and the unnumbered ones following it.
+const FALLBACK_STEP: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ChapterKind {
+    #[default]
+    Regular,
+    Oneshot,
+    /// Extras, specials and side stories, which don't take a chapter number
+    /// of their own.
+    Extra,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChapterNumber {
+    pub volume: Option<f64>,
+    pub chapter: Option<f64>,
+    pub kind: ChapterKind,
+}
+
+lazy_static! {
+    static ref VOLUME: Regex = Regex::new(&format!(
+        r"(?i)(?:\bvol(?:ume)?\.?\s*{NUM}|第?\s*{NUM}\s*巻)"
+    ))
+    .unwrap();
+    static ref CHAPTER: Regex = Regex::new(&format!(
+        r"(?i)(?:\b(?:chapter|chap|ch|episode|ep)\.?\s*{NUM}|第\s*{NUM}\s*[話回]|{DIGITS}\s*[話回]|#\s*{NUM})"
+    ))
+    .unwrap();
+    static ref BARE: Regex = Regex::new(r"\d+(?:\.\d+)?").unwrap();
+    static ref ONESHOT: Regex = Regex::new(r"(?i)one[\s-]?shot|読み?切り?").unwrap();
+    static ref EXTRA: Regex =
+        Regex::new(r"(?i)\b(?:extra|special|omake|side\s*story)\b|番外|特別編|おまけ").unwrap();
+}
+
+/// A number in digits or kanji numerals.
+const NUM: &str = r"(\d+(?:\.\d+)?|[〇零一二三四五六七八九十百千]+)";
+/// A number in digits only, for counters where kanji numerals would also
+/// match words like `もう一回`.
+const DIGITS: &str = r"(\d+(?:\.\d+)?)";
+
+/// Reads the volume and chapter number of `title`.
+pub fn parse(title: &str) -> ChapterNumber {
+    let title = normalize(title);
+    let explicit = CHAPTER.captures(&title).and_then(|c| number(&c));
+    // a chapter numbered explicitly stays regular, like `Chapter 5 Special`
+    let kind = if explicit.is_none() && EXTRA.is_match(&title) {
+        ChapterKind::Extra
+    } else if ONESHOT.is_match(&title) {
+        ChapterKind::Oneshot
+    } else {
+        ChapterKind::Regular
+    };
+    let volume = VOLUME.captures(&title).and_then(|c| number(&c));
+
+    let chapter = match kind {
+        ChapterKind::Extra => None,
+        _ => explicit.or_else(|| {
+            // any other number, as long as it isn't the volume
+            let rest = VOLUME.replace_all(&title, " ");
+            BARE.find(&rest).and_then(|m| m.as_str().parse().ok())
+        }),
+    };
+    ChapterNumber {
+        volume,
+        chapter,
+        kind,
+    }
+}
+
+/// Chapter numbers of `titles`, listed in the site's order, for sorting the
+/// chapters. A title without a number is placed right after the chapter
+/// before it in reading order, or before every chapter if it comes first.
+pub fn assign_numbers<S: AsRef<str>>(titles: &[S]) -> Vec<f64> {
+    let parsed: Vec<Option<f64>> = titles.iter().map(|t| parse(t.as_ref()).chapter).collect();
+    let mut numbered = parsed.iter().flatten();
+    // sites list the latest chapter first unless the numbers say otherwise
+    let newest_first = match (numbered.next(), numbered.last()) {
+        (Some(first), Some(last)) => first >= last,
+        _ => true,
+    };
+
+    let mut order: Vec<usize> = (0..titles.len()).collect();
+    if newest_first {
+        order.reverse();
+    }
+    let mut numbers = vec![0.0; titles.len()];
+    let mut previous = 0.0;
+    for i in order {
+        numbers[i] = match parsed[i] {
+            Some(n) => n,
+            None => previous + FALLBACK_STEP,
+        };
+        previous = numbers[i];
+    }
+    numbers
+}
+
+/// Turns full-width characters into their ASCII form.
+fn normalize(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+fn number(captures: &regex::Captures) -> Option<f64> {
+    let m = captures.iter().skip(1).flatten().next()?.as_str();
+    m.parse().ok().or_else(|| kanji_number(m))
+}
+
+/// Value of kanji numerals, either positional like `一二〇` or with
+/// multipliers like `百二十`.
+fn kanji_number(s: &str) -> Option<f64> {
+    let digit = |c| match c {
+        '〇' | '零' => Some(0),
+        '一' => Some(1),
+        '二' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    };
+    let multiplier = |c| match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1000),
+        _ => None,
+    };
+
+    if !s.chars().any(|c| multiplier(c).is_some()) {
+        return s
+            .chars()
+            .try_fold(0u64, |n, c| Some(n * 10 + digit(c)?))
+            .map(|n| n as f64);
+    }
+    let (mut total, mut current) = (0u64, None);
+    for c in s.chars() {
+        if let Some(d) = digit(c) {
+            current = Some(d);
+        } else {
+            total += current.take().unwrap_or(1) * multiplier(c)?;
+        }
+    }
+    Some((total + current.unwrap_or(0)) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assign_numbers, parse, ChapterKind, ChapterNumber};
+
+    #[test]
+    fn test_parse_chapter_number() {
+        let corpus: &[(&str, Option<f64>, Option<f64>)] = &[
+            // rawkuro.net
+            ("第251話", None, Some(251.0)),
+            ("第250.5話", None, Some(250.5)),
+            ("第251話 - 異世界の", None, Some(251.0)),
+            // jmanga.org
+            ("第1125話", None, Some(1125.0)),
+            ("Chapter 1125", None, Some(1125.0)),
+            // mangatop.jp
+            ("第120話 - 転生したら", None, Some(120.0)),
+            ("【第２話】", None, Some(2.0)),
+            // spoilerplus.tv
+            ("第200話 - ワンパンマン", None, Some(200.0)),
+            ("ワンパンマン 200", None, Some(200.0)),
+            ("Vol.3 第12話", Some(3.0), Some(12.0)),
+            ("Vol. 3 Ch. 12.5", Some(3.0), Some(12.5)),
+            ("Volume 2 Chapter 7", Some(2.0), Some(7.0)),
+            ("第3巻 第12話", Some(3.0), Some(12.0)),
+            ("3巻 12", Some(3.0), Some(12.0)),
+            ("第５巻", Some(5.0), None),
+            ("第十二話", None, Some(12.0)),
+            ("第百二十話", None, Some(120.0)),
+            ("第二〇一話", None, Some(201.0)),
+            ("Episode 4", None, Some(4.0)),
+            ("#37 再会", None, Some(37.0)),
+            ("最終回", None, None),
+            ("12話", None, Some(12.0)),
+            // kanji numerals count a chapter only after 第
+            ("十回目の告白", None, None),
+            ("もう一回", None, None),
+            ("もう一回 第3話", None, Some(3.0)),
+            // extras with an explicit number keep it
+            ("Chapter 5 Special", None, Some(5.0)),
+            ("第5話 おまけ付き", None, Some(5.0)),
+        ];
+        for (title, volume, chapter) in corpus.iter() {
+            let n = parse(title);
+            assert_eq!((n.volume, n.chapter), (*volume, *chapter), "{}", title);
+            assert_eq!(n.kind, ChapterKind::Regular, "{}", title);
+        }
+
+        assert_eq!(
+            parse("番外編 2"),
+            ChapterNumber {
+                volume: None,
+                chapter: None,
+                kind: ChapterKind::Extra,
+            }
+        );
+        assert_eq!(parse("Extra").kind, ChapterKind::Extra);
+        assert_eq!(parse("Side Story 3").kind, ChapterKind::Extra);
+        assert_eq!(parse("Oneshot").kind, ChapterKind::Oneshot);
+        assert_eq!(parse("Oneshot").chapter, None);
+        assert_eq!(parse("読み切り 第1話").chapter, Some(1.0));
+        assert_eq!(parse("Chapter 3 (Extended)").kind, ChapterKind::Regular);
+    }
+
+    #[test]
+    fn test_assign_numbers() {
+        let newest_first = ["第3話", "番外編", "第2話", "第1話", "Prologue"];
+        assert_eq!(
+            assign_numbers(&newest_first),
+            vec![3.0, 2.001, 2.0, 1.0, 0.001]
+        );
+
+        let oldest_first = ["Prologue", "Chapter 1", "Extra", "Extra", "Chapter 2"];
+        let numbers = assign_numbers(&oldest_first);
+        assert_eq!(numbers[..2], [0.001, 1.0]);
+        assert!(numbers[1] < numbers[2] && numbers[2] < numbers[3] && numbers[3] < numbers[4]);
+
+        assert_eq!(assign_numbers(&["Oneshot"]), vec![0.001]);
+        assert!(assign_numbers::<&str>(&[]).is_empty());
+    }
+}
@@ -5,13 +5,36 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use anyhow::bail;
-use lazy_static::lazy_static;
-use regex::Regex;
+use anyhow::anyhow;
 use reqwest::Url;
-use sqlx::{migrate::Migrate, Connection, SqliteConnection, SqlitePool};
+use sqlx::{migrate::Migrate, Connection, QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 
-use crate::error::MangaError;
+use crate::{
+    error::MangaError,
+    sites::{
+        canonical::{canonicalize, variants},
+        registry::SourceInfo,
+        Link, MangaDetails, PublicationStatus,
+    },
+    chapter_number,
+    source_migration::ChapterMatch,
+};
+
+/// Tables with rows belonging to a manga, by their `manga` column.
+const MANGA_TABLES: [&str; 5] = [
+    "chapter",
+    "manga_person",
+    "manga_genre",
+    "manga_alt_title",
+    "manga_category",
+];
+
+/// Whether chapter `c` is `chapter` stored for another manga: same key, or
+/// same title when either has no key.
+const SAME_CHAPTER: &str = "CASE WHEN c.chapter_key IS NULL OR chapter.chapter_key IS NULL THEN c.title = chapter.title ELSE c.chapter_key = chapter.chapter_key END";
+
+/// Puts manga `?1` in the default category if it's in none.
+const ADD_TO_DEFAULT_CATEGORY: &str = "INSERT INTO manga_category (manga, category) SELECT ?1, id FROM category WHERE is_default = 1 AND NOT EXISTS (SELECT 1 FROM manga_category WHERE manga = ?1)";
 
 #[derive(Debug, Clone)]
 pub struct Db {
@@ -70,7 +93,104 @@ impl Db {
     pub async fn do_migration(&self) -> anyhow::Result<()> {
         let pool = self.pool.clone();
         let migrator = sqlx::migrate!();
-        Ok(migrator.run(&pool).await?)
+        migrator.run(&pool).await?;
+        // the rules of `canonical` can't all be written in SQL
+        self.canonicalize_urls().await
+    }
+
+    /// Rewrites stored urls to their canonical form. Manga whose urls turn
+    /// out to be spellings of the same page, see `canonical::variants`, are
+    /// merged into the one opened last.
+    async fn canonicalize_urls(&self) -> anyhow::Result<()> {
+        let manga: Vec<(i64, String)> =
+            sqlx::query_as("SELECT id, url FROM manga ORDER BY updated_at DESC, id")
+                .fetch_all(&self.pool)
+                .await?;
+        let mut kept: HashMap<String, i64> = HashMap::new();
+        let mut merges = vec![];
+        let mut renames = vec![];
+        for (id, url) in manga.into_iter() {
+            let canonical = canonicalize(&url);
+            let key = variants(&canonical).into_iter().min().unwrap_or_default();
+            match kept.get(&key) {
+                Some(keep) => merges.push((id, *keep)),
+                None => {
+                    kept.insert(key, id);
+                    if canonical != url {
+                        renames.push((id, canonical));
+                    }
+                }
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for (dup, keep) in merges.iter() {
+            // a chapter read in either copy stays read
+            let _ = sqlx::query(&format!(
+                "UPDATE chapter SET is_read = 1 WHERE manga = ?1 AND is_read = 0 AND EXISTS (SELECT 1 FROM chapter c WHERE c.manga = ?2 AND c.is_read = 1 AND {})",
+                SAME_CHAPTER
+            ))
+            .bind(keep)
+            .bind(dup)
+            .execute(&mut *tx)
+            .await?;
+            let _ = sqlx::query(&format!(
+                "UPDATE OR IGNORE chapter SET manga = ?1 WHERE manga = ?2 AND NOT EXISTS (SELECT 1 FROM chapter c WHERE c.manga = ?1 AND {})",
+                SAME_CHAPTER
+            ))
+            .bind(keep)
+            .bind(dup)
+            .execute(&mut *tx)
+            .await?;
+            let _ = sqlx::query(
+                "UPDATE manga SET in_library = MAX(in_library, (SELECT in_library FROM manga WHERE id = ?2)) WHERE id = ?1",
+            )
+            .bind(keep)
+            .bind(dup)
+            .execute(&mut *tx)
+            .await?;
+            let _ = sqlx::query(
+                "INSERT OR IGNORE INTO manga_category (manga, category) SELECT ?1, category FROM manga_category WHERE manga = ?2",
+            )
+            .bind(keep)
+            .bind(dup)
+            .execute(&mut *tx)
+            .await?;
+
+            for table in MANGA_TABLES {
+                let _ = sqlx::query(&format!("DELETE FROM {} WHERE manga = ?", table))
+                    .bind(dup)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            let _ = sqlx::query("DELETE FROM manga WHERE id = ?")
+                .bind(dup)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for (id, url) in renames.iter() {
+            let _ = sqlx::query("UPDATE manga SET url = ? WHERE id = ?")
+                .bind(url)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let chapters: Vec<(i64, String)> = sqlx::query_as("SELECT id, url FROM chapter")
+            .fetch_all(&mut *tx)
+            .await?;
+        for (id, url) in chapters.iter() {
+            let canonical = canonicalize(url);
+            if canonical != *url {
+                let _ = sqlx::query("UPDATE chapter SET url = ? WHERE id = ?")
+                    .bind(&canonical)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
     }
 
     pub fn reset(&self) -> anyhow::Result<()> {
@@ -80,11 +200,172 @@ impl Db {
         Ok(())
     }
 
-    pub async fn list_manga(&self) -> anyhow::Result<Vec<MangaData>> {
+    /// Every manga in the library, or only those in `category`.
+    pub async fn list_manga(&self, category: Option<i64>) -> anyhow::Result<Vec<MangaData>> {
+        let list: Vec<MangaDb> = match category {
+            Some(category) => sqlx::query_as(
+                "SELECT * FROM manga WHERE in_library = 1 AND id IN (SELECT manga FROM manga_category WHERE category = ?) ORDER BY updated_at DESC",
+            )
+            .bind(category)
+            .fetch_all(&self.pool)
+            .await?,
+            None => sqlx::query_as(
+                "SELECT * FROM manga WHERE in_library = 1 ORDER BY updated_at DESC",
+            )
+            .fetch_all(&self.pool)
+            .await?,
+        };
+        let mut list: Vec<MangaData> = list.into_iter().map(MangaData::from).collect();
+        self.fill_details(&mut list).await?;
+        Ok(list)
+    }
+
+    /// Every opened manga, in the library or not, last opened first.
+    pub async fn list_history(&self) -> anyhow::Result<Vec<MangaData>> {
         let list: Vec<MangaDb> = sqlx::query_as("SELECT * FROM manga ORDER BY updated_at DESC")
             .fetch_all(&self.pool)
             .await?;
-        Ok(list.into_iter().map(MangaData::from).collect())
+        let mut list: Vec<MangaData> = list.into_iter().map(MangaData::from).collect();
+        self.fill_details(&mut list).await?;
+        Ok(list)
+    }
+
+    /// Loads the list parts of `MangaDetails`, which live in their own tables.
+    async fn fill_details(&self, list: &mut [MangaData]) -> anyhow::Result<()> {
+        if list.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<i64> = list.iter().map(|m| m.id).collect();
+        let in_ids = |sql: &str| {
+            let mut q: QueryBuilder<Sqlite> = QueryBuilder::new(sql);
+            q.push(" WHERE manga IN (");
+            let mut sep = q.separated(", ");
+            for id in ids.iter() {
+                sep.push_bind(*id);
+            }
+            q.push(") ORDER BY position");
+            q
+        };
+
+        let people: Vec<MangaPersonDb> = in_ids("SELECT manga, name, role FROM manga_person")
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await?;
+        let genres: Vec<MangaNameDb> = in_ids("SELECT manga, name FROM manga_genre")
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await?;
+        let alt_titles: Vec<MangaNameDb> =
+            in_ids("SELECT manga, title AS name FROM manga_alt_title")
+                .build_query_as()
+                .fetch_all(&self.pool)
+                .await?;
+
+        for manga in list.iter_mut() {
+            for p in people.iter().filter(|p| p.manga == manga.id) {
+                match p.role.as_str() {
+                    "artist" => manga.details.artists.push(p.name.clone()),
+                    _ => manga.details.authors.push(p.name.clone()),
+                }
+            }
+            manga.details.genres = genres
+                .iter()
+                .filter(|g| g.manga == manga.id)
+                .map(|g| g.name.clone())
+                .collect();
+            manga.details.alt_titles = alt_titles
+                .iter()
+                .filter(|t| t.manga == manga.id)
+                .map(|t| t.name.clone())
+                .collect();
+        }
+        Ok(())
+    }
+
+    async fn with_details(&self, manga: Option<MangaDb>) -> anyhow::Result<Option<MangaData>> {
+        match manga {
+            Some(m) => {
+                let mut list = [MangaData::from(m)];
+                self.fill_details(&mut list).await?;
+                let [manga] = list;
+                Ok(Some(manga))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// When the details of manga `id` were last stored, `None` if never.
+    pub async fn details_updated_at(&self, id: i64) -> anyhow::Result<Option<i64>> {
+        let at: Option<(Option<i64>,)> =
+            sqlx::query_as("SELECT details_updated_at FROM manga WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(at.and_then(|(at,)| at))
+    }
+
+    pub async fn update_manga_details(
+        &self,
+        id: i64,
+        details: &MangaDetails,
+    ) -> anyhow::Result<()> {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut tx = self.pool.begin().await?;
+
+        let _ = sqlx::query(
+            "UPDATE manga SET description = ?, status = ?, details_updated_at = ? WHERE id = ?",
+        )
+        .bind(&details.description)
+        .bind(details.status.as_str())
+        .bind(ts as i64)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        for table in ["manga_person", "manga_genre", "manga_alt_title"] {
+            let _ = sqlx::query(&format!("DELETE FROM {} WHERE manga = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let people = details
+            .authors
+            .iter()
+            .map(|n| (n, "author"))
+            .chain(details.artists.iter().map(|n| (n, "artist")));
+        for (i, (name, role)) in people.enumerate() {
+            let _ = sqlx::query(
+                "INSERT INTO manga_person (manga, name, role, position) VALUES (?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(name)
+            .bind(role)
+            .bind(i as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for (i, name) in details.genres.iter().enumerate() {
+            let _ = sqlx::query("INSERT INTO manga_genre (manga, name, position) VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(name)
+                .bind(i as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for (i, title) in details.alt_titles.iter().enumerate() {
+            let _ = sqlx::query(
+                "INSERT INTO manga_alt_title (manga, title, position) VALUES (?, ?, ?)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(i as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
     }
 
     pub async fn create_manga(
@@ -93,6 +374,7 @@ impl Db {
         url: String,
         image: Option<String>,
     ) -> anyhow::Result<MangaData> {
+        let url = canonicalize(&url);
         let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
         let _ = sqlx::query(
@@ -119,21 +401,23 @@ impl Db {
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
-        Ok(match manga {
-            Some(m) => Some(m.into()),
-            None => None,
-        })
+        self.with_details(manga).await
     }
 
+    /// The manga at `url`, however the url is spelled, see
+    /// `canonical::variants`.
     pub async fn find_manga_by_url(&self, url: &str) -> anyhow::Result<Option<MangaData>> {
-        let manga: Option<MangaDb> = sqlx::query_as("SELECT * FROM manga WHERE url = ?")
-            .bind(url)
-            .fetch_optional(&self.pool)
-            .await?;
-        Ok(match manga {
-            Some(m) => Some(m.into()),
-            None => None,
-        })
+        let variants = variants(url);
+        let mut q: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT * FROM manga WHERE url IN (");
+        let mut list = q.separated(", ");
+        for v in variants.iter() {
+            list.push_bind(v);
+        }
+        q.push(")");
+        let mut found: Vec<MangaDb> = q.build_query_as().fetch_all(&self.pool).await?;
+        // the closest spelling, in case duplicates haven't been merged yet
+        found.sort_by_key(|m| variants.iter().position(|v| *v == m.url));
+        self.with_details(found.into_iter().next()).await
     }
 
     pub async fn update_manga_time(&self, id: i64, ts: i64) -> anyhow::Result<()> {
@@ -145,16 +429,224 @@ impl Db {
         Ok(())
     }
 
-    pub async fn delete_manga(&self, id: i64) -> anyhow::Result<()> {
-        let _ = sqlx::query("DELETE FROM chapter WHERE manga = ?")
+    /// Adds manga `id` to the library, in the default category unless it's
+    /// in some category already, or takes it out of the library and all its
+    /// categories. False if there is no such manga.
+    pub async fn set_in_library(&self, id: i64, in_library: bool) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("UPDATE manga SET in_library = ? WHERE id = ?")
+            .bind(in_library)
             .bind(id)
-            .execute(&self.pool).await?;
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        let categories = if in_library {
+            ADD_TO_DEFAULT_CATEGORY
+        } else {
+            "DELETE FROM manga_category WHERE manga = ?1"
+        };
+        let _ = sqlx::query(categories).bind(id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Deletes the manga outside the library last opened before `before`,
+    /// returning how many were deleted.
+    pub async fn prune_history(&self, before: i64) -> anyhow::Result<u64> {
+        let ids: Vec<(i64,)> =
+            sqlx::query_as("SELECT id FROM manga WHERE in_library = 0 AND updated_at < ?")
+                .bind(before)
+                .fetch_all(&self.pool)
+                .await?;
+        for (id,) in ids.iter() {
+            self.delete_manga(*id).await?;
+        }
+        Ok(ids.len() as u64)
+    }
+
+    pub async fn delete_manga(&self, id: i64) -> anyhow::Result<()> {
+        for table in MANGA_TABLES {
+            let _ = sqlx::query(&format!("DELETE FROM {} WHERE manga = ?", table))
+                .bind(id)
+                .execute(&self.pool).await?;
+        }
         let _ = sqlx::query("DELETE FROM manga WHERE id = ?")
             .bind(id)
             .execute(&self.pool).await?;
         Ok(())
     }
 
+    pub async fn list_categories(&self) -> anyhow::Result<Vec<CategoryDb>> {
+        let categories: Vec<CategoryDb> = sqlx::query_as(&format!(
+            "{} ORDER BY c.position, c.id",
+            SELECT_CATEGORY
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(categories)
+    }
+
+    pub async fn find_category(&self, id: i64) -> anyhow::Result<Option<CategoryDb>> {
+        let category: Option<CategoryDb> =
+            sqlx::query_as(&format!("{} WHERE c.id = ?", SELECT_CATEGORY))
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(category)
+    }
+
+    /// The categories `manga_id` is in.
+    pub async fn manga_categories(&self, manga_id: i64) -> anyhow::Result<Vec<CategoryDb>> {
+        let categories: Vec<CategoryDb> = sqlx::query_as(&format!(
+            "{} WHERE c.id IN (SELECT category FROM manga_category WHERE manga = ?) ORDER BY c.position, c.id",
+            SELECT_CATEGORY
+        ))
+        .bind(manga_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(categories)
+    }
+
+    /// Adds a category after the existing ones.
+    pub async fn create_category(&self, name: &str) -> anyhow::Result<CategoryDb> {
+        let name = self.category_name(None, name).await?;
+        let result = sqlx::query(
+            "INSERT INTO category (name, position) SELECT ?, COALESCE(MAX(position) + 1, 0) FROM category",
+        )
+        .bind(&name)
+        .execute(&self.pool)
+        .await?;
+        self.find_category(result.last_insert_rowid())
+            .await?
+            .ok_or_else(|| anyhow!("category {} was not created", name))
+    }
+
+    pub async fn rename_category(&self, id: i64, name: &str) -> anyhow::Result<()> {
+        let name = self.category_name(Some(id), name).await?;
+        let result = sqlx::query("UPDATE category SET name = ? WHERE id = ?")
+            .bind(&name)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(MangaError::CategoryNotFound { category_id: id }.into());
+        }
+        Ok(())
+    }
+
+    /// `name` trimmed, as long as it's not empty or taken by another category
+    /// than `id`.
+    async fn category_name(&self, id: Option<i64>, name: &str) -> anyhow::Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(MangaError::EmptyCategoryName.into());
+        }
+        let taken: Option<(i64,)> = sqlx::query_as("SELECT id FROM category WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+        match taken {
+            Some((other,)) if Some(other) != id => Err(MangaError::CategoryExists {
+                name: name.to_string(),
+            }
+            .into()),
+            _ => Ok(name.to_string()),
+        }
+    }
+
+    /// Puts the categories in the order of `ids`; categories left out go
+    /// last, keeping their order.
+    pub async fn reorder_categories(&self, ids: &[i64]) -> anyhow::Result<()> {
+        let mut categories = self.list_categories().await?;
+        categories.sort_by_key(|c| ids.iter().position(|id| *id == c.id).unwrap_or(usize::MAX));
+
+        let mut tx = self.pool.begin().await?;
+        for (i, category) in categories.iter().enumerate() {
+            let _ = sqlx::query("UPDATE category SET position = ? WHERE id = ?")
+                .bind(i as i64)
+                .bind(category.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Deletes a category; its manga stay in the library. The default
+    /// category can't be deleted.
+    pub async fn delete_category(&self, id: i64) -> anyhow::Result<()> {
+        match self.find_category(id).await? {
+            Some(category) if category.is_default => {
+                return Err(MangaError::DefaultCategory {
+                    name: category.name,
+                }
+                .into())
+            }
+            Some(_) => {}
+            None => return Err(MangaError::CategoryNotFound { category_id: id }.into()),
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let _ = sqlx::query("DELETE FROM manga_category WHERE category = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let _ = sqlx::query("DELETE FROM category WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Makes `id` the category manga added to the library are put in.
+    pub async fn set_default_category(&self, id: i64) -> anyhow::Result<()> {
+        if self.find_category(id).await?.is_none() {
+            return Err(MangaError::CategoryNotFound { category_id: id }.into());
+        }
+        let _ = sqlx::query("UPDATE category SET is_default = (id = ?)")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the categories of `manga_id` with `categories` and adds it to
+    /// the library. Unknown categories are skipped, and the default category
+    /// is used when none is left.
+    pub async fn set_manga_categories(
+        &self,
+        manga_id: i64,
+        categories: &[i64],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let _ = sqlx::query("DELETE FROM manga_category WHERE manga = ?")
+            .bind(manga_id)
+            .execute(&mut *tx)
+            .await?;
+        for category in categories.iter() {
+            let _ = sqlx::query(
+                "INSERT OR IGNORE INTO manga_category (manga, category) SELECT ?, id FROM category WHERE id = ?",
+            )
+            .bind(manga_id)
+            .bind(category)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let _ = sqlx::query(ADD_TO_DEFAULT_CATEGORY)
+            .bind(manga_id)
+            .execute(&mut *tx)
+            .await?;
+        let _ = sqlx::query("UPDATE manga SET in_library = 1 WHERE id = ?")
+            .bind(manga_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn find_chapter(&self, chapter_id: i64) -> anyhow::Result<Option<ChapterDb>> {
         let chapter: Option<ChapterDb> = sqlx::query_as("SELECT * FROM chapter WHERE id = ?")
             .bind(chapter_id)
@@ -163,88 +655,301 @@ impl Db {
         Ok(chapter)
     }
 
-    pub async fn find_chapter_by_title(
+    /// The chapter at `url`, however the url is spelled.
+    pub async fn find_chapter_by_url(&self, url: &str) -> anyhow::Result<Option<ChapterDb>> {
+        let variants = variants(url);
+        let mut q: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT * FROM chapter WHERE url IN (");
+        let mut list = q.separated(", ");
+        for v in variants.iter() {
+            list.push_bind(v);
+        }
+        q.push(")");
+        let mut found: Vec<ChapterDb> = q.build_query_as().fetch_all(&self.pool).await?;
+        found.sort_by_key(|c| variants.iter().position(|v| *v == c.url));
+        Ok(found.into_iter().next())
+    }
+
+    pub async fn find_chapter_by_key(
         &self,
         manga_id: i64,
-        title: String,
+        chapter_key: &str,
     ) -> anyhow::Result<Option<ChapterDb>> {
         let chapter: Option<ChapterDb> =
-            sqlx::query_as("SELECT * FROM chapter WHERE manga = ? AND title = ?")
+            sqlx::query_as("SELECT * FROM chapter WHERE manga = ? AND chapter_key = ?")
                 .bind(manga_id)
-                .bind(&title)
+                .bind(chapter_key)
                 .fetch_optional(&self.pool)
                 .await?;
         Ok(chapter)
     }
 
+    /// A chapter stored without a key, such as after a source migration,
+    /// titled `title`. Chapters with a key are only found by it.
+    pub async fn find_chapter_by_title(
+        &self,
+        manga_id: i64,
+        title: String,
+    ) -> anyhow::Result<Option<ChapterDb>> {
+        let chapter: Option<ChapterDb> = sqlx::query_as(
+            "SELECT * FROM chapter WHERE manga = ? AND title = ? AND chapter_key IS NULL",
+        )
+        .bind(manga_id)
+        .bind(&title)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(chapter)
+    }
+
     pub async fn create_chapter(
         &self,
         manga_id: i64,
         title: String,
         url: String,
+        title_number: f64,
+        chapter_key: String,
     ) -> anyhow::Result<ChapterDb> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"([\d.]+)").unwrap();
-        }
-
-        let title_num = if let Some(m) = RE.captures(&title) {
-            m.get(1).unwrap().as_str().parse::<f64>()?
-        } else {
-            bail!("failed to extract number from chapter title");
-        };
-
         let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let _ = sqlx::query(
-            "INSERT INTO chapter (manga, title, title_number, url, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+        let result = sqlx::query(
+            "INSERT INTO chapter (manga, title, title_number, url, chapter_key, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(manga_id)
         .bind(&title)
-        .bind(title_num)
+        .bind(title_number)
         .bind(&url)
+        .bind(&chapter_key)
         .bind(ts as i64)
         .bind(ts as i64)
         .execute(&self.pool)
         .await?;
 
-        let chapter: ChapterDb =
-            sqlx::query_as("SELECT * FROM chapter WHERE manga = ? AND title = ?")
-                .bind(manga_id)
-                .bind(&title)
-                .fetch_one(&self.pool)
-                .await?;
+        let chapter: ChapterDb = sqlx::query_as("SELECT * FROM chapter WHERE id = ?")
+            .bind(result.last_insert_rowid())
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(chapter)
     }
 
+    /// Updates chapter `id` with what the site lists now, its title included
+    /// as the site may have renamed it.
     pub async fn update_chapter(
         &self,
-        manga_id: i64,
+        id: i64,
         title: String,
         url: String,
+        title_number: f64,
+        chapter_key: String,
     ) -> anyhow::Result<()> {
         let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let _ =
-            sqlx::query("UPDATE chapter SET url = ?, updated_at = ? WHERE manga = ? AND title = ?")
-                .bind(&url)
-                .bind(ts as i64)
-                .bind(manga_id)
-                .bind(&title)
-                .execute(&self.pool)
-                .await?;
+        let _ = sqlx::query(
+            "UPDATE chapter SET title = ?, url = ?, title_number = ?, chapter_key = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&title)
+        .bind(&url)
+        .bind(title_number)
+        .bind(&chapter_key)
+        .bind(ts as i64)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
+    /// Points manga `id` at `link` and replaces its chapters with the new
+    /// ones in `chapters`, taking read state and timestamps from the matched
+    /// stored chapter. Chapters are keyed with `key`, and one whose key an
+    /// earlier chapter has is skipped.
+    pub async fn replace_manga_source(
+        &self,
+        id: i64,
+        link: &Link,
+        chapters: &[ChapterMatch],
+        key: impl Fn(&str) -> String,
+    ) -> anyhow::Result<()> {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut tx = self.pool.begin().await?;
+
+        let _ = sqlx::query(
+            "UPDATE manga SET title = ?, url = ?, image = COALESCE(?, image) WHERE id = ?",
+        )
+        .bind(&link.text)
+        .bind(canonicalize(&link.url))
+        .bind(&link.image)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query("DELETE FROM chapter WHERE manga = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let new: Vec<_> = chapters
+            .iter()
+            .filter_map(|m| m.new.as_ref().map(|new| (new, m.old.as_ref())))
+            .collect();
+        let numbers = chapter_number::assign_numbers(
+            &new.iter().map(|(l, _)| l.text.as_str()).collect::<Vec<_>>(),
+        );
+        for ((new, old), number) in new.into_iter().zip(numbers) {
+            let (is_read, created_at, updated_at) = match old {
+                Some(old) => (old.is_read, old.created_at, old.updated_at),
+                None => (0, ts, ts),
+            };
+            let _ = sqlx::query(
+                "INSERT INTO chapter (manga, title, title_number, url, chapter_key, is_read, created_at, updated_at, last_page, total_pages, read_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT (manga, chapter_key) DO NOTHING",
+            )
+            .bind(id)
+            .bind(&new.text)
+            .bind(number)
+            .bind(&new.url)
+            .bind(key(&new.url))
+            .bind(is_read)
+            .bind(created_at)
+            .bind(updated_at)
+            .bind(old.and_then(|c| c.last_page))
+            .bind(old.and_then(|c| c.total_pages))
+            .bind(old.and_then(|c| c.read_at))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Marking a chapter unread also forgets the page it was read up to.
     pub async fn mark_chapter_read(&self, chapter_id: i64, is_read: bool) -> anyhow::Result<()> {
         let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let _ = sqlx::query("UPDATE chapter SET is_read = ?, updated_at = ? WHERE id = ?")
-            .bind(if is_read { 1 } else { 0 })
-            .bind(ts as i64)
-            .bind(chapter_id)
+        let result = sqlx::query(
+            "UPDATE chapter SET is_read = ?1, last_page = CASE WHEN ?1 THEN last_page END, updated_at = ?2 WHERE id = ?3",
+        )
+        .bind(is_read)
+        .bind(ts as i64)
+        .bind(chapter_id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(MangaError::ChapterNotFound { chapter_id }.into());
+        }
+        Ok(())
+    }
+
+    /// Records that page `page` of `total` was reached in chapter
+    /// `chapter_id`, marking the chapter read on its last page. `None` if
+    /// there is no such chapter.
+    pub async fn save_progress(
+        &self,
+        chapter_id: i64,
+        page: i64,
+        total: i64,
+    ) -> anyhow::Result<Option<ChapterDb>> {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let _ = sqlx::query(
+            "UPDATE chapter SET last_page = ?1, total_pages = ?2, read_at = ?3, is_read = CASE WHEN ?2 > 0 AND ?1 + 1 >= ?2 THEN 1 ELSE is_read END WHERE id = ?4",
+        )
+        .bind(page)
+        .bind(total)
+        .bind(ts as i64)
+        .bind(chapter_id)
+        .execute(&self.pool)
+        .await?;
+        self.find_chapter(chapter_id).await
+    }
+
+    pub async fn list_site_settings(&self) -> anyhow::Result<Vec<SiteSettingDb>> {
+        let settings: Vec<SiteSettingDb> = sqlx::query_as("SELECT * FROM site ORDER BY position")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(settings)
+    }
+
+    pub async fn save_site_settings(&self, sources: &[SourceInfo]) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for source in sources.iter() {
+            let _ = sqlx::query(
+                "INSERT INTO site (id, enabled, position) VALUES (?, ?, ?) ON CONFLICT (id) DO UPDATE SET enabled = excluded.enabled, position = excluded.position",
+            )
+            .bind(&source.id)
+            .bind(if source.enabled { 1 } else { 0 })
+            .bind(source.position)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn list_cookies(&self) -> anyhow::Result<Vec<CookieDb>> {
+        let cookies: Vec<CookieDb> = sqlx::query_as("SELECT * FROM cookie")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(cookies)
+    }
+
+    /// Replaces every stored cookie with `cookies`.
+    pub async fn save_cookies(&self, cookies: &[CookieDb]) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let _ = sqlx::query("DELETE FROM cookie").execute(&mut *tx).await?;
+        for cookie in cookies.iter() {
+            let _ = sqlx::query(
+                "INSERT OR REPLACE INTO cookie (domain, path, name, data) VALUES (?, ?, ?, ?)",
+            )
+            .bind(&cookie.domain)
+            .bind(&cookie.path)
+            .bind(&cookie.name)
+            .bind(&cookie.data)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Saves url overrides of a site, `None` meaning the site's own url.
+    pub async fn update_site_urls(
+        &self,
+        id: &str,
+        base_url: Option<&str>,
+        cdn_url: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let _ = sqlx::query("UPDATE site SET base_url = ?, cdn_url = ? WHERE id = ?")
+            .bind(base_url)
+            .bind(cdn_url)
+            .bind(id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    /// Points stored manga and chapters at `new_base` instead of `old_base`.
+    /// A manga whose new url is already stored keeps its old one.
+    pub async fn rewrite_base_url(&self, old_base: &str, new_base: &str) -> anyhow::Result<u64> {
+        let old = format!("{}/", old_base.trim_end_matches('/'));
+        let new = format!("{}/", new_base.trim_end_matches('/'));
+        let mut tx = self.pool.begin().await?;
+
+        let mut rows = 0;
+        for sql in [
+            "UPDATE OR IGNORE manga SET url = ? || substr(url, length(?) + 1) WHERE substr(url, 1, length(?)) = ?",
+            "UPDATE manga SET image = ? || substr(image, length(?) + 1) WHERE substr(image, 1, length(?)) = ?",
+            "UPDATE chapter SET url = ? || substr(url, length(?) + 1) WHERE substr(url, 1, length(?)) = ?",
+        ] {
+            rows += sqlx::query(sql)
+                .bind(&new)
+                .bind(&old)
+                .bind(&old)
+                .bind(&old)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(rows)
+    }
+
     pub async fn get_chapters(&self, manga_id: i64) -> anyhow::Result<Vec<ChapterDb>> {
         let chapters: Vec<ChapterDb> =
             sqlx::query_as("SELECT * FROM chapter WHERE manga = ? ORDER BY title_number DESC")
@@ -263,8 +968,12 @@ pub struct MangaData {
     pub url: String,
     pub image: Option<String>,
     pub created_at: i64,
+    /// When the manga was last opened.
     pub updated_at: i64,
     pub domain: String,
+    pub details: MangaDetails,
+    /// Followed by the user, rather than only opened, see `Manga::list_history`.
+    pub in_library: bool,
 }
 
 impl From<MangaDb> for MangaData {
@@ -280,6 +989,12 @@ impl From<MangaDb> for MangaData {
             created_at: value.created_at,
             updated_at: value.updated_at,
             domain,
+            details: MangaDetails {
+                status: PublicationStatus::from_name(&value.status),
+                description: value.description,
+                ..Default::default()
+            },
+            in_library: value.in_library,
         }
     }
 }
@@ -292,9 +1007,25 @@ pub struct MangaDb {
     pub image: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    pub description: Option<String>,
+    pub status: String,
+    pub in_library: bool,
 }
 
 #[derive(sqlx::FromRow)]
+struct MangaPersonDb {
+    manga: i64,
+    name: String,
+    role: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct MangaNameDb {
+    manga: i64,
+    name: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
 pub struct ChapterDb {
     pub id: i64,
     pub manga: i64,
@@ -304,4 +1035,437 @@ pub struct ChapterDb {
     pub is_read: i64,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Identifies the chapter on its site whatever its title, see
+    /// `MangaSite::chapter_key`. `None` until the chapters are next fetched
+    /// after a source migration.
+    pub chapter_key: Option<String>,
+    /// Index of the last page reached, `None` if the chapter wasn't opened
+    /// since it was last marked unread. Set on a chapter not yet read, the
+    /// chapter is partially read.
+    pub last_page: Option<i64>,
+    /// Page count of the chapter when it was last read.
+    pub total_pages: Option<i64>,
+    /// When a page of the chapter was last reached.
+    pub read_at: Option<i64>,
+}
+
+const SELECT_CATEGORY: &str = "SELECT c.*, (SELECT COUNT(*) FROM manga_category mc WHERE mc.category = c.id) AS manga_count FROM category c";
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct CategoryDb {
+    pub id: i64,
+    pub name: String,
+    pub position: i64,
+    /// Manga added to the library are put in the default category.
+    pub is_default: bool,
+    pub manga_count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct SiteSettingDb {
+    pub id: String,
+    pub enabled: i64,
+    pub position: i64,
+    pub base_url: Option<String>,
+    pub cdn_url: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct CookieDb {
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+    pub data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CategoryDb, Db, MangaData};
+    use crate::{error::MangaError, sites::Link, source_migration::match_chapters};
+
+    /// A migrated database of its own, gone once the last connection closes.
+    async fn db() -> Db {
+        let db = Db::new("sqlite::memory:".to_string()).unwrap();
+        db.do_migration().await.unwrap();
+        db
+    }
+
+    async fn manga(db: &Db, url: &str) -> i64 {
+        db.create_manga("Foo".to_string(), url.to_string(), None)
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn ids(list: &[MangaData]) -> Vec<i64> {
+        list.iter().map(|m| m.id).collect()
+    }
+
+    fn names(categories: &[CategoryDb]) -> Vec<&str> {
+        categories.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    fn error<T: std::fmt::Debug>(result: anyhow::Result<T>) -> MangaError {
+        MangaError::from(result.unwrap_err())
+    }
+
+    #[tokio::test]
+    async fn test_categories() {
+        let db = db().await;
+        let categories = db.list_categories().await.unwrap();
+        assert_eq!(names(&categories), ["Default"]);
+        assert!(categories[0].is_default);
+        let default = categories[0].id;
+
+        let reading = db.create_category("  Reading ").await.unwrap();
+        assert_eq!((reading.name.as_str(), reading.position), ("Reading", 1));
+        assert!(!reading.is_default);
+        let done = db.create_category("Done").await.unwrap();
+        assert!(matches!(
+            error(db.create_category(" ").await),
+            MangaError::EmptyCategoryName
+        ));
+        assert!(matches!(
+            error(db.create_category("Reading").await),
+            MangaError::CategoryExists { name } if name == "Reading"
+        ));
+
+        // a category may keep its own name but not take another's
+        db.rename_category(reading.id, "Reading").await.unwrap();
+        assert!(matches!(
+            error(db.rename_category(done.id, "Reading").await),
+            MangaError::CategoryExists { .. }
+        ));
+        db.rename_category(done.id, "Finished").await.unwrap();
+        assert!(matches!(
+            error(db.rename_category(999, "Other").await),
+            MangaError::CategoryNotFound { category_id: 999 }
+        ));
+
+        // left out categories go last
+        db.reorder_categories(&[done.id, reading.id]).await.unwrap();
+        let categories = db.list_categories().await.unwrap();
+        assert_eq!(names(&categories), ["Finished", "Reading", "Default"]);
+        assert_eq!(
+            categories.iter().map(|c| c.position).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        // manga added to the library go in the default category, which
+        // can't be deleted while it's the default
+        assert!(matches!(
+            error(db.delete_category(default).await),
+            MangaError::DefaultCategory { name } if name == "Default"
+        ));
+        db.set_default_category(reading.id).await.unwrap();
+        let id = manga(&db, "https://rawkuro.net/manga/foo").await;
+        db.set_in_library(id, true).await.unwrap();
+        assert_eq!(names(&db.manga_categories(id).await.unwrap()), ["Reading"]);
+        let reading = db.find_category(reading.id).await.unwrap().unwrap();
+        assert!(reading.is_default);
+        assert_eq!(reading.manga_count, 1);
+        db.delete_category(default).await.unwrap();
+
+        // deleting a category keeps its manga in the library
+        db.set_manga_categories(id, &[done.id]).await.unwrap();
+        db.delete_category(done.id).await.unwrap();
+        assert!(db.manga_categories(id).await.unwrap().is_empty());
+        assert_eq!(db.list_manga(None).await.unwrap().len(), 1);
+        assert_eq!(names(&db.list_categories().await.unwrap()), ["Reading"]);
+
+        assert!(matches!(
+            error(db.delete_category(999).await),
+            MangaError::CategoryNotFound { category_id: 999 }
+        ));
+        assert!(matches!(
+            error(db.set_default_category(999).await),
+            MangaError::CategoryNotFound { category_id: 999 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_library() {
+        let db = db().await;
+        let default = db.list_categories().await.unwrap()[0].id;
+        let other = db.create_category("Other").await.unwrap().id;
+        let a = manga(&db, "https://rawkuro.net/manga/a").await;
+        let b = manga(&db, "https://rawkuro.net/manga/b").await;
+        let c = manga(&db, "https://rawkuro.net/manga/c").await;
+        for (id, ts) in [(a, 100), (b, 200), (c, 300)] {
+            db.update_manga_time(id, ts).await.unwrap();
+        }
+
+        // opened manga are only in the history
+        assert!(db.list_manga(None).await.unwrap().is_empty());
+        assert_eq!(ids(&db.list_history().await.unwrap()), [c, b, a]);
+
+        assert!(db.set_in_library(a, true).await.unwrap());
+        assert!(!db.set_in_library(999, true).await.unwrap());
+        // setting categories adds to the library, in the default category
+        // when none of them exists
+        db.set_manga_categories(b, &[other, 999]).await.unwrap();
+        db.set_manga_categories(c, &[999]).await.unwrap();
+        assert_eq!(ids(&db.list_manga(None).await.unwrap()), [c, b, a]);
+        assert_eq!(ids(&db.list_manga(Some(default)).await.unwrap()), [c, a]);
+        assert_eq!(ids(&db.list_manga(Some(other)).await.unwrap()), [b]);
+        assert!(db.find_manga(b).await.unwrap().unwrap().in_library);
+
+        // leaving the library leaves the categories too
+        db.set_in_library(b, false).await.unwrap();
+        assert!(db.list_manga(Some(other)).await.unwrap().is_empty());
+        assert!(db.manga_categories(b).await.unwrap().is_empty());
+        let counts: Vec<_> = db
+            .list_categories()
+            .await
+            .unwrap()
+            .iter()
+            .map(|c| c.manga_count)
+            .collect();
+        assert_eq!(counts, [2, 0]);
+
+        // pruning deletes what is only in the history and older than the
+        // cutoff
+        db.set_in_library(a, false).await.unwrap();
+        db.set_in_library(c, false).await.unwrap();
+        db.set_in_library(c, true).await.unwrap();
+        assert_eq!(db.prune_history(250).await.unwrap(), 2);
+        assert_eq!(ids(&db.list_history().await.unwrap()), [c]);
+        assert!(db.find_manga(a).await.unwrap().is_none());
+        assert_eq!(db.prune_history(1000).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_save_progress() {
+        let db = db().await;
+        let manga = manga(&db, "https://rawkuro.net/manga/a").await;
+        let url = "https://rawkuro.net/manga/a/1";
+        let id = db
+            .create_chapter(manga, "Chapter 1".into(), url.into(), 1.0, "1".into())
+            .await
+            .unwrap()
+            .id;
+
+        let chapter = db.save_progress(id, 3, 10).await.unwrap().unwrap();
+        assert_eq!((chapter.last_page, chapter.total_pages), (Some(3), Some(10)));
+        assert_eq!(chapter.is_read, 0);
+        assert!(chapter.read_at.is_some());
+
+        // the last page marks the chapter read, and going back keeps it read
+        assert_eq!(db.save_progress(id, 9, 10).await.unwrap().unwrap().is_read, 1);
+        let chapter = db.save_progress(id, 2, 10).await.unwrap().unwrap();
+        assert_eq!((chapter.is_read, chapter.last_page), (1, Some(2)));
+
+        db.mark_chapter_read(id, false).await.unwrap();
+        let chapter = db.find_chapter(id).await.unwrap().unwrap();
+        assert_eq!((chapter.is_read, chapter.last_page), (0, None));
+        db.mark_chapter_read(id, true).await.unwrap();
+        assert_eq!(db.find_chapter(id).await.unwrap().unwrap().is_read, 1);
+
+        assert!(db.save_progress(999, 0, 10).await.unwrap().is_none());
+        assert!(matches!(
+            error(db.mark_chapter_read(999, true).await),
+            MangaError::ChapterNotFound { chapter_id: 999 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_find_chapter_by_title() {
+        let db = db().await;
+        let a = manga(&db, "https://rawkuro.net/manga/a").await;
+        let b = manga(&db, "https://rawkuro.net/manga/b").await;
+        // as left by a source migration
+        sqlx::query(
+            "INSERT INTO chapter (manga, title, title_number, url, created_at, updated_at) VALUES (?, 'Chapter 1', 1, 'https://rawkuro.net/manga/a/1', 0, 0)",
+        )
+        .bind(a)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let url = "https://rawkuro.net/manga/a/2".to_string();
+        let keyed = db.create_chapter(a, "Chapter 2".into(), url, 2.0, "2".into()).await.unwrap();
+
+        let found = db.find_chapter_by_title(a, "Chapter 1".into()).await.unwrap();
+        assert_eq!(found.unwrap().chapter_key, None);
+        assert!(db.find_chapter_by_title(b, "Chapter 1".into()).await.unwrap().is_none());
+        // a chapter with a key is only found by it
+        assert!(db.find_chapter_by_title(a, "Chapter 2".into()).await.unwrap().is_none());
+        let found = db.find_chapter_by_key(a, "2").await.unwrap().unwrap();
+        assert_eq!(found.id, keyed.id);
+
+        // keys are unique per manga, titles are not
+        let url = "https://rawkuro.net/manga/a/2-2".to_string();
+        let duplicate = db.create_chapter(a, "Chapter 2 (v2)".into(), url, 2.0, "2".into()).await;
+        assert!(duplicate.is_err());
+        let url = "https://rawkuro.net/manga/a/2-3".to_string();
+        db.create_chapter(a, "Chapter 2".into(), url, 2.0, "3".into()).await.unwrap();
+        let url = "https://rawkuro.net/manga/b/2".to_string();
+        db.create_chapter(b, "Chapter 2".into(), url, 2.0, "2".into()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_canonicalize_urls() {
+        let db = db().await;
+        let reading = db.create_category("Reading").await.unwrap().id;
+        let a = manga(&db, "https://rawkuro.net/manga/a").await;
+        let b = manga(&db, "https://rawkuro.net/manga/b").await;
+        let c = manga(&db, "https://rawkuro.net/manga/c").await;
+        // as stored before urls were canonicalized
+        for (id, url, ts) in [
+            (a, "https://rawkuro.net/manga/foo", 100),
+            (b, "http://RAWKURO.net:80/manga/foo/?utm_source=x", 200),
+            (c, "https://rawkuro.net/manga/bar?utm_medium=y", 300),
+        ] {
+            sqlx::query("UPDATE manga SET url = ?, updated_at = ? WHERE id = ?")
+                .bind(url)
+                .bind(ts)
+                .bind(id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        db.set_manga_categories(a, &[reading]).await.unwrap();
+        for (manga, key, is_read, url) in [
+            (a, "1", true, "https://rawkuro.net/manga/foo/1"),
+            (a, "2", false, "https://rawkuro.net/manga/foo/2"),
+            (a, "4", false, "https://rawkuro.net/manga/foo/4#top"),
+            (b, "1", false, "https://rawkuro.net/manga/foo/1"),
+            (b, "2", true, "https://rawkuro.net/manga/foo/2"),
+            (b, "3", false, "https://rawkuro.net/manga/foo/3"),
+        ] {
+            let title = format!("Chapter {}", key);
+            let chapter = db
+                .create_chapter(manga, title, url.into(), 0.0, key.into())
+                .await
+                .unwrap();
+            db.mark_chapter_read(chapter.id, is_read).await.unwrap();
+        }
+
+        db.canonicalize_urls().await.unwrap();
+
+        // merged into the one opened last
+        assert!(db.find_manga(a).await.unwrap().is_none());
+        let kept = db.find_manga(b).await.unwrap().unwrap();
+        assert_eq!(kept.url, "http://rawkuro.net/manga/foo/");
+        assert!(kept.in_library);
+        assert_eq!(names(&db.manga_categories(b).await.unwrap()), ["Reading"]);
+        // read in either copy stays read, and the fragment is dropped
+        let mut chapters: Vec<_> = db
+            .get_chapters(b)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.chapter_key.unwrap(), c.is_read, c.url))
+            .collect();
+        chapters.sort();
+        let chapters: Vec<_> =
+            chapters.iter().map(|(k, r, u)| (k.as_str(), *r, u.as_str())).collect();
+        assert_eq!(
+            chapters,
+            [
+                ("1", 1, "https://rawkuro.net/manga/foo/1"),
+                ("2", 1, "https://rawkuro.net/manga/foo/2"),
+                ("3", 0, "https://rawkuro.net/manga/foo/3"),
+                ("4", 0, "https://rawkuro.net/manga/foo/4"),
+            ]
+        );
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM chapter")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(count.0, 4);
+
+        let other = db.find_manga(c).await.unwrap().unwrap();
+        assert_eq!(other.url, "https://rawkuro.net/manga/bar");
+        assert!(!other.in_library);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_base_url() {
+        let db = db().await;
+        let image = Some("https://rawkuro.net/covers/a.jpg".to_string());
+        let a = db
+            .create_manga("A".into(), "https://rawkuro.net/manga/a".into(), image)
+            .await
+            .unwrap()
+            .id;
+        let url = "https://rawkuro.net/manga/a/1".to_string();
+        let chapter = db.create_chapter(a, "Chapter 1".into(), url, 1.0, "1".into()).await.unwrap();
+        let lookalike = manga(&db, "https://rawkuro.network/manga/a").await;
+        let other = manga(&db, "https://jmanga.org/manga/a").await;
+        let stale = manga(&db, "https://rawkuro.net/manga/b").await;
+        let moved = manga(&db, "https://rawkuro.com/manga/b").await;
+
+        // the url, image and chapter of `a`; `stale` would clash with `moved`
+        let rows = db.rewrite_base_url("https://rawkuro.net", "https://rawkuro.com/").await;
+        assert_eq!(rows.unwrap(), 3);
+
+        let manga = db.find_manga(a).await.unwrap().unwrap();
+        assert_eq!(manga.url, "https://rawkuro.com/manga/a");
+        assert_eq!(manga.image.as_deref(), Some("https://rawkuro.com/covers/a.jpg"));
+        let chapter = db.find_chapter(chapter.id).await.unwrap().unwrap();
+        assert_eq!(chapter.url, "https://rawkuro.com/manga/a/1");
+        for (id, url) in [
+            (lookalike, "https://rawkuro.network/manga/a"),
+            (other, "https://jmanga.org/manga/a"),
+            (stale, "https://rawkuro.net/manga/b"),
+            (moved, "https://rawkuro.com/manga/b"),
+        ] {
+            assert_eq!(db.find_manga(id).await.unwrap().unwrap().url, url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_manga_source() {
+        let db = db().await;
+        let id = manga(&db, "https://rawkuro.net/manga/foo").await;
+        for (number, is_read) in [(1, false), (2, true), (3, true)] {
+            let url = format!("https://rawkuro.net/manga/foo/{}", number);
+            let title = format!("Chapter {}", number);
+            let key = number.to_string();
+            let chapter = db.create_chapter(id, title, url, number as f64, key).await.unwrap();
+            db.mark_chapter_read(chapter.id, is_read).await.unwrap();
+        }
+        let read = db.find_chapter_by_key(id, "2").await.unwrap().unwrap();
+        db.save_progress(read.id, 4, 20).await.unwrap();
+
+        let link = |text: &str, path: &str| Link {
+            text: text.into(),
+            url: format!("https://jmanga.org/read/foo/{}", path),
+            image: None,
+        };
+        // newest first, with chapter 2 listed twice and chapter 3 missing
+        let new = vec![
+            link("Chapter 2", "c2"),
+            link("Chapter 2 (reupload)", "c2-v2"),
+            link("Side Story", "side"),
+            link("Chapter 1", "c1"),
+        ];
+        let chapters = match_chapters(db.get_chapters(id).await.unwrap(), new);
+        let source = Link {
+            text: "Foo".into(),
+            url: "https://JMANGA.org/read/foo".into(),
+            image: None,
+        };
+        db.replace_manga_source(id, &source, &chapters, |url| {
+            url.rsplit('/').next().unwrap().split('-').next().unwrap().to_string()
+        })
+        .await
+        .unwrap();
+
+        let manga = db.find_manga(id).await.unwrap().unwrap();
+        assert_eq!(manga.title, "Foo");
+        assert_eq!(manga.url, "https://jmanga.org/read/foo");
+        let chapters = db.get_chapters(id).await.unwrap();
+        let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Chapter 2", "Side Story", "Chapter 1"]);
+        // the reupload has the key of chapter 2 and is skipped
+        let keys: Vec<_> = chapters.iter().map(|c| c.chapter_key.as_deref().unwrap()).collect();
+        assert_eq!(keys, ["c2", "side", "c1"]);
+        let side = &chapters[1];
+        assert!(side.title_number > 1.0 && side.title_number < 2.0);
+        // read state and progress follow the chapter of the same number
+        let read: Vec<_> = chapters.iter().map(|c| (c.is_read, c.last_page)).collect();
+        assert_eq!(read, [(1, Some(4)), (0, None), (0, None)]);
+        assert_eq!(chapters[0].url, "https://jmanga.org/read/foo/c2");
+    }
 }
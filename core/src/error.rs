@@ -10,6 +10,72 @@ pub enum MangaError {
 
     #[error("database migrate error: {msg}")]
     MigrateError { msg: String },
+
+    /// No site handles the url.
+    #[error("unsupported site: {url}")]
+    SiteUnsupported { url: String },
+
+    #[error("unknown site: {site_id}")]
+    UnknownSite { site_id: String },
+
+    /// The manga isn't stored, looked up by id or by url.
+    #[error("manga not found: {}", manga_id.map(|id| id.to_string()).or(url.clone()).unwrap_or_default())]
+    MangaNotFound {
+        manga_id: Option<i64>,
+        url: Option<String>,
+    },
+
+    #[error("chapter not found: {chapter_id}")]
+    ChapterNotFound { chapter_id: i64 },
+
+    #[error("category not found: {category_id}")]
+    CategoryNotFound { category_id: i64 },
+
+    /// Category names can't be empty.
+    #[error("category name is empty")]
+    EmptyCategoryName,
+
+    #[error("category {name} already exists")]
+    CategoryExists { name: String },
+
+    /// The category manga added to the library go in can't be deleted.
+    #[error("category {name} is the default category")]
+    DefaultCategory { name: String },
+
+    /// A page outside the `total` pages of the chapter.
+    #[error("page {page} is out of range for {total} pages")]
+    PageOutOfRange { page: u32, total: u32 },
+
+    #[error("{site_id}: HTTP {status} for {url}")]
+    HttpStatus {
+        site_id: String,
+        url: String,
+        status: u16,
+    },
+
+    /// An anti-bot page, such as a Cloudflare challenge, was served instead.
+    #[error("{site_id}: blocked by anti-bot protection (HTTP {status}) for {url}")]
+    Blocked {
+        site_id: String,
+        url: String,
+        status: u16,
+    },
+
+    /// The page was fetched but didn't have the expected content.
+    #[error("{site_id}: failed to parse {url}: {msg}")]
+    ParseFailed {
+        site_id: String,
+        url: String,
+        msg: String,
+    },
+
+    #[error("{site_id}: rate limited on {url}")]
+    RateLimited {
+        site_id: String,
+        url: String,
+        /// Seconds the site asked to wait, from `Retry-After`.
+        retry_after_secs: Option<u64>,
+    },
 }
 
 impl From<uniffi::UnexpectedUniFFICallbackError> for MangaError {
@@ -21,10 +87,19 @@ impl From<uniffi::UnexpectedUniFFICallbackError> for MangaError {
     }
 }
 
+/// Errors raised as a `MangaError` or a `reqwest::Error` keep their kind
+/// through `anyhow`, everything else is internal.
 impl From<anyhow::Error> for MangaError {
     fn from(value: anyhow::Error) -> Self {
-        Self::InternalError {
-            msg: value.to_string(),
+        let value = match value.downcast::<MangaError>() {
+            Ok(e) => return e,
+            Err(value) => value,
+        };
+        match value.downcast::<reqwest::Error>() {
+            Ok(e) => e.into(),
+            Err(value) => Self::InternalError {
+                msg: value.to_string(),
+            },
         }
     }
 }
@@ -60,3 +135,31 @@ impl From<sqlx::migrate::MigrateError> for MangaError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+
+    use super::MangaError;
+
+    #[test]
+    fn test_from_anyhow() {
+        let e: anyhow::Error = MangaError::RateLimited {
+            site_id: "a".into(),
+            url: "https://a/".into(),
+            retry_after_secs: Some(30),
+        }
+        .into();
+        let e = Err::<(), _>(e).context("searching").unwrap_err();
+        assert!(matches!(
+            MangaError::from(e),
+            MangaError::RateLimited {
+                retry_after_secs: Some(30),
+                ..
+            }
+        ));
+
+        let e = MangaError::from(anyhow::anyhow!("oops"));
+        assert!(matches!(e, MangaError::InternalError { msg } if msg == "oops"));
+    }
+}
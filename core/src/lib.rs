@@ -1,18 +1,48 @@
+mod chapter_number;
 mod db;
 mod error;
 mod log;
+mod reading_progress;
 mod sites;
+mod source_migration;
 
 use std::{
-    sync::{Arc, LazyLock, Once},
-    time::{SystemTime, UNIX_EPOCH},
+    collections::HashMap,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, Once,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::bail;
-use db::{ChapterDb, Db, MangaData};
+use db::{CategoryDb, ChapterDb, Db, MangaData};
 use error::MangaError;
 use log::{FFILogLayer, Logger};
-use sites::{jmangaorg::Jmangaorg, mangatopjp::MangaTopJp, rawkuronet::RawkuroNet, spoilerplustv::Spoilerplustv, Link, MangaSite};
+use sites::{
+    cache,
+    canonical::canonicalize,
+    client::{self, CookieSaver},
+    declarative::DeclarativeSite,
+    diagnose::{self, DiagnosticStage, SiteDiagnosis, StageReport},
+    domain,
+    federated::{self, FederatedResults, MergedTitle, SiteLink, SiteResults},
+    jmangaorg::Jmangaorg,
+    mangatopjp::MangaTopJp,
+    fetch_image,
+    probe::{MissingAttr, RequestReport, SelectorReport},
+    proxy::{self, ProxyConfig},
+    rawkuronet::RawkuroNet,
+    limit,
+    registry::{SiteRegistry, SourceInfo},
+    retry::{self, RetryPolicy},
+    spoilerplustv::Spoilerplustv,
+    Descramble, Link, Listing, MangaDetails, MangaSite, Page, PublicationStatus, RateLimit,
+    SearchPage, SiteCapabilities,
+};
+use reading_progress::ResumePoint;
+use source_migration::{ChapterMatch, SourceMigration};
 use tracing_subscriber::{layer::SubscriberExt, Registry};
 
 static _RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
@@ -36,26 +66,333 @@ pub fn init_logger(logger: Arc<dyn Logger>) {
     })
 }
 
+/// Set while a `Manga` exists.
+static INSTANCE_ALIVE: AtomicBool = AtomicBool::new(false);
+
+/// Held by the one `Manga` alive, released when it's dropped.
+#[derive(Debug)]
+struct Instance;
+
+impl Instance {
+    fn acquire() -> anyhow::Result<Self> {
+        if INSTANCE_ALIVE.swap(true, Ordering::SeqCst) {
+            bail!("another Manga instance is alive; drop it before creating a new one");
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        INSTANCE_ALIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Only one may exist at a time. The http clients with their cookie jar,
+/// the rate limits, the retry policy, the cache and the proxies belong to
+/// the process and are set up by `Manga::new`, so a second instance would
+/// quietly take them over from the first.
 #[derive(Debug)]
 pub struct Manga {
     db: Db,
+    registry: SiteRegistry,
+    search_timeout: Duration,
+    proxies: Mutex<Proxies>,
+    _cookie_saver: CookieSaver,
+    /// Last, so it's released once everything else is dropped.
+    _instance: Instance,
 }
 
 impl Manga {
+    /// Fails while another `Manga` is alive, see `Manga`.
     pub fn new(config: Config) -> anyhow::Result<Self> {
+        let instance = Instance::acquire()?;
         rt().block_on(async move {
             let db = Db::new(config.database_url.clone())?;
-            Ok(Self { db })
+
+            // Bundled sites followed by the sites loaded from `Config::sites_dir`.
+            // A definition with the id of a bundled site replaces it.
+            let mut sites: Vec<Arc<dyn MangaSite>> = vec![
+                Arc::new(Spoilerplustv::default()),
+                Arc::new(RawkuroNet::default()),
+                Arc::new(MangaTopJp::default()),
+                Arc::new(Jmangaorg::default()),
+            ];
+            if let Some(dir) = &config.sites_dir {
+                for site in DeclarativeSite::load_dir(Path::new(dir))?.into_iter() {
+                    sites.push(Arc::new(site));
+                }
+            }
+            for site in sites.iter() {
+                let id = site.id();
+                let rate_limit = match config.rate_limits.as_ref().and_then(|l| l.get(&id)) {
+                    Some(l) => *l,
+                    None => site.rate_limit(),
+                };
+                limit::set_site_limit(&id, rate_limit);
+            }
+            retry::set_policy(config.retry.unwrap_or_default());
+            cache::configure(
+                config.cache_dir.as_deref().map(Path::new),
+                Duration::from_secs(config.cache_ttl_secs),
+                config.cache_max_bytes,
+            )?;
+
+            let manga = Self {
+                _cookie_saver: CookieSaver::spawn(db.clone()),
+                db,
+                registry: SiteRegistry::new(sites),
+                search_timeout: Duration::from_secs(config.search_timeout_secs),
+                proxies: Mutex::new(Proxies {
+                    global: config.proxy,
+                    sites: config.site_proxies.unwrap_or_default(),
+                }),
+                _instance: instance,
+            };
+            manga.load_site_settings().await;
+            manga.load_cookies().await;
+            manga.apply_proxies()?;
+            Ok(manga)
         })
     }
 
+    async fn load_site_settings(&self) {
+        // The table doesn't exist until the migration has been done.
+        match self.db.list_site_settings().await {
+            Ok(settings) => self.registry.apply(&settings),
+            Err(e) => tracing::warn!(error = %e, "failed to load site settings"),
+        }
+    }
+
+    /// Points the site clients at the proxies, for the sites' current hosts.
+    fn apply_proxies(&self) -> anyhow::Result<()> {
+        let proxies = self.proxies.lock().unwrap();
+        proxy::set(self.proxy_settings(&proxies)?);
+        Ok(())
+    }
+
+    fn proxy_settings(&self, proxies: &Proxies) -> anyhow::Result<proxy::Settings> {
+        let mut sites = vec![];
+        for (site_id, config) in proxies.sites.iter() {
+            let site = match self.registry.get(site_id) {
+                Some(site) => site,
+                None => {
+                    return Err(MangaError::UnknownSite {
+                        site_id: site_id.clone(),
+                    }
+                    .into())
+                }
+            };
+            let hosts = proxy::site_hosts(&site.base_url(), site.cdn_url().as_deref());
+            sites.push((hosts, config.clone()));
+        }
+        proxy::Settings::new(proxies.global.as_ref(), &sites)
+    }
+
+    /// Replaces the proxy every site goes through, unless it has its own.
+    /// `None` connects directly.
+    pub fn set_proxy(&self, proxy: Option<ProxyConfig>) -> anyhow::Result<()> {
+        let mut proxies = self.proxies.lock().unwrap();
+        let next = Proxies {
+            global: proxy,
+            sites: proxies.sites.clone(),
+        };
+        proxy::set(self.proxy_settings(&next)?);
+        *proxies = next;
+        Ok(())
+    }
+
+    /// Sets the proxy of site `site_id`, overriding the one of `set_proxy`.
+    /// `None` removes the override.
+    pub fn set_site_proxy(
+        &self,
+        site_id: String,
+        proxy: Option<ProxyConfig>,
+    ) -> anyhow::Result<()> {
+        let mut proxies = self.proxies.lock().unwrap();
+        let mut next = proxies.clone();
+        match proxy {
+            Some(proxy) => next.sites.insert(site_id, proxy),
+            None => next.sites.remove(&site_id),
+        };
+        proxy::set(self.proxy_settings(&next)?);
+        *proxies = next;
+        Ok(())
+    }
+
+    /// Empties the cache of pages fetched by the sites.
+    pub async fn clear_http_cache(&self) -> anyhow::Result<()> {
+        match cache::current() {
+            Some(cache) => rt().spawn(async move { cache.clear().await }).await?,
+            None => Ok(()),
+        }
+    }
+
+    async fn load_cookies(&self) {
+        if let Err(e) = client::load_cookies(&self.db).await {
+            tracing::warn!(error = %e, "failed to load cookies");
+        }
+    }
+
     pub fn supported_sites(&self) -> Vec<Arc<dyn MangaSite>> {
-        vec![
-            Arc::new(Spoilerplustv::default()),
-            Arc::new(RawkuroNet::default()),
-            Arc::new(MangaTopJp::default()),
-            Arc::new(Jmangaorg::default()),
-        ]
+        self.registry.enabled()
+    }
+
+    /// Searches every enabled site at once. A site failing or timing out is
+    /// reported in its own results instead of failing the whole search.
+    ///
+    /// With `collapse`, near-identical titles from different sites are also
+    /// merged into `FederatedResults::merged`.
+    pub async fn search_all(
+        &self,
+        text: String,
+        collapse: bool,
+    ) -> anyhow::Result<FederatedResults> {
+        let sites = self.registry.enabled();
+        let timeout = self.search_timeout;
+        let res = rt()
+            .spawn(async move { federated::search_all(sites, text, timeout, collapse).await })
+            .await?;
+        Ok(res)
+    }
+
+    /// Runs site `site_id` through search, chapters and images, reporting
+    /// what each stage fetched and matched. Meant to find out which part of
+    /// a site broke when it shows nothing.
+    pub async fn diagnose_site(
+        &self,
+        site_id: String,
+        sample_query: String,
+    ) -> anyhow::Result<SiteDiagnosis> {
+        let site = match self.registry.get(&site_id) {
+            Some(site) => site,
+            None => return Err(MangaError::UnknownSite { site_id }.into()),
+        };
+        let res = rt()
+            .spawn(async move { diagnose::diagnose(site, sample_query).await })
+            .await?;
+        Ok(res)
+    }
+
+    pub fn list_sources(&self) -> Vec<SourceInfo> {
+        self.registry.sources()
+    }
+
+    pub async fn set_source_enabled(&self, id: String, enabled: bool) -> anyhow::Result<()> {
+        if !self.registry.set_enabled(&id, enabled) {
+            return Err(MangaError::UnknownSite { site_id: id }.into());
+        }
+        self.save_site_settings().await
+    }
+
+    pub async fn reorder_sources(&self, ids: Vec<String>) -> anyhow::Result<()> {
+        self.registry.reorder(&ids);
+        self.save_site_settings().await
+    }
+
+    /// Serves site `site_id` from other hosts, e.g. after it moved domains.
+    /// `None` restores the site's own url. When the base url changes, stored
+    /// manga and chapter urls are rewritten to the new one.
+    pub async fn set_site_urls(
+        &self,
+        site_id: String,
+        base_url: Option<String>,
+        cdn_url: Option<String>,
+    ) -> anyhow::Result<()> {
+        for url in base_url.iter().chain(cdn_url.iter()) {
+            if domain::origin(url).is_none() {
+                bail!("invalid url: {}", url);
+            }
+        }
+        let base_url = base_url.map(|u| u.trim_end_matches('/').to_string());
+        let cdn_url = cdn_url.map(|u| u.trim_end_matches('/').to_string());
+
+        let old_base = match self.registry.get(&site_id) {
+            Some(site) => site.base_url(),
+            None => return Err(MangaError::UnknownSite { site_id }.into()),
+        };
+        self.registry
+            .set_urls(&site_id, base_url.clone(), cdn_url.clone())?;
+        let new_base = self.registry.get(&site_id).map(|s| s.base_url());
+        self.save_site_settings().await?;
+        self.apply_proxies()?;
+
+        let db = self.db.clone();
+        rt().spawn(async move {
+            db.update_site_urls(&site_id, base_url.as_deref(), cdn_url.as_deref())
+                .await?;
+            match new_base {
+                Some(new_base) if !old_base.is_empty() && new_base != old_base => {
+                    let rows = db.rewrite_base_url(&old_base, &new_base).await?;
+                    tracing::info!(site_id, old_base, new_base, rows, "rewrote stored urls");
+                }
+                _ => {}
+            }
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Checks whether site `site_id` permanently redirects to another
+    /// domain, returning the new base url. With `follow`, the site is also
+    /// moved there as with `set_site_urls`.
+    pub async fn check_domain(
+        &self,
+        site_id: String,
+        follow: bool,
+    ) -> anyhow::Result<Option<String>> {
+        let base_url = match self.registry.get(&site_id) {
+            Some(site) => site.base_url(),
+            None => return Err(MangaError::UnknownSite { site_id }.into()),
+        };
+        if domain::origin(&base_url).is_none() {
+            bail!("site {} has no base url", site_id);
+        }
+        let new_base = domain::detect_move(&base_url).await?;
+
+        if let (Some(new_base), true) = (&new_base, follow) {
+            let (_, cdn_url) = self.registry.url_overrides(&site_id).unwrap_or_default();
+            self.set_site_urls(site_id, Some(new_base.clone()), cdn_url)
+                .await?;
+        }
+        Ok(new_base)
+    }
+
+    /// Adds cookies for site `site_id`, given as the `Cookie` header a
+    /// WebView has for the site, e.g. after the user solved a challenge or
+    /// logged in. Every request to the site sends them from then on.
+    pub async fn set_cookies(&self, site_id: String, cookies: String) -> anyhow::Result<()> {
+        let base_url = match self.registry.get(&site_id) {
+            Some(site) => site.base_url(),
+            None => return Err(MangaError::UnknownSite { site_id }.into()),
+        };
+        let url = match reqwest::Url::parse(&format!("{}/", base_url)) {
+            Ok(url) => url,
+            Err(_) => bail!("site {} has no base url", site_id),
+        };
+        if client::jar().set_header(&url, &cookies) == 0 {
+            bail!("no cookies in {:?}", cookies);
+        }
+        let db = self.db.clone();
+        rt().spawn(async move { client::save_cookies(&db).await })
+            .await?
+    }
+
+    /// Imports the cookies of a Netscape `cookies.txt` file, returning how
+    /// many were added.
+    pub async fn import_cookies(&self, contents: String) -> anyhow::Result<u32> {
+        let added = client::jar().import_netscape(&contents)?;
+        let db = self.db.clone();
+        rt().spawn(async move { client::save_cookies(&db).await })
+            .await??;
+        Ok(added as u32)
+    }
+
+    async fn save_site_settings(&self) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        let sources = self.registry.sources();
+        rt().spawn(async move { db.save_site_settings(&sources).await })
+            .await?
     }
 
     pub async fn migration_available(&self) -> Result<bool, MangaError> {
@@ -66,20 +403,74 @@ impl Manga {
 
     pub async fn do_migration(&self) -> anyhow::Result<()> {
         let db = self.db.clone();
-        rt().spawn(async move { db.do_migration().await }).await?
+        rt().spawn(async move { db.do_migration().await }).await??;
+        self.load_site_settings().await;
+        self.load_cookies().await;
+        self.apply_proxies()?;
+        Ok(())
     }
 
     pub fn reset_db(&self) -> anyhow::Result<()> {
         self.db.reset()
     }
 
-    pub async fn list_manga(&self) -> anyhow::Result<Vec<MangaData>> {
+    /// The whole library, or only the manga in `category_id`.
+    pub async fn list_manga(&self, category_id: Option<i64>) -> anyhow::Result<Vec<MangaData>> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.list_manga(category_id).await }).await?
+    }
+
+    /// Every manga opened so far, followed or not, last opened first; see
+    /// `MangaData::updated_at`.
+    pub async fn list_history(&self) -> anyhow::Result<Vec<MangaData>> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.list_history().await }).await?
+    }
+
+    /// Forgets the manga outside the library that weren't opened for
+    /// `older_than_secs`, returning how many were forgotten. Followed manga
+    /// are never pruned.
+    pub async fn prune_history(&self, older_than_secs: u64) -> anyhow::Result<u64> {
         let db = self.db.clone();
-        rt().spawn(async move { db.list_manga().await }).await?
+        rt().spawn(async move {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            db.prune_history(now.saturating_sub(older_than_secs) as i64).await
+        })
+        .await?
+    }
+
+    /// Follows manga `id`, putting it in the default category unless it
+    /// already has categories.
+    pub async fn add_to_library(&self, id: i64) -> anyhow::Result<()> {
+        self.set_in_library(id, true).await
+    }
+
+    /// Unfollows manga `id`; it stays in the history.
+    pub async fn remove_from_library(&self, id: i64) -> anyhow::Result<()> {
+        self.set_in_library(id, false).await
     }
 
+    async fn set_in_library(&self, id: i64, in_library: bool) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move {
+            if !db.set_in_library(id, in_library).await? {
+                return Err(MangaError::MangaNotFound {
+                    manga_id: Some(id),
+                    url: None,
+                }
+                .into());
+            }
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Details are fetched when they weren't stored in the last
+    /// `DETAILS_MAX_AGE`; a failure there is logged and the stored data is
+    /// returned instead.
     pub async fn open_manga(&self, link: Link) -> anyhow::Result<MangaData> {
         let db = self.db.clone();
+        let site = self.registry.find_for_chapters(&link.url);
         rt().spawn(async move {
             let link = link;
             let manga = match db.find_manga_by_url(&link.url).await? {
@@ -91,7 +482,182 @@ impl Manga {
                 }
                 None => db.create_manga(link.text, link.url, link.image).await?,
             };
-            Ok(manga)
+            let stale = match db.details_updated_at(manga.id).await? {
+                Some(at) => manga.updated_at - at >= DETAILS_MAX_AGE.as_secs() as i64,
+                None => true,
+            };
+            match site {
+                Some(site) if stale => refresh_details(&db, site.as_ref(), manga).await,
+                _ => Ok(manga),
+            }
+        })
+        .await?
+    }
+
+    /// Opens whatever `url` points to, such as a link shared from a browser.
+    ///
+    /// A manga page is opened like with `open_manga` and added to the
+    /// library. For a chapter page its manga is opened, added to the library
+    /// and its chapters fetched, the chapter being `None` only if the site
+    /// doesn't list it.
+    pub async fn resolve_url(&self, url: String) -> anyhow::Result<ResolvedUrl> {
+        let url = canonicalize(&url);
+        if let Some(site) = self.registry.find_for_chapters(&url) {
+            let site_id = site.id();
+            let manga = self.open_manga_url(site, url).await?;
+            return Ok(ResolvedUrl {
+                site_id,
+                kind: UrlKind::Manga,
+                manga: self.follow(manga).await?,
+                chapter: None,
+            });
+        }
+        let site = match self.registry.find_for_images(&url) {
+            Some(site) => site,
+            None => return Err(MangaError::SiteUnsupported { url }.into()),
+        };
+
+        let db = self.db.clone();
+        let chapter_url = url.clone();
+        let stored = rt()
+            .spawn(async move {
+                let Some(chapter) = db.find_chapter_by_url(&chapter_url).await? else {
+                    return anyhow::Ok(None);
+                };
+                Ok(db.find_manga(chapter.manga).await?.map(|m| (chapter, m)))
+            })
+            .await??;
+        let (manga, chapter) = match stored {
+            Some((chapter, manga)) => {
+                let link = Link {
+                    text: manga.title,
+                    url: manga.url,
+                    image: manga.image,
+                };
+                (self.open_manga(link).await?, Some(chapter))
+            }
+            None => {
+                let not_found = || MangaError::MangaNotFound {
+                    manga_id: None,
+                    url: Some(url.clone()),
+                };
+                let manga_url = site.chapter_manga_url(&url).ok_or_else(not_found)?;
+                let manga_site = self
+                    .registry
+                    .find_for_chapters(&manga_url)
+                    .ok_or_else(not_found)?;
+                let manga = self.open_manga_url(manga_site, manga_url).await?;
+                let key = site.chapter_key(&url);
+                let chapter = self
+                    .get_chapters(manga.url.clone())
+                    .await?
+                    .into_iter()
+                    .find(|c| c.chapter_key.as_deref() == Some(key.as_str()) || c.url == url);
+                (manga, chapter)
+            }
+        };
+        Ok(ResolvedUrl {
+            site_id: site.id(),
+            kind: UrlKind::Chapter,
+            manga: self.follow(manga).await?,
+            chapter,
+        })
+    }
+
+    /// Adds `manga` to the library, see `add_to_library`.
+    async fn follow(&self, mut manga: MangaData) -> anyhow::Result<MangaData> {
+        self.add_to_library(manga.id).await?;
+        manga.in_library = true;
+        Ok(manga)
+    }
+
+    /// Opens the manga page `url` of `site`, reading its title and cover
+    /// from the page unless it's stored.
+    async fn open_manga_url(
+        &self,
+        site: Arc<dyn MangaSite>,
+        url: String,
+    ) -> anyhow::Result<MangaData> {
+        let db = self.db.clone();
+        let link = rt()
+            .spawn(async move {
+                match db.find_manga_by_url(&url).await? {
+                    Some(manga) => Ok(Link {
+                        text: manga.title,
+                        url: manga.url,
+                        image: manga.image,
+                    }),
+                    None => site.manga_link(&url).await,
+                }
+            })
+            .await??;
+        self.open_manga(link).await
+    }
+
+    /// Moves manga `manga_id` to `link`, likely on another site.
+    ///
+    /// Chapters are matched by the number in their title and keep their read
+    /// state and timestamps. The manga keeps its id, so everything attached
+    /// to it stays. With `dry_run` only the matches are returned.
+    pub async fn migrate_source(
+        &self,
+        manga_id: i64,
+        link: Link,
+        dry_run: bool,
+    ) -> anyhow::Result<SourceMigration> {
+        let db = self.db.clone();
+        let site = self.registry.find_for_chapters(&link.url);
+        rt().spawn(async move {
+            let site = match site {
+                Some(site) => site,
+                None => return Err(MangaError::SiteUnsupported { url: link.url }.into()),
+            };
+            let manga = match db.find_manga(manga_id).await? {
+                Some(manga) => manga,
+                None => {
+                    return Err(MangaError::MangaNotFound {
+                        manga_id: Some(manga_id),
+                        url: None,
+                    }
+                    .into())
+                }
+            };
+            if let Some(other) = db.find_manga_by_url(&link.url).await? {
+                if other.id != manga.id {
+                    bail!("{} is already in the library", link.url);
+                }
+            }
+
+            let old = db.get_chapters(manga.id).await?;
+            let new = site.chapters(&link.url).await?;
+            let chapters = source_migration::match_chapters(old, new);
+            let skipped = source_migration::duplicates(&chapters, |url| site.chapter_key(url));
+            let mut migration = SourceMigration {
+                manga_id,
+                chapters,
+                skipped,
+                applied: false,
+            };
+            if dry_run {
+                return Ok(migration);
+            }
+
+            tracing::info!(
+                from = %manga.url,
+                to = %link.url,
+                lost_reads = migration.lost_reads(),
+                skipped = migration.skipped.len(),
+                "migrating manga source"
+            );
+            db.replace_manga_source(manga.id, &link, &migration.chapters, |url| {
+                site.chapter_key(url)
+            })
+            .await?;
+            if let Some(manga) = db.find_manga(manga.id).await? {
+                refresh_details(&db, site.as_ref(), manga).await?;
+            }
+            migration.applied = true;
+            Ok(migration)
         })
         .await?
     }
@@ -117,6 +683,65 @@ impl Manga {
         }).await?
     }
 
+    pub async fn list_categories(&self) -> anyhow::Result<Vec<CategoryDb>> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.list_categories().await }).await?
+    }
+
+    pub async fn create_category(&self, name: String) -> anyhow::Result<CategoryDb> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.create_category(&name).await }).await?
+    }
+
+    pub async fn rename_category(&self, id: i64, name: String) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.rename_category(id, &name).await }).await?
+    }
+
+    pub async fn reorder_categories(&self, ids: Vec<i64>) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.reorder_categories(&ids).await }).await?
+    }
+
+    /// The manga of a deleted category stay in the library, in their other
+    /// categories if any.
+    pub async fn delete_category(&self, id: i64) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.delete_category(id).await }).await?
+    }
+
+    pub async fn set_default_category(&self, id: i64) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.set_default_category(id).await }).await?
+    }
+
+    pub async fn get_manga_categories(&self, manga_id: i64) -> anyhow::Result<Vec<CategoryDb>> {
+        let db = self.db.clone();
+        rt().spawn(async move { db.manga_categories(manga_id).await }).await?
+    }
+
+    /// Puts manga `manga_id` in exactly the categories `category_ids`, adding
+    /// it to the library if it isn't in it. With no category it goes in the
+    /// default one.
+    pub async fn set_manga_categories(
+        &self,
+        manga_id: i64,
+        category_ids: Vec<i64>,
+    ) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        rt().spawn(async move {
+            if db.find_manga(manga_id).await?.is_none() {
+                return Err(MangaError::MangaNotFound {
+                    manga_id: Some(manga_id),
+                    url: None,
+                }
+                .into());
+            }
+            db.set_manga_categories(manga_id, &category_ids).await
+        })
+        .await?
+    }
+
     pub async fn get_chapter(&self, id: i64) -> anyhow::Result<Option<ChapterDb>> {
         let db = self.db.clone();
         rt().spawn(async move { db.find_chapter(id).await }).await?
@@ -128,39 +753,103 @@ impl Manga {
             .await?
     }
 
+    /// Records that page `page` (from 0) of `total` was reached in chapter
+    /// `chapter_id`. The chapter becomes read on its last page, and its manga
+    /// moves to the top of the history.
+    pub async fn save_progress(
+        &self,
+        chapter_id: i64,
+        page: u32,
+        total: u32,
+    ) -> anyhow::Result<ChapterDb> {
+        if page >= total {
+            return Err(MangaError::PageOutOfRange { page, total }.into());
+        }
+        let db = self.db.clone();
+        rt().spawn(async move {
+            let Some(chapter) = db.save_progress(chapter_id, page as i64, total as i64).await?
+            else {
+                return Err(MangaError::ChapterNotFound { chapter_id }.into());
+            };
+            if let Some(ts) = chapter.read_at {
+                db.update_manga_time(chapter.manga, ts).await?;
+            }
+            Ok(chapter)
+        })
+        .await?
+    }
+
+    /// The chapter and page to continue reading manga `manga_id` at, see
+    /// `reading_progress::resume_point`.
+    pub async fn resume_point(&self, manga_id: i64) -> anyhow::Result<Option<ResumePoint>> {
+        let db = self.db.clone();
+        rt().spawn(async move {
+            let chapters = db.get_chapters(manga_id).await?;
+            Ok(reading_progress::resume_point(chapters))
+        })
+        .await?
+    }
+
     pub async fn get_chapters(&self, url: String) -> anyhow::Result<Vec<ChapterDb>> {
+        self.fetch_chapters(url, false).await
+    }
+
+    /// Like `get_chapters`, asking the site for the manga page even if the
+    /// cached one is recent.
+    pub async fn refresh_chapters(&self, url: String) -> anyhow::Result<Vec<ChapterDb>> {
+        self.fetch_chapters(url, true).await
+    }
+
+    async fn fetch_chapters(&self, url: String, refresh: bool) -> anyhow::Result<Vec<ChapterDb>> {
         let db = self.db.clone();
-        let sites = self.supported_sites();
+        let site = self.registry.find_for_chapters(&url);
         rt().spawn(async move {
             let url = url;
-            let site = match sites.iter().find(|s| s.can_handle_chapters(&url)) {
+            let site = match site {
                 Some(site) => site,
-                None => bail!("Couldn't find site handler for {}", url),
+                None => return Err(MangaError::SiteUnsupported { url }.into()),
             };
 
             let manga = match db.find_manga_by_url(&url).await? {
                 Some(manga) => manga,
-                None => bail!("the url isn't in database yet: {}", url),
+                None => {
+                    return Err(MangaError::MangaNotFound {
+                        manga_id: None,
+                        url: Some(url),
+                    }
+                    .into())
+                }
             };
 
-            let chapters = site.chapters(&url).await?;
+            let chapters = if refresh {
+                cache::refreshing(site.chapters(&url)).await?
+            } else {
+                site.chapters(&url).await?
+            };
 
-            // TODO: insert to db
-            for chapter in chapters.into_iter() {
-                let _ = match db
-                    .find_chapter_by_title(manga.id, chapter.text.clone())
-                    .await?
-                {
-                    Some(in_db) => {
-                        db.update_chapter(manga.id, chapter.text, chapter.url)
-                            .await?;
-                        in_db
-                    }
+            let numbers = chapter_number::assign_numbers(
+                &chapters.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+            );
+            for (chapter, number) in chapters.into_iter().zip(numbers) {
+                let key = site.chapter_key(&chapter.url);
+                // by title for chapters stored before they had a key
+                let in_db = match db.find_chapter_by_key(manga.id, &key).await? {
+                    Some(in_db) => Some(in_db),
                     None => {
-                        db.create_chapter(manga.id, chapter.text, chapter.url)
+                        db.find_chapter_by_title(manga.id, chapter.text.clone())
                             .await?
                     }
                 };
+                match in_db {
+                    Some(in_db) => {
+                        db.update_chapter(in_db.id, chapter.text, chapter.url, number, key)
+                            .await?
+                    }
+                    None => {
+                        db.create_chapter(manga.id, chapter.text, chapter.url, number, key)
+                            .await?;
+                    }
+                }
             }
 
             let chapters = db.get_chapters(manga.id).await?;
@@ -186,20 +875,16 @@ impl Manga {
     }
 
     pub fn get_site(&self, url: String) -> anyhow::Result<Option<Arc<dyn MangaSite>>> {
-        let site = self
-            .supported_sites()
-            .into_iter()
-            .find(|s| s.can_handle_images(&url));
-        Ok(site)
+        Ok(self.registry.find_for_images(&url))
     }
 
-    pub async fn get_images(&self, url: String) -> anyhow::Result<Vec<String>> {
-        let sites = self.supported_sites();
+    pub async fn get_images(&self, url: String) -> anyhow::Result<Vec<Page>> {
+        let site = self.registry.find_for_images(&url);
         rt().spawn(async move {
             let url = url;
-            let site = match sites.iter().find(|s| s.can_handle_images(&url)) {
+            let site = match site {
                 Some(site) => site,
-                None => bail!("Couldn't find site handler for {}", url),
+                None => return Err(MangaError::SiteUnsupported { url }.into()),
             };
 
             let images = site.images(&url).await?;
@@ -207,19 +892,257 @@ impl Manga {
         })
         .await?
     }
+
+    /// Downloads `page` of chapter `chapter_url` with the headers it needs,
+    /// through the site's proxy. Its alternate urls are tried when the main
+    /// one fails.
+    pub async fn download_image(&self, chapter_url: String, page: Page) -> anyhow::Result<Vec<u8>> {
+        let site = match self.registry.find_for_images(&chapter_url) {
+            Some(site) => site,
+            None => return Err(MangaError::SiteUnsupported { url: chapter_url }.into()),
+        };
+        rt().spawn(async move {
+            let mut last_err = None;
+            for url in std::iter::once(&page.url).chain(page.alternates.iter()) {
+                let mut req = client::shared().get(url);
+                for (name, value) in page.headers.iter() {
+                    req = req.header(name, value);
+                }
+                match fetch_image(&site.id(), req).await {
+                    Ok(image) => return Ok(image),
+                    Err(e) => {
+                        tracing::warn!(url, error = %e, "failed to download image");
+                        last_err = Some(e);
+                    }
+                }
+            }
+            Err(last_err.unwrap())
+        })
+        .await?
+    }
+}
+
+/// Fetches and stores the details of `manga`. A failure to fetch is logged
+/// and the stored data is returned instead.
+/// How long stored details are used before opening the manga fetches them
+/// again.
+const DETAILS_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+async fn refresh_details(
+    db: &Db,
+    site: &dyn MangaSite,
+    manga: MangaData,
+) -> anyhow::Result<MangaData> {
+    match site.details(&manga.url).await {
+        Ok(details) => {
+            db.update_manga_details(manga.id, &details).await?;
+            Ok(db.find_manga(manga.id).await?.unwrap_or(manga))
+        }
+        Err(e) => {
+            tracing::warn!("failed to fetch details for {}: {:?}", manga.url, e);
+            Ok(manga)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UrlKind {
+    Manga,
+    Chapter,
+}
+
+/// What `Manga::resolve_url` found at a url.
+#[derive(Debug)]
+pub struct ResolvedUrl {
+    pub site_id: String,
+    pub kind: UrlKind,
+    pub manga: MangaData,
+    pub chapter: Option<ChapterDb>,
+}
+
+/// Proxy settings as given, resolved by `Manga::apply_proxies` against the
+/// sites' current hosts.
+#[derive(Debug, Clone)]
+struct Proxies {
+    global: Option<ProxyConfig>,
+    sites: HashMap<String, ProxyConfig>,
 }
 
 #[derive(Debug)]
 pub struct Config {
     pub database_url: String,
+    /// Directory containing declarative site definitions (`*.toml`, `*.json`),
+    /// which may not exist yet
+    pub sites_dir: Option<String>,
+    /// How long `Manga::search_all` waits for each site
+    pub search_timeout_secs: u64,
+    /// Rate limits by site id, overriding the ones the sites declare
+    pub rate_limits: Option<HashMap<String, RateLimit>>,
+    /// Retries of failed requests made by the sites
+    pub retry: Option<RetryPolicy>,
+    /// Proxy every site goes through
+    pub proxy: Option<ProxyConfig>,
+    /// Proxies by site id, overriding `proxy`
+    pub site_proxies: Option<HashMap<String, ProxyConfig>>,
+    /// Directory pages fetched by the sites are cached in, if any
+    pub cache_dir: Option<String>,
+    /// How long a cached page is used without asking the site
+    pub cache_ttl_secs: u64,
+    /// Bytes of cached pages kept before the oldest are evicted, 0 for no limit
+    pub cache_max_bytes: u64,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             database_url: "sqlite://database.db".to_string(),
+            sites_dir: None,
+            search_timeout_secs: 15,
+            rate_limits: None,
+            retry: None,
+            proxy: None,
+            site_proxies: None,
+            cache_dir: None,
+            cache_ttl_secs: 300,
+            cache_max_bytes: 50 * 1024 * 1024,
         }
     }
 }
 
 uniffi::include_scaffolding!("manga");
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, MutexGuard},
+        time::Duration,
+    };
+
+    use super::{Instance, Link, Manga, Proxies, UrlKind};
+    use crate::{
+        db::Db,
+        sites::{
+            client::CookieSaver,
+            declarative::{DeclarativeSite, SiteDefinition},
+            registry::SiteRegistry,
+            testing::{Fixture, FixtureServer},
+            MangaSite,
+        },
+    };
+
+    /// Held by the tests taking the `Instance`, so they don't run at once.
+    /// `Manga::new` isn't used here: it resets the proxies, the retry policy
+    /// and the cache of the whole process, which other tests rely on.
+    static INSTANCE_TESTS: Mutex<()> = Mutex::new(());
+
+    fn lock() -> MutexGuard<'static, ()> {
+        INSTANCE_TESTS.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    const SITE: &str = r##"
+id = "fixture"
+name = "Fixture"
+base_url = "https://fixture.example"
+
+[search]
+url = "{base_url}/search"
+query_key = "keyword"
+item = "#main a"
+title = { attr = "title" }
+href = { attr = "href" }
+
+[chapters]
+pattern = "^{base_url}/manga/[^/]+$"
+item = "#myUL li a"
+title = {}
+href = { attr = "href" }
+
+[details]
+authors = ".manga-info .author a"
+
+[images]
+pattern = "^{base_url}/manga/[^/]+/[^/]+$"
+item = ".separator"
+src = { selector = ".readImg", attr = "href" }
+"##;
+
+    /// A `Manga` with the site of `SITE` served by `server`. Unlike
+    /// `Manga::new`, the settings of the process are left alone.
+    async fn manga(server: &FixtureServer) -> Manga {
+        let mut def: SiteDefinition = toml::from_str(SITE).unwrap();
+        def.base_url = server.url();
+        let site: Arc<dyn MangaSite> = Arc::new(DeclarativeSite::new(def).unwrap());
+        let db = Db::new("sqlite::memory:".to_string()).unwrap();
+        db.do_migration().await.unwrap();
+        Manga {
+            _cookie_saver: CookieSaver::spawn(db.clone()),
+            db,
+            registry: SiteRegistry::new(vec![site]),
+            search_timeout: Duration::from_secs(5),
+            proxies: Mutex::new(Proxies {
+                global: None,
+                sites: HashMap::new(),
+            }),
+            _instance: Instance::acquire().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_url_adds_to_library() {
+        let server = FixtureServer::start(vec![(
+            "/manga/isekai-nonbiri-nouka",
+            Fixture::html(include_str!("../fixtures/rawkuronet/manga.html")),
+        )])
+        .await;
+        let _lock = lock();
+        let manga = manga(&server).await;
+
+        let manga_url = format!("{}/manga/isekai-nonbiri-nouka", server.url());
+        let resolved = manga.resolve_url(manga_url).await.unwrap();
+        assert_eq!(resolved.kind, UrlKind::Manga);
+        assert!(resolved.manga.in_library);
+        let library = manga.list_manga(None).await.unwrap();
+        assert_eq!(library.iter().map(|m| m.id).collect::<Vec<_>>(), [resolved.manga.id]);
+
+        manga.remove_from_library(resolved.manga.id).await.unwrap();
+        let chapter_url = format!("{}/manga/isekai-nonbiri-nouka/chapter-251", server.url());
+        let resolved = manga.resolve_url(chapter_url.clone()).await.unwrap();
+        assert_eq!(resolved.kind, UrlKind::Chapter);
+        assert_eq!(resolved.chapter.map(|c| c.url), Some(chapter_url));
+        let library = manga.list_manga(None).await.unwrap();
+        assert_eq!(library.iter().map(|m| m.id).collect::<Vec<_>>(), [resolved.manga.id]);
+    }
+
+    #[tokio::test]
+    async fn test_open_manga_details() {
+        let server = FixtureServer::start(vec![(
+            "/manga/isekai-nonbiri-nouka",
+            Fixture::html(include_str!("../fixtures/rawkuronet/manga.html")),
+        )])
+        .await;
+        let _lock = lock();
+        let manga = manga(&server).await;
+        let link = Link {
+            text: "異世界のんびり農家".to_string(),
+            url: format!("{}/manga/isekai-nonbiri-nouka", server.url()),
+            image: None,
+        };
+
+        let opened = manga.open_manga(link.clone()).await.unwrap();
+        assert_eq!(opened.details.authors, vec!["内藤騎之介"]);
+        // stored moments ago, so not fetched again
+        let opened = manga.open_manga(link).await.unwrap();
+        assert_eq!(opened.details.authors, vec!["内藤騎之介"]);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_single_instance() {
+        let _lock = lock();
+        let instance = Instance::acquire().unwrap();
+        assert!(Instance::acquire().is_err());
+        drop(instance);
+        assert!(Instance::acquire().is_ok());
+    }
+}
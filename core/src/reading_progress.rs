@@ -0,0 +1,107 @@
+//! Where to pick up reading a manga.
+
+use crate::db::ChapterDb;
+
+/// A chapter and the page to open it at.
+#[derive(Debug)]
+pub struct ResumePoint {
+    pub chapter: ChapterDb,
+    pub page: u32,
+}
+
+/// Where to continue reading among `chapters`: the chapter read last at the
+/// page it was left on, or once it's finished, the first unread chapter after
+/// it. `None` when every chapter from there on has been read.
+pub fn resume_point(mut chapters: Vec<ChapterDb>) -> Option<ResumePoint> {
+    chapters.sort_by(|a, b| a.title_number.total_cmp(&b.title_number));
+    let last = chapters
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.read_at.is_some())
+        .max_by_key(|(_, c)| c.read_at)
+        .map(|(i, _)| i);
+    let start = match last {
+        Some(i) if chapters[i].is_read == 0 => i,
+        Some(i) => i + 1,
+        None => 0,
+    };
+
+    let chapter = chapters.into_iter().skip(start).find(|c| c.is_read == 0)?;
+    let page = chapter.last_page.unwrap_or(0).max(0) as u32;
+    Some(ResumePoint { chapter, page })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::ChapterDb;
+
+    use super::resume_point;
+
+    fn chapter(number: f64, is_read: bool, progress: Option<(i64, i64)>) -> ChapterDb {
+        ChapterDb {
+            id: number as i64,
+            manga: 1,
+            title: format!("第{}話", number),
+            title_number: number,
+            url: format!("https://rawkuro.net/manga/foo/{}", number),
+            is_read: if is_read { 1 } else { 0 },
+            created_at: 100,
+            updated_at: 200,
+            chapter_key: None,
+            last_page: progress.map(|(page, _)| page),
+            total_pages: progress.map(|_| 20),
+            read_at: progress.map(|(_, at)| at),
+        }
+    }
+
+    fn resume(chapters: Vec<ChapterDb>) -> Option<(f64, u32)> {
+        resume_point(chapters).map(|p| (p.chapter.title_number, p.page))
+    }
+
+    #[test]
+    fn test_resume_point() {
+        // never read: the first chapter
+        assert_eq!(
+            resume(vec![chapter(2.0, false, None), chapter(1.0, false, None)]),
+            Some((1.0, 0))
+        );
+        // read without progress, like chapters marked read by hand
+        assert_eq!(
+            resume(vec![
+                chapter(3.0, false, None),
+                chapter(2.0, true, None),
+                chapter(1.0, true, None),
+            ]),
+            Some((3.0, 0))
+        );
+        // in the middle of a chapter
+        assert_eq!(
+            resume(vec![
+                chapter(3.0, false, None),
+                chapter(2.0, false, Some((7, 500))),
+                chapter(1.0, true, Some((19, 400))),
+            ]),
+            Some((2.0, 7))
+        );
+        // last read chapter finished: the next unread one, even if an older
+        // chapter was left unfinished
+        assert_eq!(
+            resume(vec![
+                chapter(4.0, false, None),
+                chapter(3.0, true, None),
+                chapter(2.0, true, Some((19, 500))),
+                chapter(1.0, false, Some((3, 400))),
+            ]),
+            Some((4.0, 0))
+        );
+        // everything read
+        assert_eq!(
+            resume(vec![
+                chapter(2.0, true, Some((19, 500))),
+                chapter(1.0, false, Some((3, 400))),
+            ]),
+            None
+        );
+        assert_eq!(resume(vec![]), None);
+    }
+}
@@ -0,0 +1,335 @@
+//! On-disk cache of the pages the sites fetch.
+//!
+//! A page fetched less than the TTL ago is served without a request. An
+//! older one is revalidated with `If-None-Match` / `If-Modified-Since`, so
+//! an unchanged page costs a 304 rather than the whole page. Running under
+//! `refreshing` skips the TTL, and a diagnosis never reads the cache.
+//!
+//! Once the pages take up more than the size limit, the least recently
+//! stored ones are evicted.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    future::Future,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lazy_static::lazy_static;
+use reqwest::header::{
+    HeaderMap, HeaderValue, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+
+/// Share of the size limit an eviction frees the cache down to, so that it
+/// doesn't run again on the next page stored.
+const EVICT_TO_PERCENT: u64 = 90;
+
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// Bytes of stored pages beyond which the oldest are evicted, 0 for no
+    /// limit.
+    max_bytes: u64,
+    /// Bytes stored, as counted by the last eviction plus what was written
+    /// since.
+    size: AtomicU64,
+    evicting: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Unix time of the last response, a 304 included.
+    fetched_at: u64,
+    pub body: String,
+}
+
+lazy_static! {
+    static ref CACHE: RwLock<Option<Arc<Cache>>> = RwLock::new(None);
+}
+
+tokio::task_local! {
+    static REFRESH: bool;
+}
+
+/// Caches pages in `dir`, or nothing when `None`.
+pub fn configure(dir: Option<&Path>, ttl: Duration, max_bytes: u64) -> anyhow::Result<()> {
+    let cache = match dir {
+        Some(dir) => Some(Arc::new(Cache::new(dir, ttl, max_bytes)?)),
+        None => None,
+    };
+    *CACHE.write().unwrap() = cache;
+    Ok(())
+}
+
+pub(crate) fn current() -> Option<Arc<Cache>> {
+    CACHE.read().unwrap().clone()
+}
+
+/// Runs `f` revalidating every cached page it fetches, however recent.
+pub async fn refreshing<F: Future>(f: F) -> F::Output {
+    REFRESH.scope(true, f).await
+}
+
+fn forced() -> bool {
+    REFRESH.try_with(|r| *r).unwrap_or(false)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Cache {
+    pub fn new(dir: &Path, ttl: Duration, max_bytes: u64) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let mut size = 0;
+        for file in std::fs::read_dir(dir)? {
+            let meta = file?.metadata()?;
+            if meta.is_file() {
+                size += meta.len();
+            }
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            ttl,
+            max_bytes,
+            size: AtomicU64::new(size),
+            evicting: AtomicBool::new(false),
+        })
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        self.dir.join(format!("{:016x}.json", hasher.finish()))
+    }
+
+    /// The stored page of `url`, if any.
+    pub(crate) async fn get(&self, url: &str) -> Option<Entry> {
+        let data = tokio::fs::read(self.path(url)).await.ok()?;
+        let entry: Entry = serde_json::from_slice(&data).ok()?;
+        // another url with the same hash
+        (entry.url == url).then_some(entry)
+    }
+
+    /// Whether `entry` can be used without asking the site.
+    pub(crate) fn is_fresh(&self, entry: &Entry) -> bool {
+        !forced() && now().saturating_sub(entry.fetched_at) < self.ttl.as_secs()
+    }
+
+    /// Stores the page of `url` unless the site said not to.
+    pub(crate) async fn put(&self, url: &str, headers: &HeaderMap, body: &str) {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(String::from)
+        };
+        if header(CACHE_CONTROL).is_some_and(|v| v.to_ascii_lowercase().contains("no-store")) {
+            return;
+        }
+        self.write(&Entry {
+            url: url.to_string(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            fetched_at: now(),
+            body: body.to_string(),
+        })
+        .await;
+    }
+
+    /// Marks `entry` as just revalidated.
+    pub(crate) async fn touch(&self, mut entry: Entry) {
+        entry.fetched_at = now();
+        self.write(&entry).await;
+    }
+
+    async fn write(&self, entry: &Entry) {
+        let path = self.path(&entry.url);
+        let tmp = path.with_extension("tmp");
+        let res = async {
+            let data = serde_json::to_vec(entry)?;
+            let replaced = match tokio::fs::metadata(&path).await {
+                Ok(meta) => meta.len(),
+                Err(_) => 0,
+            };
+            tokio::fs::write(&tmp, &data).await?;
+            tokio::fs::rename(&tmp, &path).await?;
+            anyhow::Ok((data.len() as u64, replaced))
+        }
+        .await;
+        match res {
+            Ok((written, replaced)) => {
+                // an eviction running meanwhile may have counted this page
+                // already, so the count is only an estimate
+                let update = |size: u64| Some((size + written).saturating_sub(replaced));
+                let size = self
+                    .size
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, update);
+                let size = update(size.unwrap_or_default()).unwrap_or_default();
+                if self.max_bytes > 0 && size > self.max_bytes {
+                    self.evict().await;
+                }
+            }
+            Err(e) => tracing::debug!(url = %entry.url, error = %e, "failed to cache page"),
+        }
+    }
+
+    /// Removes the least recently stored pages until the rest fit well
+    /// within the size limit.
+    async fn evict(&self) {
+        if self.evicting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let res = async {
+            let mut files = vec![];
+            let mut dir = tokio::fs::read_dir(&self.dir).await?;
+            while let Some(file) = dir.next_entry().await? {
+                let meta = file.metadata().await?;
+                if meta.is_file() {
+                    files.push((meta.modified()?, meta.len(), file.path()));
+                }
+            }
+            files.sort();
+
+            let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+            let target = self.max_bytes / 100 * EVICT_TO_PERCENT;
+            let mut evicted = 0;
+            for (_, len, path) in files.iter() {
+                if size <= target {
+                    break;
+                }
+                if tokio::fs::remove_file(path).await.is_ok() {
+                    size -= len;
+                    evicted += 1;
+                }
+            }
+            self.size.store(size, Ordering::SeqCst);
+            tracing::debug!(evicted, size, "evicted cached pages");
+            anyhow::Ok(())
+        }
+        .await;
+        self.evicting.store(false, Ordering::SeqCst);
+        if let Err(e) = res {
+            tracing::debug!(error = %e, "failed to evict cached pages");
+        }
+    }
+
+    /// Removes every stored page.
+    pub async fn clear(&self) -> anyhow::Result<()> {
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(file) = dir.next_entry().await? {
+            if file.file_type().await?.is_file() {
+                tokio::fs::remove_file(file.path()).await?;
+            }
+        }
+        self.size.store(0, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl Entry {
+    /// Adds the headers asking the site to answer 304 if `entry` is current.
+    pub(crate) fn add_validators(&self, headers: &mut HeaderMap) {
+        if let Some(v) = self.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, v);
+        }
+        if let Some(v) = self.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::Ordering,
+        time::{Duration, SystemTime},
+    };
+
+    use reqwest::header::{HeaderMap, CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+
+    use super::{refreshing, Cache};
+
+    #[tokio::test]
+    async fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("cache-test-{}", std::process::id()));
+        let cache = Cache::new(&dir, Duration::from_secs(60), 0).unwrap();
+        let url = "https://rawkuro.net/manga/foo";
+        assert!(cache.get(url).await.is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        cache.put(url, &headers, "<p>v1</p>").await;
+        let entry = cache.get(url).await.unwrap();
+        assert_eq!(entry.body, "<p>v1</p>");
+        assert!(cache.is_fresh(&entry));
+        assert!(!refreshing(async { cache.is_fresh(&entry) }).await);
+        assert!(cache.get("https://rawkuro.net/manga/bar").await.is_none());
+
+        let mut validators = HeaderMap::new();
+        entry.add_validators(&mut validators);
+        assert_eq!(validators[IF_NONE_MATCH], "\"v1\"");
+
+        let stale = Cache::new(&dir, Duration::ZERO, 0).unwrap();
+        assert!(!stale.is_fresh(&entry));
+
+        headers.insert(CACHE_CONTROL, "private, no-store".parse().unwrap());
+        cache.put(url, &headers, "<p>v2</p>").await;
+        assert_eq!(cache.get(url).await.unwrap().body, "<p>v1</p>");
+
+        cache.clear().await.unwrap();
+        assert!(cache.get(url).await.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cache_eviction() {
+        let dir = std::env::temp_dir().join(format!("cache-evict-{}", std::process::id()));
+        let cache = Cache::new(&dir, Duration::from_secs(60), 600).unwrap();
+        let body = "x".repeat(100);
+        let urls = ["https://rawkuro.net/manga/a", "https://rawkuro.net/manga/b"];
+        for url in urls {
+            cache.put(url, &HeaderMap::new(), &body).await;
+        }
+        // make the first page the least recently stored
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        let file = std::fs::File::options()
+            .write(true)
+            .open(cache.path(urls[0]))
+            .unwrap();
+        file.set_modified(old).unwrap();
+
+        // storing the same page again doesn't count twice
+        cache.put(urls[1], &HeaderMap::new(), &body).await;
+        assert!(cache.get(urls[0]).await.is_some());
+
+        cache
+            .put("https://rawkuro.net/manga/c", &HeaderMap::new(), &body)
+            .await;
+        assert!(cache.get(urls[0]).await.is_none());
+        assert!(cache.get(urls[1]).await.is_some());
+        assert!(cache.get("https://rawkuro.net/manga/c").await.is_some());
+        assert!(cache.size.load(Ordering::SeqCst) <= 600);
+
+        // the size is counted again when reopening
+        let reopened = Cache::new(&dir, Duration::from_secs(60), 600).unwrap();
+        assert_eq!(
+            reopened.size.load(Ordering::SeqCst),
+            cache.size.load(Ordering::SeqCst)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
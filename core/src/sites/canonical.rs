@@ -0,0 +1,151 @@
+//! One spelling for every url of a page.
+//!
+//! Sites link the same page in different ways: relative or absolute, with a
+//! fragment or tracking parameters, over http or https. Links are resolved
+//! against the page they were found on and canonicalized, so that the
+//! library doesn't end up with the same manga twice.
+
+use reqwest::Url;
+
+/// Query parameters that only tell where a visitor came from.
+fn is_tracking(key: &str) -> bool {
+    key.starts_with("utm_") || matches!(key, "fbclid" | "gclid")
+}
+
+/// Canonical form of `url`: lowercase scheme and host, no default port,
+/// fragment or tracking parameters. `url` trimmed if it isn't absolute.
+pub fn canonicalize(url: &str) -> String {
+    match Url::parse(url.trim()) {
+        Ok(url) => clean(url),
+        Err(_) => url.trim().to_string(),
+    }
+}
+
+/// `href`, found on the page at `base`, as a canonical absolute url.
+pub fn resolve(base: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    let url = match Url::parse(href) {
+        Ok(url) => url,
+        Err(_) => Url::parse(base).ok()?.join(href).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then(|| clean(url))
+}
+
+fn clean(mut url: Url) -> String {
+    url.set_fragment(None);
+    if url.query().is_some() {
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| !is_tracking(k))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            url.set_query(None);
+        } else if kept.len() < url.query_pairs().count() {
+            url.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+    url.to_string()
+}
+
+/// Spellings of `url` naming the same page: over http or https, with or
+/// without a trailing slash. The canonical form comes first.
+pub fn variants(url: &str) -> Vec<String> {
+    let canonical = canonicalize(url);
+    let Ok(parsed) = Url::parse(&canonical) else {
+        return vec![canonical];
+    };
+
+    let mut paths = vec![parsed.clone()];
+    if parsed.path() != "/" {
+        let mut other = parsed.clone();
+        let path = parsed.path();
+        match path.strip_suffix('/') {
+            Some(trimmed) => other.set_path(trimmed),
+            None => other.set_path(&format!("{}/", path)),
+        }
+        paths.push(other);
+    }
+
+    let mut variants = vec![];
+    for url in paths.iter() {
+        variants.push(url.to_string());
+        let mut other = url.clone();
+        let scheme = match url.scheme() {
+            "http" => "https",
+            "https" => "http",
+            _ => continue,
+        };
+        if other.set_scheme(scheme).is_ok() {
+            variants.push(other.to_string());
+        }
+    }
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize, resolve, variants};
+
+    #[test]
+    fn test_canonicalize() {
+        assert_eq!(
+            canonicalize(" HTTPS://Rawkuro.NET:443/manga/foo#top "),
+            "https://rawkuro.net/manga/foo"
+        );
+        assert_eq!(
+            canonicalize("https://jmanga.org/json/chapter?mode=vertical&id=5531&utm_source=x"),
+            "https://jmanga.org/json/chapter?mode=vertical&id=5531"
+        );
+        assert_eq!(
+            canonicalize("https://mangatop.jp/manga/slime/?fbclid=abc"),
+            "https://mangatop.jp/manga/slime/"
+        );
+        assert_eq!(
+            canonicalize("https://jmanga.org/?q=%E3%83%AF"),
+            "https://jmanga.org/?q=%E3%83%AF"
+        );
+        assert_eq!(canonicalize("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let page = "https://spoilerplus.tv/one-punch-man/";
+        assert_eq!(
+            resolve(page, "chapter-200/").as_deref(),
+            Some("https://spoilerplus.tv/one-punch-man/chapter-200/")
+        );
+        assert_eq!(
+            resolve(page, "/wp-content/uploads/opm.jpg").as_deref(),
+            Some("https://spoilerplus.tv/wp-content/uploads/opm.jpg")
+        );
+        assert_eq!(
+            resolve(page, "//cdn.spoilerplus.tv/1.jpg").as_deref(),
+            Some("https://cdn.spoilerplus.tv/1.jpg")
+        );
+        assert_eq!(
+            resolve(page, "https://rawkuro.net/manga/foo#chapters").as_deref(),
+            Some("https://rawkuro.net/manga/foo")
+        );
+        assert_eq!(resolve(page, "javascript:void(0)"), None);
+        assert_eq!(resolve("not a url", "chapter-200/"), None);
+    }
+
+    #[test]
+    fn test_variants() {
+        assert_eq!(
+            variants("http://rawkuro.net/manga/foo"),
+            vec![
+                "http://rawkuro.net/manga/foo",
+                "https://rawkuro.net/manga/foo",
+                "http://rawkuro.net/manga/foo/",
+                "https://rawkuro.net/manga/foo/",
+            ]
+        );
+        assert_eq!(
+            variants("https://jmanga.org/"),
+            vec!["https://jmanga.org/", "http://jmanga.org/"]
+        );
+        assert_eq!(variants("not a url"), vec!["not a url"]);
+    }
+}
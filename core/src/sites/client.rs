@@ -0,0 +1,411 @@
+//! HTTP clients for the sites, sharing one cookie jar.
+//!
+//! The jar lives for the whole process so that cookies handed over by the
+//! app, such as `cf_clearance` after solving a challenge, are sent by every
+//! client. `Manga` loads it from and saves it to the database.
+//!
+//! Clients are rebuilt once the proxies change, see `SiteClient`.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::bail;
+use cookie_store::{Cookie, CookieStore, RawCookie};
+use lazy_static::lazy_static;
+use reqwest::{header::HeaderValue, IntoUrl, RequestBuilder, Url};
+use tokio::task::JoinHandle;
+
+use crate::{
+    db::{CookieDb, Db},
+    rt,
+};
+
+use super::{proxy, USER_AGENT};
+
+/// How often `CookieSaver` checks the jar for changes.
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref JAR: Arc<CookieJar> = Arc::new(CookieJar::default());
+    static ref CLIENT: SiteClient =
+        SiteClient::new(builder).expect("failed to initialize http client");
+}
+
+/// The cookie jar every client made here uses.
+pub(crate) fn jar() -> Arc<CookieJar> {
+    JAR.clone()
+}
+
+/// Builder with the user agent, the shared cookie jar and the proxies, for
+/// sites that need their own settings such as default headers.
+pub(crate) fn builder() -> reqwest::ClientBuilder {
+    reqwest::ClientBuilder::new()
+        .user_agent(USER_AGENT)
+        .cookie_provider(JAR.clone())
+        .proxy(reqwest::Proxy::custom(proxy::for_url))
+}
+
+/// Client for sites without settings of their own.
+pub(crate) fn shared() -> SiteClient {
+    CLIENT.clone()
+}
+
+type MakeClient = dyn Fn() -> reqwest::ClientBuilder + Send + Sync;
+
+/// A client built anew when the proxies change. A `reqwest::Client` only
+/// picks its proxy when it opens a connection, so pooled keep-alive
+/// connections would otherwise carry on over the old route, or directly.
+#[derive(Clone)]
+pub struct SiteClient {
+    /// `None` for a client given as is, which is never rebuilt.
+    make: Option<Arc<MakeClient>>,
+    /// The client, with the `proxy::generation` it was built for.
+    built: Arc<RwLock<(u64, reqwest::Client)>>,
+}
+
+impl SiteClient {
+    /// Client built by `make`, usually from `builder()`.
+    pub fn new(
+        make: impl Fn() -> reqwest::ClientBuilder + Send + Sync + 'static,
+    ) -> anyhow::Result<Self> {
+        let generation = proxy::generation();
+        let client = make().build()?;
+        Ok(Self {
+            make: Some(Arc::new(make)),
+            built: Arc::new(RwLock::new((generation, client))),
+        })
+    }
+
+    /// The client for the current proxies.
+    pub fn current(&self) -> reqwest::Client {
+        let generation = proxy::generation();
+        {
+            let built = self.built.read().unwrap();
+            if built.0 == generation || self.make.is_none() {
+                return built.1.clone();
+            }
+        }
+        let mut built = self.built.write().unwrap();
+        if let (Some(make), true) = (&self.make, built.0 != generation) {
+            let client = make().build().expect("failed to initialize http client");
+            *built = (generation, client);
+        }
+        built.1.clone()
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.current().get(url)
+    }
+}
+
+impl From<reqwest::Client> for SiteClient {
+    fn from(client: reqwest::Client) -> Self {
+        Self {
+            make: None,
+            built: Arc::new(RwLock::new((0, client))),
+        }
+    }
+}
+
+impl fmt::Debug for SiteClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SiteClient")
+            .field("generation", &self.built.read().unwrap().0)
+            .finish()
+    }
+}
+
+/// Replaces the cookies of the jar with the ones stored in `db`.
+pub(crate) async fn load_cookies(db: &Db) -> anyhow::Result<()> {
+    JAR.load(db.list_cookies().await?);
+    Ok(())
+}
+
+/// Stores the cookies of the jar in `db` if they changed since last time.
+pub(crate) async fn save_cookies(db: &Db) -> anyhow::Result<()> {
+    if !JAR.take_changed() {
+        return Ok(());
+    }
+    if let Err(e) = db.save_cookies(&JAR.rows()).await {
+        JAR.changed.store(true, Ordering::SeqCst);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Saves the jar to the database while it changes, until dropped.
+#[derive(Debug)]
+pub struct CookieSaver(JoinHandle<()>);
+
+impl CookieSaver {
+    pub fn spawn(db: Db) -> Self {
+        Self(rt().spawn(async move {
+            let mut interval = tokio::time::interval(SAVE_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = save_cookies(&db).await {
+                    tracing::warn!(error = %e, "failed to save cookies");
+                }
+            }
+        }))
+    }
+}
+
+impl Drop for CookieSaver {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    store: Mutex<CookieStore>,
+    /// Set when cookies changed since the last `take_changed`.
+    changed: AtomicBool,
+}
+
+impl CookieJar {
+    /// Replaces every cookie with the stored ones. Rows that fail to parse
+    /// are skipped.
+    pub fn load(&self, rows: Vec<CookieDb>) {
+        let cookies = rows.into_iter().filter_map(|row| {
+            match serde_json::from_str::<Cookie<'static>>(&row.data) {
+                Ok(cookie) => Some(Ok::<_, ()>(cookie)),
+                Err(e) => {
+                    tracing::warn!(domain = %row.domain, name = %row.name, error = %e, "skipping stored cookie");
+                    None
+                }
+            }
+        });
+        let store = CookieStore::from_cookies(cookies, false).unwrap_or_default();
+        *self.store.lock().unwrap() = store;
+        self.changed.store(false, Ordering::SeqCst);
+    }
+
+    /// Unexpired cookies, session ones included, as rows to store.
+    pub fn rows(&self) -> Vec<CookieDb> {
+        let store = self.store.lock().unwrap();
+        store
+            .iter_unexpired()
+            .filter_map(|c| {
+                Some(CookieDb {
+                    domain: String::from(&c.domain),
+                    path: String::from(&c.path),
+                    name: c.name().to_string(),
+                    data: serde_json::to_string(c).ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether cookies changed since the last call.
+    pub fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+
+    /// Adds cookies in the form of a `Cookie` header, `a=1; b=2`, which is
+    /// what a WebView gives for `url`. They are sent to the host of `url`
+    /// only, for any path. Returns how many were added.
+    pub fn set_header(&self, url: &Url, header: &str) -> usize {
+        let mut store = self.store.lock().unwrap();
+        let added = header
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .filter(|(name, _)| !name.trim().is_empty())
+            .filter(|(name, value)| {
+                let cookie = format!("{}={}; Path=/", name.trim(), value.trim());
+                store.parse(&cookie, url).is_ok()
+            })
+            .count();
+        self.changed.store(true, Ordering::SeqCst);
+        added
+    }
+
+    /// Imports a Netscape `cookies.txt` file, as exported by browsers and
+    /// curl. Expired cookies are skipped. Returns how many were added.
+    pub fn import_netscape(&self, text: &str) -> anyhow::Result<usize> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut store = self.store.lock().unwrap();
+        let mut added = 0;
+        for (i, line) in text.lines().enumerate() {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                bail!("line {}: expected 7 tab separated fields", i + 1);
+            };
+            let expires: i64 = match expires.parse() {
+                Ok(e) => e,
+                Err(_) => bail!("line {}: invalid expiry {:?}", i + 1, expires),
+            };
+            if expires != 0 && expires <= now {
+                continue;
+            }
+
+            let host = domain.trim_start_matches('.');
+            let url = Url::parse(&format!("https://{}{}", host, path))?;
+            let mut cookie = format!("{}={}; Path={}", name, value, path);
+            if subdomains.eq_ignore_ascii_case("TRUE") {
+                cookie.push_str(&format!("; Domain={}", host));
+            }
+            if expires != 0 {
+                cookie.push_str(&format!("; Max-Age={}", expires - now));
+            }
+            if secure.eq_ignore_ascii_case("TRUE") {
+                cookie.push_str("; Secure");
+            }
+            if http_only {
+                cookie.push_str("; HttpOnly");
+            }
+            if store.parse(&cookie, &url).is_ok() {
+                added += 1;
+            }
+        }
+        self.changed.store(true, Ordering::SeqCst);
+        Ok(added)
+    }
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies: Vec<RawCookie<'static>> = cookie_headers
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|v| RawCookie::parse(v.to_string()).ok())
+            .collect();
+        if cookies.is_empty() {
+            return;
+        }
+        self.store
+            .lock()
+            .unwrap()
+            .store_response_cookies(cookies.into_iter(), url);
+        self.changed.store(true, Ordering::SeqCst);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let store = self.store.lock().unwrap();
+        let header = store
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::{cookie::CookieStore as _, Url};
+
+    use crate::sites::{
+        proxy::{self, ProxyConfig, Settings},
+        testing::{Fixture, FixtureServer},
+    };
+
+    use super::{builder, CookieJar, SiteClient};
+
+    fn header(jar: &CookieJar, url: &str) -> Option<String> {
+        jar.cookies(&Url::parse(url).unwrap())
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_cookie_jar() {
+        let jar = CookieJar::default();
+        let url = Url::parse("https://rawkuro.net/").unwrap();
+        assert_eq!(jar.set_header(&url, "cf_clearance=abc; theme=dark"), 2);
+        assert!(jar.take_changed());
+        assert!(!jar.take_changed());
+        let sent = header(&jar, "https://rawkuro.net/manga/foo").unwrap();
+        assert!(sent.contains("cf_clearance=abc") && sent.contains("theme=dark"));
+        assert_eq!(header(&jar, "https://jmanga.org/"), None);
+
+        let text = "# Netscape HTTP Cookie File\n\
+            .jmanga.org\tTRUE\t/\tTRUE\t0\tsession\t1\n\
+            #HttpOnly_mangatop.jp\tFALSE\t/\tFALSE\t4102444800\ttoken\t2\n\
+            jmanga.org\tFALSE\t/\tFALSE\t1\texpired\t3\n";
+        assert_eq!(jar.import_netscape(text).unwrap(), 2);
+        assert_eq!(
+            header(&jar, "https://www.jmanga.org/").as_deref(),
+            Some("session=1")
+        );
+        assert_eq!(header(&jar, "http://jmanga.org/"), None);
+        assert_eq!(
+            header(&jar, "https://mangatop.jp/").as_deref(),
+            Some("token=2")
+        );
+        assert!(jar.import_netscape("jmanga.org\tTRUE\t/").is_err());
+
+        let copy = CookieJar::default();
+        copy.load(jar.rows());
+        assert_eq!(copy.rows().len(), 4);
+        assert_eq!(
+            header(&copy, "https://mangatop.jp/").as_deref(),
+            Some("token=2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cookie_jar_client() {
+        let mut fixture = Fixture::html("<p></p>");
+        fixture
+            .headers
+            .push(("Set-Cookie".to_string(), "visited=1; Path=/".to_string()));
+        let server = FixtureServer::start(vec![("/", fixture)]).await;
+
+        let jar = Arc::new(CookieJar::default());
+        let client = reqwest::Client::builder()
+            .cookie_provider(jar.clone())
+            .build()
+            .unwrap();
+        client.get(server.url()).send().await.unwrap();
+        assert!(jar.take_changed());
+        assert_eq!(header(&jar, &server.url()).as_deref(), Some("visited=1"));
+    }
+
+    #[tokio::test]
+    async fn test_site_client_rebuilt() {
+        let server = FixtureServer::start(vec![(
+            "http://rebuilt.example/manga",
+            Fixture::html("<p>proxied</p>"),
+        )])
+        .await;
+        let client = SiteClient::new(builder).unwrap();
+        let fixed = SiteClient::from(reqwest::Client::new());
+        let generation = client.built.read().unwrap().0;
+
+        let config = ProxyConfig {
+            url: server.url(),
+            username: None,
+            password: None,
+            no_proxy: vec![],
+        };
+        proxy::set(Settings::new(None, &[(vec!["rebuilt.example".into()], config)]).unwrap());
+        let res = client
+            .get("http://rebuilt.example/manga")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.text().await.unwrap(), "<p>proxied</p>");
+        assert!(client.built.read().unwrap().0 > generation);
+        fixed.current();
+        assert_eq!(fixed.built.read().unwrap().0, 0);
+        proxy::set(Settings::default());
+    }
+}
@@ -0,0 +1,1142 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+
+use crate::rt;
+
+use super::{
+    canonical::resolve, client::{self, SiteClient}, cursor_page, fetch_text, image_headers, manga_page_link, parse_failed, probe, select_text, select_texts, split_names, Link, Listing, MangaDetails, MangaSite,
+    Page, PublicationStatus, RateLimit, SearchPage, SiteCapabilities, USER_AGENT,
+};
+
+/// Site definition loaded from a `.toml` or `.json` file.
+///
+/// Every string template may contain `{base_url}` and `{cdn_url}`, which are
+/// replaced with `base_url` and `cdn_url`. Regex patterns get them escaped.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SiteDefinition {
+    /// Stable id. A definition with the id of a bundled site replaces it.
+    pub id: String,
+    pub name: String,
+    pub base_url: String,
+    /// Host images are served from. Defaults to `base_url`.
+    pub cdn_url: Option<String>,
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Defaults to `RateLimit::default()`.
+    pub rate_limit: Option<RateLimit>,
+    pub search: SearchDefinition,
+    pub browse: Option<BrowseDefinition>,
+    pub chapters: Option<ChaptersDefinition>,
+    pub details: Option<DetailsDefinition>,
+    pub images: Option<ImagesDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchDefinition {
+    /// e.g. `{base_url}/search`
+    pub url: String,
+    /// Url of the following pages, where `{page}` is the page number.
+    /// e.g. `{base_url}/search?page={page}` or `{base_url}/page/{page}/`
+    pub page_url: Option<String>,
+    pub query_key: String,
+    /// Selector matching one element per result.
+    pub item: String,
+    pub title: Extractor,
+    pub href: Extractor,
+    pub image: Option<Extractor>,
+    /// Selector matching the next page link. Without it there is only one page.
+    pub next: Option<String>,
+}
+
+/// Listing urls, parsed with the `search` selectors. A missing url means the
+/// listing isn't supported.
+///
+/// `{page}` is replaced with the page number and `{genre}` with the genre slug.
+/// Without `{page}` a listing has only one page.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BrowseDefinition {
+    /// e.g. `{base_url}/latest?page={page}`
+    pub latest: Option<String>,
+    pub popular: Option<String>,
+    /// e.g. `{base_url}/genre/{genre}?sort=latest&page={page}`
+    pub latest_genre: Option<String>,
+    pub popular_genre: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChaptersDefinition {
+    /// Regex used by `can_handle_chapters`.
+    pub pattern: String,
+    pub item: String,
+    pub title: Extractor,
+    pub href: Extractor,
+}
+
+/// Selectors on the manga page. Every one of them is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DetailsDefinition {
+    pub authors: Option<String>,
+    pub artists: Option<String>,
+    pub genres: Option<String>,
+    pub status: Option<String>,
+    /// Element holding the alternative titles separated by `,` `;` `、` or `/`.
+    pub alt_titles: Option<String>,
+    pub description: Option<String>,
+    /// Element holding the title, for manga opened from a link. The page's
+    /// `og:title` or `<title>` otherwise.
+    pub title: Option<String>,
+    /// `img` of the cover, read from `data-src` or `src`. The page's
+    /// `og:image` otherwise.
+    pub cover: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImagesDefinition {
+    /// Regex used by `can_handle_images`.
+    pub pattern: String,
+    /// Regex run on the chapter page. Its first capture group replaces `{id}` in `url`.
+    pub id_regex: Option<String>,
+    /// Url to fetch the images from. Defaults to the chapter url itself.
+    pub url: Option<String>,
+    /// Name of the JSON field holding the html when the response is JSON.
+    pub json_field: Option<String>,
+    pub item: String,
+    /// Numeric attribute of `item` to sort images by.
+    pub sort_attr: Option<String>,
+    pub src: Extractor,
+    /// Another url of the same image, such as a mirror.
+    pub alternate: Option<Extractor>,
+    pub width: Option<Extractor>,
+    pub height: Option<Extractor>,
+}
+
+/// Picks a string out of an element.
+///
+/// The first element matching `selector` (or the element itself when no
+/// selector is given) is used, and its `attr` (or its text when no attribute
+/// is given) is prefixed with `prefix`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Extractor {
+    pub selector: Option<String>,
+    pub attr: Option<String>,
+    pub prefix: Option<String>,
+}
+
+impl SiteDefinition {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(toml::from_str(&content)?),
+            Some("json") => Ok(serde_json::from_str(&content)?),
+            _ => bail!("unsupported site definition: {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeclarativeSite {
+    client: SiteClient,
+    inner: Arc<Compiled>,
+}
+
+#[derive(Debug)]
+struct Compiled {
+    /// Kept to rebuild the site on other hosts.
+    definition: SiteDefinition,
+    id: String,
+    name: String,
+    headers: HashMap<String, String>,
+    search: CompiledSearch,
+    browse: BrowseDefinition,
+    chapters: Option<CompiledChapters>,
+    details: Option<DetailsDefinition>,
+    images: Option<CompiledImages>,
+}
+
+#[derive(Debug)]
+struct CompiledSearch {
+    url: String,
+    page_url: Option<String>,
+    query_key: String,
+    item: Selector,
+    item_css: String,
+    title: CompiledExtractor,
+    href: CompiledExtractor,
+    image: Option<CompiledExtractor>,
+    next: Option<Selector>,
+}
+
+#[derive(Debug)]
+struct CompiledChapters {
+    pattern: Regex,
+    item: Selector,
+    item_css: String,
+    title: CompiledExtractor,
+    href: CompiledExtractor,
+}
+
+#[derive(Debug)]
+struct CompiledImages {
+    pattern: Regex,
+    id_regex: Option<Regex>,
+    url: Option<String>,
+    json_field: Option<String>,
+    item: Selector,
+    item_css: String,
+    sort_attr: Option<String>,
+    src: CompiledExtractor,
+    alternate: Option<CompiledExtractor>,
+    width: Option<CompiledExtractor>,
+    height: Option<CompiledExtractor>,
+}
+
+#[derive(Debug)]
+struct CompiledExtractor {
+    selector: Option<Selector>,
+    attr: Option<String>,
+    prefix: String,
+    /// Selector the value is read from, reported when it's missing.
+    source: String,
+}
+
+impl CompiledExtractor {
+    /// `item` is the selector of the element the extractor runs on.
+    fn new(def: Extractor, hosts: &Hosts, item: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            selector: def.selector.as_deref().map(parse_selector).transpose()?,
+            source: def.selector.unwrap_or_else(|| item.to_string()),
+            attr: def.attr,
+            prefix: def
+                .prefix
+                .map(|p| hosts.expand(&p))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Extracts a value the result can't do without.
+    fn extract(&self, elem: ElementRef) -> Option<String> {
+        let what = self.attr.as_deref().unwrap_or("text");
+        probe::require(&self.source, what, self.extract_optional(elem))
+    }
+
+    fn extract_optional(&self, elem: ElementRef) -> Option<String> {
+        self.value(elem).map(|v| format!("{}{}", self.prefix, v))
+    }
+
+    fn value(&self, elem: ElementRef) -> Option<String> {
+        let elem = match &self.selector {
+            Some(sel) => elem.select(sel).next()?,
+            None => elem,
+        };
+        let value = match &self.attr {
+            Some(attr) => elem.attr(attr)?.trim().to_string(),
+            None => elem.text().collect::<String>().trim().to_string(),
+        };
+        if value.is_empty() {
+            return None;
+        }
+        Some(value)
+    }
+}
+
+fn parse_selector(s: &str) -> anyhow::Result<Selector> {
+    Selector::parse(s).map_err(|e| anyhow!("invalid selector {:?}: {:?}", s, e))
+}
+
+/// Values of the `{base_url}` and `{cdn_url}` placeholders.
+struct Hosts {
+    base_url: String,
+    cdn_url: String,
+}
+
+impl Hosts {
+    fn pattern(&self, s: &str) -> anyhow::Result<Regex> {
+        let pattern = s
+            .replace("{base_url}", &regex::escape(&self.base_url))
+            .replace("{cdn_url}", &regex::escape(&self.cdn_url));
+        Ok(Regex::new(&pattern)?)
+    }
+
+    fn expand(&self, template: &str) -> String {
+        template
+            .replace("{base_url}", &self.base_url)
+            .replace("{cdn_url}", &self.cdn_url)
+    }
+}
+
+impl DeclarativeSite {
+    pub fn new(def: SiteDefinition) -> anyhow::Result<Self> {
+        let definition = def.clone();
+        let base_url = def.base_url.trim_end_matches('/').to_string();
+        let hosts = Hosts {
+            cdn_url: def
+                .cdn_url
+                .as_deref()
+                .map(|u| u.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| base_url.clone()),
+            base_url,
+        };
+
+        let mut headers = HeaderMap::new();
+        for (k, v) in def.headers.iter() {
+            headers.insert(HeaderName::try_from(k)?, HeaderValue::try_from(v)?);
+        }
+        let user_agent = def.user_agent.clone().unwrap_or_else(|| USER_AGENT.to_string());
+        let client = SiteClient::new(move || {
+            client::builder()
+                .user_agent(user_agent.clone())
+                .default_headers(headers.clone())
+        })?;
+
+        let search = CompiledSearch {
+            url: hosts.expand(&def.search.url),
+            page_url: def.search.page_url.map(|u| hosts.expand(&u)),
+            query_key: def.search.query_key,
+            item: parse_selector(&def.search.item)?,
+            title: CompiledExtractor::new(def.search.title, &hosts, &def.search.item)?,
+            href: CompiledExtractor::new(def.search.href, &hosts, &def.search.item)?,
+            image: def
+                .search
+                .image
+                .map(|i| CompiledExtractor::new(i, &hosts, &def.search.item))
+                .transpose()?,
+            item_css: def.search.item,
+            next: def.search.next.as_deref().map(parse_selector).transpose()?,
+        };
+
+        let browse = match def.browse {
+            Some(b) => {
+                let expand = |u: Option<String>| u.map(|u| hosts.expand(&u));
+                BrowseDefinition {
+                    latest: expand(b.latest),
+                    popular: expand(b.popular),
+                    latest_genre: expand(b.latest_genre),
+                    popular_genre: expand(b.popular_genre),
+                }
+            }
+            None => BrowseDefinition::default(),
+        };
+
+        let chapters = match def.chapters {
+            Some(c) => Some(CompiledChapters {
+                pattern: hosts.pattern(&c.pattern)?,
+                item: parse_selector(&c.item)?,
+                title: CompiledExtractor::new(c.title, &hosts, &c.item)?,
+                href: CompiledExtractor::new(c.href, &hosts, &c.item)?,
+                item_css: c.item,
+            }),
+            None => None,
+        };
+
+        if let Some(d) = &def.details {
+            for sel in [
+                &d.authors,
+                &d.artists,
+                &d.genres,
+                &d.status,
+                &d.alt_titles,
+                &d.description,
+                &d.title,
+                &d.cover,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                parse_selector(sel)?;
+            }
+        }
+
+        let images = match def.images {
+            Some(i) => Some(CompiledImages {
+                pattern: hosts.pattern(&i.pattern)?,
+                id_regex: i.id_regex.as_deref().map(Regex::new).transpose()?,
+                url: i.url.map(|u| hosts.expand(&u)),
+                json_field: i.json_field,
+                item: parse_selector(&i.item)?,
+                sort_attr: i.sort_attr,
+                src: CompiledExtractor::new(i.src, &hosts, &i.item)?,
+                alternate: i
+                    .alternate
+                    .map(|x| CompiledExtractor::new(x, &hosts, &i.item))
+                    .transpose()?,
+                width: i
+                    .width
+                    .map(|x| CompiledExtractor::new(x, &hosts, &i.item))
+                    .transpose()?,
+                height: i
+                    .height
+                    .map(|x| CompiledExtractor::new(x, &hosts, &i.item))
+                    .transpose()?,
+                item_css: i.item,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            client,
+            inner: Arc::new(Compiled {
+                definition,
+                id: def.id,
+                name: def.name,
+                headers: def.headers,
+                search,
+                browse,
+                chapters,
+                details: def.details,
+                images,
+            }),
+        })
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let def = SiteDefinition::from_file(path)?;
+        Self::new(def).with_context(|| format!("invalid site definition: {}", path.display()))
+    }
+
+    /// Loads every `.toml` and `.json` definition in `dir`, sorted by file name.
+    /// Broken definitions are logged and skipped, and a missing `dir` has no
+    /// definitions.
+    pub fn load_dir(dir: &Path) -> anyhow::Result<Vec<Self>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                tracing::info!(dir = %dir.display(), "no site definitions directory");
+                return Ok(vec![]);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("toml") | Some("json")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        let sites = paths
+            .iter()
+            .filter_map(|path| match Self::from_file(path) {
+                Ok(site) => Some(site),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "failed to load site definition");
+                    None
+                }
+            })
+            .collect();
+        Ok(sites)
+    }
+
+    /// Parses the search or listing page at `url`. Only a `paged` one can
+    /// have more pages. With a chapters pattern, links it doesn't match, such
+    /// as genres or pagination, are left out.
+    fn parse_search(&self, html: &str, url: &str, page: u32, paged: bool) -> SearchPage {
+        let search = &self.inner.search;
+        let doc = Html::parse_document(html);
+        probe::matches(&search.item_css, || doc.select(&search.item).count());
+        let links = doc
+            .select(&search.item)
+            .filter_map(|item| {
+                let text = search.title.extract(item)?;
+                let href = resolve(url, &search.href.extract(item)?)?;
+                let image = search
+                    .image
+                    .as_ref()
+                    .and_then(|i| resolve(url, &i.extract_optional(item)?));
+                Some(Link {
+                    text,
+                    url: href,
+                    image,
+                })
+            })
+            .filter(|link| {
+                self.inner
+                    .chapters
+                    .as_ref()
+                    .is_none_or(|c| c.pattern.is_match(&link.url))
+            })
+            .collect();
+        let has_more = paged
+            && search
+                .next
+                .as_ref()
+                .is_some_and(|next| doc.select(next).next().is_some());
+        SearchPage::numbered(links, page, has_more)
+    }
+
+    /// Parses the manga page at `url`.
+    fn parse_chapters(&self, html: &str, url: &str) -> Vec<Link> {
+        let chapters = match &self.inner.chapters {
+            Some(c) => c,
+            None => return vec![],
+        };
+        let doc = Html::parse_document(html);
+        probe::matches(&chapters.item_css, || doc.select(&chapters.item).count());
+        doc.select(&chapters.item)
+            .filter_map(|item| {
+                let text = chapters.title.extract(item)?;
+                let href = resolve(url, &chapters.href.extract(item)?)?;
+                Some(Link {
+                    text,
+                    url: href,
+                    image: None,
+                })
+            })
+            .collect()
+    }
+
+    fn parse_details(&self, html: &str) -> MangaDetails {
+        let details = match &self.inner.details {
+            Some(d) => d,
+            None => return MangaDetails::default(),
+        };
+        let doc = Html::parse_document(html);
+        let texts = |sel: &Option<String>| {
+            sel.as_deref()
+                .map(|s| select_texts(&doc, s))
+                .unwrap_or_default()
+        };
+        let text = |sel: &Option<String>| sel.as_deref().and_then(|s| select_text(&doc, s));
+
+        MangaDetails {
+            authors: texts(&details.authors),
+            artists: texts(&details.artists),
+            genres: texts(&details.genres),
+            status: text(&details.status)
+                .map(|s| PublicationStatus::parse(&s))
+                .unwrap_or_default(),
+            alt_titles: text(&details.alt_titles)
+                .map(|s| split_names(&s))
+                .unwrap_or_default(),
+            description: text(&details.description),
+        }
+    }
+
+    /// Parses the title and cover out of the manga page at `url`.
+    fn parse_manga_link(&self, html: &str, url: &str) -> Option<Link> {
+        let doc = Html::parse_document(html);
+        let details = self.inner.details.as_ref();
+        let title = details
+            .and_then(|d| d.title.as_deref())
+            .and_then(|s| select_text(&doc, s));
+        let cover = details.and_then(|d| d.cover.as_deref()).and_then(|s| {
+            let sel = Selector::parse(s).ok()?;
+            let img = doc.select(&sel).next()?;
+            img.attr("data-src").or(img.attr("src"))
+        });
+        manga_page_link(&doc, url, title, cover)
+    }
+
+    /// Parses the images of a chapter out of `body`, fetched from `url`.
+    fn parse_images(
+        &self,
+        body: &str,
+        url: &str,
+        headers: &HashMap<String, String>,
+    ) -> anyhow::Result<Vec<Page>> {
+        let images = match &self.inner.images {
+            Some(i) => i,
+            None => return Ok(vec![]),
+        };
+
+        let json;
+        let html = match &images.json_field {
+            Some(field) => {
+                json = serde_json::from_str::<serde_json::Value>(body)?;
+                match json.get(field).and_then(|v| v.as_str()) {
+                    Some(s) => s,
+                    None => bail!("invalid response: no {} field", field),
+                }
+            }
+            None => body,
+        };
+
+        let doc = Html::parse_fragment(html);
+        probe::matches(&images.item_css, || doc.select(&images.item).count());
+        let mut items: Vec<(Option<u64>, ElementRef)> = doc
+            .select(&images.item)
+            .map(|e| {
+                let index = images
+                    .sort_attr
+                    .as_ref()
+                    .and_then(|a| e.attr(a))
+                    .and_then(|v| v.parse::<u64>().ok());
+                (index, e)
+            })
+            .collect();
+        if images.sort_attr.is_some() {
+            items.sort_by_key(|(index, _)| *index);
+        }
+
+        let base = url;
+        let optional = |extractor: &Option<CompiledExtractor>, e| {
+            extractor.as_ref().and_then(|x| x.extract_optional(e))
+        };
+        let size = |extractor: &Option<CompiledExtractor>, e| {
+            optional(extractor, e).and_then(|v| v.parse::<u32>().ok())
+        };
+        Ok(items
+            .into_iter()
+            .filter_map(|(_, e)| {
+                let url = resolve(base, &images.src.extract(e)?)?;
+                let alternates = optional(&images.alternate, e)
+                    .and_then(|alt| resolve(base, &alt))
+                    .filter(|alt| *alt != url)
+                    .into_iter()
+                    .collect();
+                Some((url, alternates, e))
+            })
+            .enumerate()
+            .map(|(index, (url, alternates, e))| Page {
+                index: index as u32,
+                url,
+                headers: headers.clone(),
+                width: size(&images.width, e),
+                height: size(&images.height, e),
+                alternates,
+                descramble: None,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl MangaSite for DeclarativeSite {
+    fn id(&self) -> String {
+        self.inner.id.clone()
+    }
+
+    fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    fn base_url(&self) -> String {
+        self.inner.definition.base_url.trim_end_matches('/').to_string()
+    }
+
+    fn cdn_url(&self) -> Option<String> {
+        self.inner.definition.cdn_url.clone()
+    }
+
+    fn rate_limit(&self) -> RateLimit {
+        self.inner.definition.rate_limit.unwrap_or_default()
+    }
+
+    fn relocate(&self, base_url: &str, cdn_url: Option<&str>) -> Option<Arc<dyn MangaSite>> {
+        let mut def = self.inner.definition.clone();
+        def.base_url = base_url.to_string();
+        if let Some(cdn_url) = cdn_url {
+            def.cdn_url = Some(cdn_url.to_string());
+        }
+        match Self::new(def) {
+            Ok(site) => Some(Arc::new(site)),
+            Err(e) => {
+                tracing::warn!(id = %self.inner.id, error = %e, "failed to relocate site");
+                None
+            }
+        }
+    }
+
+    fn request_headers(&self) -> HashMap<String, String> {
+        self.inner.headers.clone()
+    }
+
+    async fn search(&self, text: String, cursor: Option<String>) -> anyhow::Result<SearchPage> {
+        let copy = self.clone();
+        let page = cursor_page(cursor.as_deref())?;
+        rt().spawn(probe::inherit(async move {
+            let search = &copy.inner.search;
+            let url = match (&search.page_url, page) {
+                (Some(page_url), 2..) => page_url.replace("{page}", &page.to_string()),
+                (None, 2..) => bail!("{} has no more pages", copy.inner.name),
+                _ => search.url.clone(),
+            };
+            let res = fetch_text(
+                &copy.inner.id,
+                copy.client
+                    .get(&url)
+                    .query(&[(search.query_key.as_str(), text.as_str())]),
+            )
+            .await?;
+            Ok(copy.parse_search(&res, &url, page, search.page_url.is_some()))
+        }))
+        .await?
+    }
+
+    fn capabilities(&self) -> SiteCapabilities {
+        let browse = &self.inner.browse;
+        let mut listings = vec![];
+        if browse.latest.is_some() {
+            listings.push(Listing::Latest);
+        }
+        if browse.popular.is_some() {
+            listings.push(Listing::Popular);
+        }
+        SiteCapabilities {
+            listings,
+            genre_filter: browse.latest_genre.is_some() || browse.popular_genre.is_some(),
+        }
+    }
+
+    async fn browse(
+        &self,
+        listing: Listing,
+        genre: Option<String>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<SearchPage> {
+        self.capabilities().check(&self.name(), listing, genre.as_deref())?;
+        let browse = &self.inner.browse;
+        let template = match (listing, &genre) {
+            (Listing::Latest, None) => &browse.latest,
+            (Listing::Popular, None) => &browse.popular,
+            (Listing::Latest, Some(_)) => &browse.latest_genre,
+            (Listing::Popular, Some(_)) => &browse.popular_genre,
+        };
+        let template = match template {
+            Some(t) => t.clone(),
+            None => bail!("{} does not support {:?} listing by genre", self.name(), listing),
+        };
+        let page = cursor_page(cursor.as_deref())?;
+        let paged = template.contains("{page}");
+        if page > 1 && !paged {
+            bail!("{} has no more pages", self.name());
+        }
+
+        let copy = self.clone();
+        rt().spawn(probe::inherit(async move {
+            let url = template
+                .replace("{page}", &page.to_string())
+                .replace("{genre}", genre.as_deref().unwrap_or_default());
+            let res = fetch_text(&copy.inner.id, copy.client.get(&url)).await?;
+            Ok(copy.parse_search(&res, &url, page, paged))
+        }))
+        .await?
+    }
+
+    fn can_handle_chapters(&self, url: &str) -> bool {
+        match &self.inner.chapters {
+            Some(c) => c.pattern.is_match(url),
+            None => false,
+        }
+    }
+
+    async fn chapters(&self, url: &str) -> anyhow::Result<Vec<Link>> {
+        let res = fetch_text(&self.inner.id, self.client.get(url)).await?;
+        Ok(self.parse_chapters(&res, url))
+    }
+
+    async fn details(&self, url: &str) -> anyhow::Result<MangaDetails> {
+        if self.inner.details.is_none() {
+            return Ok(MangaDetails::default());
+        }
+        let res = fetch_text(&self.inner.id, self.client.get(url)).await?;
+        Ok(self.parse_details(&res))
+    }
+
+    async fn manga_link(&self, url: &str) -> anyhow::Result<Link> {
+        let res = fetch_text(&self.inner.id, self.client.get(url)).await?;
+        self.parse_manga_link(&res, url)
+            .ok_or_else(|| parse_failed(&self.inner.id, url, "no title"))
+    }
+
+    fn can_handle_images(&self, url: &str) -> bool {
+        match &self.inner.images {
+            Some(i) => i.pattern.is_match(url),
+            None => false,
+        }
+    }
+
+    async fn images(&self, url: &str) -> anyhow::Result<Vec<Page>> {
+        let images = match &self.inner.images {
+            Some(i) => i,
+            None => return Ok(vec![]),
+        };
+        let headers = image_headers(self, url);
+
+        let mut res = fetch_text(&self.inner.id, self.client.get(url)).await?;
+
+        if let Some(template) = &images.url {
+            let target = match &images.id_regex {
+                Some(re) => {
+                    let id = match re.captures(&res).and_then(|c| c.get(1)) {
+                        Some(m) => m.as_str().to_string(),
+                        None => {
+                            return Err(parse_failed(&self.inner.id, url, "no chapter id found"))
+                        }
+                    };
+                    template.replace("{id}", &id)
+                }
+                None => template.clone(),
+            };
+            res = fetch_text(&self.inner.id, self.client.get(&target)).await?;
+            return self
+                .parse_images(&res, &target, &headers)
+                .map_err(|e| parse_failed(&self.inner.id, &target, e));
+        }
+
+        self.parse_images(&res, url, &headers)
+            .map_err(|e| parse_failed(&self.inner.id, url, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{DeclarativeSite, SiteDefinition};
+    use crate::sites::{
+        testing::{Fixture, FixtureServer},
+        Listing, MangaSite, PublicationStatus, RateLimit,
+    };
+
+    const RAWKURO: &str = r##"
+id = "rawkuronet"
+name = "rawkuro.net"
+base_url = "https://rawkuro.net"
+
+[headers]
+Referer = "https://rawkuro.net/"
+
+[search]
+url = "{base_url}/search"
+page_url = "{base_url}/search?page={page}"
+next = ".pagination a[rel=next]"
+query_key = "keyword"
+item = "#main a"
+title = { attr = "title" }
+href = { attr = "href" }
+image = { selector = "img", attr = "data-src", prefix = "{base_url}" }
+
+[browse]
+latest = "{base_url}/latest-updated?page={page}"
+latest_genre = "{base_url}/genre/{genre}?sort=latest-updated"
+
+[chapters]
+pattern = "^{base_url}/manga/[^/]+$"
+item = "#myUL li a"
+title = {}
+href = { attr = "href" }
+
+[details]
+authors = ".manga-info .author a"
+genres = ".manga-info .genres a"
+status = ".manga-info .status"
+alt_titles = ".manga-info .other-name"
+description = ".manga-summary"
+
+[images]
+pattern = "^{base_url}/manga/[^/]+/[^/]+$"
+id_regex = 'const\s+CHAPTER_ID\s+=\s+(\d+);'
+url = "{base_url}/ajax/image/list/chap/{id}"
+json_field = "html"
+item = ".separator"
+sort_attr = "data-index"
+src = { selector = ".readImg", attr = "href" }
+"##;
+
+    const JMANGA: &str = r##"{
+  "id": "jmangaorg",
+  "name": "jmanga.org",
+  "base_url": "https://jmanga.org/",
+  "search": {
+    "url": "{base_url}/",
+    "query_key": "q",
+    "item": ".manga_list-sbs .item",
+    "title": { "selector": ".manga-name a" },
+    "href": { "selector": ".manga-name a", "attr": "href" },
+    "image": { "selector": ".manga-poster img", "attr": "data-src" }
+  }
+}"##;
+
+    fn rawkuro() -> DeclarativeSite {
+        DeclarativeSite::new(toml::from_str::<SiteDefinition>(RAWKURO).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_declarative_toml() {
+        let s = rawkuro();
+
+        assert_eq!(s.id(), "rawkuronet");
+        assert_eq!(s.name(), "rawkuro.net");
+        assert_eq!(
+            s.request_headers().get("Referer").map(String::as_str),
+            Some("https://rawkuro.net/")
+        );
+        assert!(s.can_handle_chapters("https://rawkuro.net/manga/foo"));
+        assert!(!s.can_handle_chapters("https://rawkuro.net/manga/foo/1"));
+        assert!(!s.can_handle_chapters("https://rawkuroXnet/manga/foo"));
+        assert!(s.can_handle_images("https://rawkuro.net/manga/foo/1"));
+    }
+
+    #[test]
+    fn test_declarative_relocate() {
+        let s = rawkuro();
+        assert_eq!(s.base_url(), "https://rawkuro.net");
+        assert_eq!(s.cdn_url(), None);
+
+        let moved = s.relocate("https://rawkuro.com/", None).unwrap();
+        assert_eq!(moved.id(), "rawkuronet");
+        assert_eq!(moved.base_url(), "https://rawkuro.com");
+        assert!(moved.can_handle_chapters("https://rawkuro.com/manga/foo"));
+        assert!(!moved.can_handle_chapters("https://rawkuro.net/manga/foo"));
+        // the original is left as it was
+        assert!(s.can_handle_chapters("https://rawkuro.net/manga/foo"));
+
+        let mut def: SiteDefinition = toml::from_str(RAWKURO).unwrap();
+        def.images.as_mut().unwrap().url = Some("{cdn_url}/list/{id}".into());
+        let s = DeclarativeSite::new(def).unwrap();
+        let moved = s.relocate("https://rawkuro.com", Some("https://cdn.rawkuro.com")).unwrap();
+        assert_eq!(moved.cdn_url().as_deref(), Some("https://cdn.rawkuro.com"));
+    }
+
+    #[test]
+    fn test_declarative_rate_limit() {
+        assert_eq!(rawkuro().rate_limit(), RateLimit::default());
+
+        let text = format!("{}\n[rate_limit]\nrequests_per_sec = 0.5\n", RAWKURO);
+        let s = DeclarativeSite::new(toml::from_str(&text).unwrap()).unwrap();
+        assert_eq!(
+            s.rate_limit(),
+            RateLimit {
+                requests_per_sec: 0.5,
+                ..RateLimit::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_declarative_load_dir() {
+        let dir = std::env::temp_dir().join(format!("sites-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("rawkuro.toml"), RAWKURO).unwrap();
+        std::fs::write(dir.join("broken.toml"), "id = ").unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        let sites = DeclarativeSite::load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].id(), "rawkuronet");
+
+        assert!(DeclarativeSite::load_dir(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_declarative_json() {
+        let def: SiteDefinition = serde_json::from_str(JMANGA).unwrap();
+        let s = DeclarativeSite::new(def).unwrap();
+
+        let html = r#"
+<div class="manga_list-sbs">
+  <div class="item">
+    <div class="manga-poster"><img data-src="https://img.jmanga.org/1.jpg"></div>
+    <h3 class="manga-name"><a href="https://jmanga.org/read/one/">One</a></h3>
+  </div>
+  <div class="item"><h3 class="manga-name">no link</h3></div>
+</div>"#;
+        let page = s.parse_search(html, "https://jmanga.org/?q=one", 1, false);
+        assert!(!page.has_more);
+        let links = page.links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "One");
+        assert_eq!(links[0].url, "https://jmanga.org/read/one/");
+        assert_eq!(links[0].image.as_deref(), Some("https://img.jmanga.org/1.jpg"));
+
+        assert!(!s.can_handle_chapters("https://jmanga.org/read/one/"));
+        assert!(s.parse_chapters(html, "https://jmanga.org/read/one/").is_empty());
+        assert!(s.capabilities().listings.is_empty());
+    }
+
+    #[test]
+    fn test_declarative_parse() {
+        let s = rawkuro();
+
+        let page = s.parse_search(
+            r#"<div id="main"><a title="Foo" href="https://rawkuro.net/manga/foo"><img data-src="/foo.jpg"></a>
+<a title="Fantasy" href="/genre/fantasy">Fantasy</a></div>
+<ul class="pagination"><li><a rel="next" href="/search?page=3">next</a></li></ul>"#,
+            "https://rawkuro.net/search?page=2",
+            2,
+            true,
+        );
+        assert!(page.has_more);
+        assert_eq!(page.next_cursor.as_deref(), Some("3"));
+        let links = page.links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].image.as_deref(), Some("https://rawkuro.net/foo.jpg"));
+
+        let chapters = s.parse_chapters(
+            r#"<ul id="myUL"><li><a href="https://rawkuro.net/manga/foo/2"> 第2話 </a></li>
+<li><a href="foo/1#top"> 第1話 </a></li></ul>"#,
+            "https://rawkuro.net/manga/foo",
+        );
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].text, "第2話");
+        assert_eq!(chapters[1].url, "https://rawkuro.net/manga/foo/1");
+
+        let json = serde_json::json!({
+            "html": r#"<div class="separator" data-index="2"><a class="readImg" href="b.jpg"></a></div>
+<div class="separator" data-index="1"><a class="readImg" href="a.jpg"></a></div>"#
+        });
+        let headers = HashMap::from([("Referer".to_string(), "https://rawkuro.net/".to_string())]);
+        let list_url = "https://rawkuro.net/ajax/image/list/chap/1";
+        let images = s.parse_images(&json.to_string(), list_url, &headers).unwrap();
+        let urls: Vec<&str> = images.iter().map(|p| p.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://rawkuro.net/ajax/image/list/chap/a.jpg",
+                "https://rawkuro.net/ajax/image/list/chap/b.jpg",
+            ]
+        );
+        assert_eq!(images[1].index, 1);
+        assert_eq!(images[1].headers, headers);
+
+        assert!(s.parse_images("{}", list_url, &headers).is_err());
+    }
+
+    #[test]
+    fn test_declarative_manga_link() {
+        let mut def: SiteDefinition = toml::from_str(RAWKURO).unwrap();
+        let details = def.details.as_mut().unwrap();
+        details.cover = Some(".cover img".into());
+        let s = DeclarativeSite::new(def).unwrap();
+
+        let url = "https://rawkuro.net/manga/foo";
+        let link = s
+            .parse_manga_link(
+                r#"<head><title>Foo - Rawkuro</title></head>
+<div class="cover"><img data-src="/covers/foo.jpg"></div>"#,
+                url,
+            )
+            .unwrap();
+        assert_eq!(link.text, "Foo");
+        assert_eq!(link.url, url);
+        assert_eq!(link.image.as_deref(), Some("https://rawkuro.net/covers/foo.jpg"));
+
+        let link = s
+            .parse_manga_link(
+                r#"<head><meta property="og:title" content="Foo Bar">
+<meta property="og:image" content="https://img.rawkuro.net/foo.jpg"></head>"#,
+                url,
+            )
+            .unwrap();
+        assert_eq!(link.text, "Foo Bar");
+        assert_eq!(link.image.as_deref(), Some("https://img.rawkuro.net/foo.jpg"));
+        assert!(s.parse_manga_link("<p>nothing</p>", url).is_none());
+    }
+
+    #[test]
+    fn test_declarative_page_fields() {
+        let mut def: SiteDefinition = toml::from_str(RAWKURO).unwrap();
+        let images = def.images.as_mut().unwrap();
+        images.alternate = Some(toml::from_str(r#"selector = "img"
+attr = "data-src""#).unwrap());
+        images.width = Some(toml::from_str(r#"selector = "img"
+attr = "width""#).unwrap());
+        let s = DeclarativeSite::new(def).unwrap();
+
+        let json = serde_json::json!({
+            "html": r#"<div class="separator" data-index="1"><a class="readImg" href="a.jpg"><img data-src="mirror/a.jpg" width="800"></a></div>
+<div class="separator" data-index="2"><a class="readImg" href="b.jpg"><img data-src="b.jpg" width="auto"></a></div>"#
+        });
+        let pages = s
+            .parse_images(&json.to_string(), "https://rawkuro.net/chap/", &HashMap::new())
+            .unwrap();
+        assert_eq!(pages[0].alternates, vec!["https://rawkuro.net/chap/mirror/a.jpg"]);
+        assert_eq!(pages[0].width, Some(800));
+        assert_eq!(pages[0].height, None);
+        assert!(pages[1].alternates.is_empty());
+        assert_eq!(pages[1].width, None);
+    }
+
+    #[tokio::test]
+    async fn test_declarative_fixture() {
+        let server = FixtureServer::start(vec![
+            ("/search", Fixture::html(include_str!("../../fixtures/rawkuronet/search.html"))),
+            ("/latest-updated", Fixture::html(include_str!("../../fixtures/rawkuronet/search.html"))),
+            ("/genre/fantasy", Fixture::html(include_str!("../../fixtures/rawkuronet/search.html"))),
+            (
+                "/manga/isekai-nonbiri-nouka",
+                Fixture::html(include_str!("../../fixtures/rawkuronet/manga.html")),
+            ),
+            (
+                "/manga/isekai-nonbiri-nouka/chapter-251",
+                Fixture::html(include_str!("../../fixtures/rawkuronet/chapter.html")),
+            ),
+            (
+                "/ajax/image/list/chap/88231",
+                Fixture::json(include_str!("../../fixtures/rawkuronet/images.json")),
+            ),
+        ])
+        .await;
+        let mut def: SiteDefinition = toml::from_str(RAWKURO).unwrap();
+        def.base_url = server.url();
+        let s = DeclarativeSite::new(def).unwrap();
+
+        let page = s.search("異世界".into(), None).await.unwrap();
+        assert_eq!(page.next_cursor.as_deref(), Some("2"));
+        let links = page.links;
+        // the genre link doesn't match the chapters pattern
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].url, format!("{}/manga/isekai-nonbiri-nouka", server.url()));
+
+        let chapters = s.chapters(&links[0].url).await.unwrap();
+        assert_eq!(chapters.len(), 3);
+
+        let details = s.details(&links[0].url).await.unwrap();
+        assert_eq!(details.authors, vec!["内藤騎之介"]);
+        assert!(details.artists.is_empty());
+        assert_eq!(details.status, PublicationStatus::Ongoing);
+        assert!(details.description.is_some());
+
+        let images = s.images(&chapters[0].url).await.unwrap();
+        assert_eq!(images.len(), 3);
+        assert_eq!(images[0].url, "https://img.rawkuro.net/88231/001.jpg");
+        assert_eq!(images[0].headers["Referer"], "https://rawkuro.net/");
+
+        let caps = s.capabilities();
+        assert_eq!(caps.listings, vec![Listing::Latest]);
+        assert!(caps.genre_filter);
+
+        let page = s.browse(Listing::Latest, None, None).await.unwrap();
+        assert_eq!(page.links.len(), 2);
+        assert!(page.has_more);
+        let page = s
+            .browse(Listing::Latest, Some("fantasy".into()), None)
+            .await
+            .unwrap();
+        // the genre listing has no {page}, so it is a single page
+        assert!(!page.has_more);
+        assert!(s
+            .browse(Listing::Latest, Some("fantasy".into()), Some("2".into()))
+            .await
+            .is_err());
+        assert!(s.browse(Listing::Popular, None, None).await.is_err());
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[requests.len() - 2..],
+            ["/latest-updated?page=1", "/genre/fantasy?sort=latest-updated"]
+        );
+    }
+
+    #[test]
+    fn test_declarative_invalid() {
+        let def: SiteDefinition =
+            toml::from_str(&RAWKURO.replace("#main a", "#main >>> a")).unwrap();
+        assert!(DeclarativeSite::new(def).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! Self-test running a site through search, chapters and images.
+
+use std::{future::Future, sync::Arc, time::Instant};
+
+use super::{
+    probe::{MissingAttr, Probe, RequestReport, SelectorReport},
+    MangaSite,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticStage {
+    Search,
+    Chapters,
+    Images,
+}
+
+#[derive(Debug)]
+pub struct StageReport {
+    pub stage: DiagnosticStage,
+    /// Search text or url the stage ran on.
+    pub input: String,
+    pub requests: Vec<RequestReport>,
+    pub selectors: Vec<SelectorReport>,
+    pub missing: Vec<MissingAttr>,
+    /// Number of links or images the stage produced.
+    pub results: u64,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SiteDiagnosis {
+    pub site_id: String,
+    /// Stages that ran, in order. A stage with nothing to pass on ends the run.
+    pub stages: Vec<StageReport>,
+}
+
+/// Searches `query`, then opens the first manga found and its first chapter.
+pub async fn diagnose(site: Arc<dyn MangaSite>, query: String) -> SiteDiagnosis {
+    let mut stages = vec![];
+
+    let (report, links) = stage(
+        DiagnosticStage::Search,
+        query.clone(),
+        |r: &Vec<_>| r.len(),
+        {
+            let site = site.clone();
+            async move { site.search(query, None).await.map(|p| p.links) }
+        },
+    )
+    .await;
+    stages.push(report);
+
+    let manga = links
+        .unwrap_or_default()
+        .into_iter()
+        .find(|l| site.can_handle_chapters(&l.url));
+    if let Some(manga) = manga {
+        let (report, chapters) = stage(
+            DiagnosticStage::Chapters,
+            manga.url.clone(),
+            |r: &Vec<_>| r.len(),
+            {
+                let site = site.clone();
+                async move { site.chapters(&manga.url).await }
+            },
+        )
+        .await;
+        stages.push(report);
+
+        let chapter = chapters
+            .unwrap_or_default()
+            .into_iter()
+            .find(|l| site.can_handle_images(&l.url));
+        if let Some(chapter) = chapter {
+            let (report, _) = stage(
+                DiagnosticStage::Images,
+                chapter.url.clone(),
+                |r: &Vec<_>| r.len(),
+                {
+                    let site = site.clone();
+                    async move { site.images(&chapter.url).await }
+                },
+            )
+            .await;
+            stages.push(report);
+        }
+    }
+
+    SiteDiagnosis {
+        site_id: site.id(),
+        stages,
+    }
+}
+
+async fn stage<T>(
+    stage: DiagnosticStage,
+    input: String,
+    count: impl FnOnce(&T) -> usize,
+    f: impl Future<Output = anyhow::Result<T>>,
+) -> (StageReport, Option<T>) {
+    let probe = Arc::new(Probe::default());
+    let start = Instant::now();
+    let res = probe.scope(f).await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let recorded = probe.take();
+
+    let mut report = StageReport {
+        stage,
+        input,
+        requests: recorded.requests,
+        selectors: recorded.selectors,
+        missing: recorded.missing,
+        results: 0,
+        elapsed_ms,
+        error: None,
+    };
+    match res {
+        Ok(value) => {
+            report.results = count(&value) as u64;
+            (report, Some(value))
+        }
+        Err(e) => {
+            report.error = Some(e.to_string());
+            (report, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::sites::{
+        rawkuronet::RawkuroNet,
+        testing::{Fixture, FixtureServer},
+    };
+
+    use super::{diagnose, DiagnosticStage};
+
+    #[tokio::test]
+    async fn test_diagnose() {
+        let server = FixtureServer::start(vec![
+            (
+                "/search",
+                Fixture::html(include_str!("../../fixtures/rawkuronet/search.html")),
+            ),
+            (
+                "/manga/isekai-nonbiri-nouka",
+                Fixture::html(include_str!("../../fixtures/rawkuronet/manga.html")),
+            ),
+            (
+                "/manga/isekai-nonbiri-nouka/chapter-251",
+                Fixture::html(include_str!("../../fixtures/rawkuronet/chapter.html")),
+            ),
+            (
+                "/ajax/image/list/chap/88231",
+                Fixture::json(include_str!("../../fixtures/rawkuronet/images.json")),
+            ),
+        ])
+        .await;
+        let site = Arc::new(RawkuroNet::new(reqwest::Client::new(), &server.url()));
+
+        let res = diagnose(site, "異世界".into()).await;
+        assert_eq!(res.site_id, "rawkuronet");
+        let stages: Vec<_> = res.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            vec![
+                DiagnosticStage::Search,
+                DiagnosticStage::Chapters,
+                DiagnosticStage::Images
+            ]
+        );
+
+        let search = &res.stages[0];
+        assert_eq!(search.error, None);
+        assert_eq!(search.results, 2);
+        assert_eq!(search.requests.len(), 1);
+        assert_eq!(search.requests[0].status, 200);
+        assert!(search.requests[0].bytes > 0);
+        // the genre and pagination links are matched too, but dropped
+        assert_eq!(search.selectors[0].selector, "#main a");
+        assert_eq!(search.selectors[0].matches, 5);
+
+        assert_eq!(res.stages[1].results, 3);
+        let images = &res.stages[2];
+        assert_eq!(images.results, 3);
+        assert_eq!(images.requests.len(), 2);
+        assert!(images.missing.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_broken() {
+        let server = FixtureServer::start(vec![(
+            "/search",
+            Fixture::html(
+                r#"<div id="main"><a href="{base_url}/manga/a">no title</a><a title="B">no href</a></div>"#,
+            ),
+        )])
+        .await;
+        let site = Arc::new(RawkuroNet::new(reqwest::Client::new(), &server.url()));
+
+        let res = diagnose(site, "a".into()).await;
+        assert_eq!(res.stages.len(), 1);
+        let search = &res.stages[0];
+        assert_eq!(search.results, 0);
+        assert_eq!(search.selectors[0].matches, 2);
+        let missing: Vec<_> = search
+            .missing
+            .iter()
+            .map(|m| (m.selector.as_str(), m.attr.as_str(), m.count))
+            .collect();
+        assert_eq!(
+            missing,
+            vec![("#main a", "title", 1), ("#main a", "href", 1)]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Noticing when a site has moved to another domain.
+
+use reqwest::{redirect::Policy, StatusCode, Url};
+
+use crate::rt;
+
+use super::client;
+
+/// Scheme, host and port of `url`, without a trailing slash.
+pub fn origin(url: &str) -> Option<String> {
+    let origin = Url::parse(url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Requests the front page of `base_url` and returns it on the new origin
+/// when it permanently redirects to another one.
+///
+/// Temporary redirects and redirects within the same origin, such as to a
+/// language path, are not moves.
+pub async fn detect_move(base_url: &str) -> anyhow::Result<Option<String>> {
+    let base = Url::parse(&format!("{}/", base_url.trim_end_matches('/')))?;
+    rt().spawn(async move {
+        let client = client::builder()
+            .redirect(Policy::none())
+            .build()?;
+        let res = client.get(base.clone()).send().await?;
+        if !matches!(
+            res.status(),
+            StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+        ) {
+            return Ok(None);
+        }
+        let location = match res.headers().get(reqwest::header::LOCATION) {
+            Some(l) => l.to_str()?,
+            None => return Ok(None),
+        };
+        let target = base.join(location)?;
+        let (old, new) = (origin(base.as_str()), origin(target.as_str()));
+        Ok(match new {
+            Some(new) if Some(&new) != old.as_ref() => {
+                Some(format!("{}{}", new, base.path().trim_end_matches('/')))
+            }
+            _ => None,
+        })
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sites::testing::{Fixture, FixtureServer};
+
+    use super::{detect_move, origin};
+
+    fn redirect(status: u16, location: &str) -> Fixture {
+        let mut fixture = Fixture::new(status, "text/html", "");
+        fixture
+            .headers
+            .push(("Location".to_string(), location.to_string()));
+        fixture
+    }
+
+    #[test]
+    fn test_origin() {
+        assert_eq!(
+            origin("https://rawkuro.net/manga/foo").as_deref(),
+            Some("https://rawkuro.net")
+        );
+        assert_eq!(
+            origin("http://127.0.0.1:8080/").as_deref(),
+            Some("http://127.0.0.1:8080")
+        );
+        assert_eq!(origin("data:text/plain,foo"), None);
+        assert_eq!(origin("foo"), None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_move() {
+        let server = FixtureServer::start(vec![("/", redirect(301, "https://rawkuro.com/"))]).await;
+        assert_eq!(
+            detect_move(&server.url()).await.unwrap().as_deref(),
+            Some("https://rawkuro.com")
+        );
+
+        let server = FixtureServer::start(vec![("/", redirect(302, "https://rawkuro.com/"))]).await;
+        assert_eq!(detect_move(&server.url()).await.unwrap(), None);
+
+        let server = FixtureServer::start(vec![("/", redirect(308, "/ja/"))]).await;
+        assert_eq!(detect_move(&server.url()).await.unwrap(), None);
+
+        let server = FixtureServer::start(vec![("/", Fixture::html("<html></html>"))]).await;
+        assert_eq!(detect_move(&server.url()).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,256 @@
+//! Searching every enabled site at once.
+
+use std::{sync::Arc, time::Duration};
+
+use super::{Link, MangaSite};
+
+/// Search results of one site.
+#[derive(Debug)]
+pub struct SiteResults {
+    pub site_id: String,
+    pub site_name: String,
+    pub links: Vec<Link>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    /// Why the search failed or timed out on this site. `links` is empty then.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteLink {
+    pub site_id: String,
+    pub url: String,
+}
+
+/// A title found on one or more sites.
+#[derive(Debug)]
+pub struct MergedTitle {
+    pub title: String,
+    pub image: Option<String>,
+    pub sources: Vec<SiteLink>,
+}
+
+#[derive(Debug)]
+pub struct FederatedResults {
+    /// One entry per site, in the order the sites were given.
+    pub sites: Vec<SiteResults>,
+    /// Titles collapsed across sites. Empty unless collapsing was asked for.
+    pub merged: Vec<MergedTitle>,
+}
+
+/// Searches `sites` concurrently, giving each of them at most `timeout`.
+pub async fn search_all(
+    sites: Vec<Arc<dyn MangaSite>>,
+    text: String,
+    timeout: Duration,
+    collapse: bool,
+) -> FederatedResults {
+    let handles: Vec<_> = sites
+        .iter()
+        .map(|site| {
+            let site = site.clone();
+            let text = text.clone();
+            tokio::spawn(
+                async move { tokio::time::timeout(timeout, site.search(text, None)).await },
+            )
+        })
+        .collect();
+
+    let mut results = vec![];
+    for (site, handle) in sites.iter().zip(handles) {
+        let mut res = SiteResults {
+            site_id: site.id(),
+            site_name: site.name(),
+            links: vec![],
+            next_cursor: None,
+            has_more: false,
+            error: None,
+        };
+        match handle.await {
+            Ok(Ok(Ok(page))) => {
+                res.links = page.links;
+                res.next_cursor = page.next_cursor;
+                res.has_more = page.has_more;
+            }
+            Ok(Ok(Err(e))) => res.error = Some(e.to_string()),
+            Ok(Err(_)) => res.error = Some(format!("timed out after {:?}", timeout)),
+            Err(e) => res.error = Some(e.to_string()),
+        }
+        if let Some(e) = &res.error {
+            tracing::warn!(site = %res.site_id, error = %e, "search failed");
+        }
+        results.push(res);
+    }
+
+    let merged = if collapse { merge(&results) } else { vec![] };
+    FederatedResults {
+        sites: results,
+        merged,
+    }
+}
+
+/// Groups links whose titles are the same once normalized, keeping the
+/// order in which titles first appear.
+fn merge(results: &[SiteResults]) -> Vec<MergedTitle> {
+    let mut keys: Vec<String> = vec![];
+    let mut merged: Vec<MergedTitle> = vec![];
+    for res in results.iter() {
+        for link in res.links.iter() {
+            let source = SiteLink {
+                site_id: res.site_id.clone(),
+                url: link.url.clone(),
+            };
+            let key = normalize_title(&link.text);
+            match keys.iter().position(|k| *k == key) {
+                Some(i) => {
+                    let title = &mut merged[i];
+                    if title.image.is_none() {
+                        title.image = link.image.clone();
+                    }
+                    title.sources.push(source);
+                }
+                None => {
+                    keys.push(key);
+                    merged.push(MergedTitle {
+                        title: link.text.clone(),
+                        image: link.image.clone(),
+                        sources: vec![source],
+                    });
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Folds full-width ASCII and case, and drops spaces and punctuation, so
+/// that `ＯＮＥ ＰＩＥＣＥ!` and `one piece` compare equal.
+fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use anyhow::bail;
+    use async_trait::async_trait;
+
+    use crate::sites::{Link, MangaSite, SearchPage};
+
+    use super::{normalize_title, search_all};
+
+    enum Behavior {
+        Found(Vec<&'static str>),
+        Fail,
+        Hang,
+    }
+
+    struct Dummy(&'static str, Behavior);
+
+    #[async_trait]
+    impl MangaSite for Dummy {
+        fn id(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn name(&self) -> String {
+            self.0.to_uppercase()
+        }
+
+        async fn search(
+            &self,
+            _text: String,
+            _cursor: Option<String>,
+        ) -> anyhow::Result<SearchPage> {
+            match &self.1 {
+                Behavior::Found(titles) => {
+                    let links = titles
+                        .iter()
+                        .map(|t| Link {
+                            text: t.to_string(),
+                            url: format!("https://{}/{}", self.0, t),
+                            image: None,
+                        })
+                        .collect();
+                    Ok(SearchPage::numbered(links, 1, true))
+                }
+                Behavior::Fail => bail!("blocked"),
+                Behavior::Hang => {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    Ok(SearchPage::numbered(vec![], 1, false))
+                }
+            }
+        }
+    }
+
+    fn sites() -> Vec<Arc<dyn MangaSite>> {
+        vec![
+            Arc::new(Dummy("a", Behavior::Found(vec!["ONE PIECE", "ワンピース"]))),
+            Arc::new(Dummy("b", Behavior::Fail)),
+            Arc::new(Dummy("c", Behavior::Hang)),
+            Arc::new(Dummy(
+                "d",
+                Behavior::Found(vec!["ＯＮＥ　ＰＩＥＣＥ!", "Naruto"]),
+            )),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_search_all() {
+        let res = search_all(sites(), "one".into(), Duration::from_millis(100), false).await;
+
+        let ids: Vec<_> = res.sites.iter().map(|s| s.site_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c", "d"]);
+        assert_eq!(res.sites[0].links.len(), 2);
+        assert_eq!(res.sites[0].next_cursor.as_deref(), Some("2"));
+        assert_eq!(res.sites[0].error, None);
+        assert_eq!(res.sites[1].error.as_deref(), Some("blocked"));
+        assert!(res.sites[2]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("timed out"));
+        assert!(res.sites[2].links.is_empty());
+        assert_eq!(res.sites[3].site_name, "D");
+        assert!(res.merged.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_all_collapse() {
+        let res = search_all(sites(), "one".into(), Duration::from_millis(100), true).await;
+
+        let titles: Vec<_> = res.merged.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["ONE PIECE", "ワンピース", "Naruto"]);
+        let sources: Vec<_> = res.merged[0]
+            .sources
+            .iter()
+            .map(|s| s.site_id.as_str())
+            .collect();
+        assert_eq!(sources, vec!["a", "d"]);
+        assert_eq!(
+            res.merged[0].sources[1].url,
+            "https://d/ＯＮＥ　ＰＩＥＣＥ!"
+        );
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("ＯＮＥ　ＰＩＥＣＥ!"), "onepiece");
+        assert_eq!(normalize_title("One-Piece"), "onepiece");
+        assert_eq!(
+            normalize_title("転生したら スライムだった件"),
+            "転生したらスライムだった件"
+        );
+        assert_ne!(normalize_title("ワンピース"), normalize_title("ONE PIECE"));
+    }
+}
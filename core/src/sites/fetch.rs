@@ -0,0 +1,526 @@
+//! Requests made by scrapers, checked before anything is parsed.
+//!
+//! A page that isn't what the scraper asked for, such as an error page or an
+//! anti-bot interstitial, would otherwise parse into zero results without
+//! any sign of what went wrong.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    header::{HeaderMap, CONTENT_TYPE, RETRY_AFTER},
+    Method, StatusCode,
+};
+
+use crate::error::MangaError;
+
+use super::{
+    cache::{self, Cache},
+    limit, probe, retry,
+};
+
+/// Title of the Cloudflare challenge page.
+const CLOUDFLARE_TITLE: &str = "<title>Just a moment...</title>";
+
+/// Markers of anti-bot pages: Cloudflare challenges, DDoS-Guard and
+/// captcha walls.
+const CHALLENGE_MARKERS: &[&str] = &[
+    CLOUDFLARE_TITLE,
+    "cf-chl-",
+    "/cdn-cgi/challenge-platform/",
+    "Attention Required! | Cloudflare",
+    "cf-turnstile",
+    "ddos-guard",
+    "g-recaptcha",
+    "h-captcha",
+];
+
+/// Sends `req` and reads the body as text.
+///
+/// A response from site `site_id` is an error when it is an anti-bot page
+/// (`MangaError::Blocked`), has an error status (`MangaError::HttpStatus`,
+/// or `MangaError::RateLimited` for 429) or isn't text.
+///
+/// The request waits for the rate and concurrency limits of the host, see
+/// `limit`. A GET failing in a way that may go away is tried again as the
+/// retry policy allows, see `retry`. A GET is answered from the cache when
+/// it can be, see `cache`.
+pub(crate) async fn fetch_text(
+    site_id: &str,
+    req: reqwest::RequestBuilder,
+) -> anyhow::Result<String> {
+    fetch_cached(cache::current().as_deref(), site_id, req).await
+}
+
+async fn fetch_cached(
+    cache: Option<&Cache>,
+    site_id: &str,
+    req: reqwest::RequestBuilder,
+) -> anyhow::Result<String> {
+    let (client, req) = req.build_split();
+    let mut req = req?;
+    let cache = cache.filter(|_| req.method() == Method::GET && !probe::active());
+    let key = req.url().to_string();
+    let cached = match cache {
+        Some(cache) => cache.get(&key).await,
+        None => None,
+    };
+    if let (Some(cache), Some(entry)) = (cache, &cached) {
+        if cache.is_fresh(entry) {
+            return Ok(entry.body.clone());
+        }
+        entry.add_validators(req.headers_mut());
+    }
+
+    let req = reqwest::RequestBuilder::from_parts(client, req);
+    let fetched = with_retries(site_id, req, |client, req| send_text(site_id, client, req)).await?;
+    match (fetched, cache, cached) {
+        (Fetched::NotModified, Some(cache), Some(entry)) => {
+            let body = entry.body.clone();
+            cache.touch(entry).await;
+            Ok(body)
+        }
+        (Fetched::NotModified, ..) => Err(MangaError::HttpStatus {
+            site_id: site_id.to_string(),
+            url: key,
+            status: StatusCode::NOT_MODIFIED.as_u16(),
+        }
+        .into()),
+        (Fetched::Page { text, headers }, cache, _) => {
+            if let Some(cache) = cache {
+                cache.put(&key, &headers, &text).await;
+            }
+            Ok(text)
+        }
+    }
+}
+
+/// Response to an attempt of `fetch_text`.
+enum Fetched {
+    /// The cached page is still current.
+    NotModified,
+    Page { text: String, headers: HeaderMap },
+}
+
+/// Like `fetch_text`, for an image. A page instead of the image, such as
+/// a hotlink warning, is `MangaError::ParseFailed`.
+pub(crate) async fn fetch_image(
+    site_id: &str,
+    req: reqwest::RequestBuilder,
+) -> anyhow::Result<Vec<u8>> {
+    with_retries(site_id, req, |client, req| send_image(site_id, client, req)).await
+}
+
+/// Sends `req` with `send`, trying again as the retry policy allows.
+async fn with_retries<T, F, Fut>(
+    site_id: &str,
+    req: reqwest::RequestBuilder,
+    send: F,
+) -> anyhow::Result<T>
+where
+    F: Fn(reqwest::Client, reqwest::Request) -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let (client, req) = req.build_split();
+    let mut req = req?;
+    let policy = retry::policy();
+    let deadline = tokio::time::Instant::now() + policy.deadline();
+    let mut attempt = 1;
+    loop {
+        let next = match *req.method() {
+            Method::GET | Method::HEAD => req.try_clone(),
+            _ => None,
+        };
+        let url = req.url().to_string();
+        let err = match tokio::time::timeout_at(deadline, send(client.clone(), req)).await {
+            Ok(Ok(body)) => return Ok(body),
+            Ok(Err(e)) => e,
+            Err(_) => {
+                return Err(MangaError::NetworkError {
+                    msg: format!("{} timed out after {:?}", url, policy.deadline()),
+                }
+                .into())
+            }
+        };
+        let (Some(next), Some(delay)) = (next, policy.delay(attempt, &err)) else {
+            return Err(err);
+        };
+        if tokio::time::Instant::now() + delay >= deadline {
+            return Err(err);
+        }
+        tracing::warn!(
+            site = site_id,
+            url = %url,
+            attempt,
+            delay = ?delay,
+            error = %err,
+            "retrying request"
+        );
+        tokio::time::sleep(delay).await;
+        req = next;
+        attempt += 1;
+    }
+}
+
+/// One attempt of `fetch_text`.
+async fn send_text(
+    site_id: &str,
+    client: reqwest::Client,
+    req: reqwest::Request,
+) -> anyhow::Result<Fetched> {
+    let _permit = limit::acquire(site_id, req.url()).await;
+    let start = Instant::now();
+    let res = client.execute(req).await?;
+    let final_url = res.url().clone();
+    let url = final_url.to_string();
+    let status = res.status();
+    let headers = res.headers().clone();
+    let text = res.text().await?;
+    probe::request(&url, status.as_u16(), text.len(), start.elapsed());
+    pause_if_asked(&final_url, status, &headers);
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+
+    check_status(site_id, &url, status, &headers, &text)?;
+    let content_type = content_type(&headers);
+    if !is_text(content_type) {
+        return Err(parse_failed(
+            site_id,
+            &url,
+            format!("unexpected content type: {}", content_type),
+        ));
+    }
+    Ok(Fetched::Page { text, headers })
+}
+
+/// One attempt of `fetch_image`.
+async fn send_image(
+    site_id: &str,
+    client: reqwest::Client,
+    req: reqwest::Request,
+) -> anyhow::Result<Vec<u8>> {
+    let _permit = limit::acquire(site_id, req.url()).await;
+    let start = Instant::now();
+    let res = client.execute(req).await?;
+    let final_url = res.url().clone();
+    let url = final_url.to_string();
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.bytes().await?;
+    probe::request(&url, status.as_u16(), body.len(), start.elapsed());
+    pause_if_asked(&final_url, status, &headers);
+
+    let content_type = content_type(&headers);
+    let is_page = content_type.to_ascii_lowercase().starts_with("text/html");
+    let text = if is_page {
+        String::from_utf8_lossy(&body)
+    } else {
+        Default::default()
+    };
+    check_status(site_id, &url, status, &headers, &text)?;
+    if is_page {
+        return Err(parse_failed(site_id, &url, "expected an image, got a page"));
+    }
+    Ok(body.to_vec())
+}
+
+/// Leaves the host alone for as long as a 429 or 503 asks.
+fn pause_if_asked(url: &reqwest::Url, status: StatusCode, headers: &HeaderMap) {
+    if matches!(status.as_u16(), 429 | 503) {
+        if let Some(secs) = retry_after(headers) {
+            limit::pause(url, Duration::from_secs(secs));
+        }
+    }
+}
+
+/// Like `fetch_text`, for an endpoint answering with JSON.
+pub(crate) async fn fetch_json(
+    site_id: &str,
+    req: reqwest::RequestBuilder,
+) -> anyhow::Result<serde_json::Value> {
+    let (client, req) = req.build_split();
+    let req = req?;
+    let url = req.url().to_string();
+    let text = fetch_text(site_id, reqwest::RequestBuilder::from_parts(client, req)).await?;
+    serde_json::from_str(&text).map_err(|e| parse_failed(site_id, &url, e))
+}
+
+fn check_status(
+    site_id: &str,
+    url: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> Result<(), MangaError> {
+    if is_challenge(status, headers, body) {
+        return Err(MangaError::Blocked {
+            site_id: site_id.to_string(),
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(MangaError::RateLimited {
+            site_id: site_id.to_string(),
+            url: url.to_string(),
+            retry_after_secs: retry_after(headers),
+        });
+    }
+    if !status.is_success() {
+        return Err(MangaError::HttpStatus {
+            site_id: site_id.to_string(),
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+    Ok(())
+}
+
+fn content_type(headers: &HeaderMap) -> &str {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Cloudflare marks its challenges with a header. Other interstitials are
+/// recognized from the body, on error statuses only as a normal page may
+/// well embed a captcha, e.g. in a comment form.
+fn is_challenge(status: StatusCode, headers: &HeaderMap, body: &str) -> bool {
+    if headers
+        .get("cf-mitigated")
+        .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"challenge"))
+    {
+        return true;
+    }
+    if status.is_success() {
+        return body.contains(CLOUDFLARE_TITLE);
+    }
+    matches!(status.as_u16(), 403 | 429 | 503)
+        && CHALLENGE_MARKERS.iter().any(|m| body.contains(m))
+}
+
+/// Pages and JSON are accepted, as is a response without a content type.
+fn is_text(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    mime.is_empty()
+        || mime.starts_with("text/")
+        || mime == "application/json"
+        || mime == "application/xhtml+xml"
+        || mime == "application/javascript"
+        || mime.ends_with("+json")
+}
+
+/// Seconds of a `Retry-After` header. Only the delay form is understood,
+/// an HTTP date gives `None`.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Error for a page of `site_id` at `url` lacking what the scraper expects.
+pub(crate) fn parse_failed(
+    site_id: &str,
+    url: &str,
+    msg: impl std::fmt::Display,
+) -> anyhow::Error {
+    MangaError::ParseFailed {
+        site_id: site_id.to_string(),
+        url: url.to_string(),
+        msg: msg.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::MangaError,
+        sites::testing::{Fixture, FixtureServer},
+    };
+
+    use std::time::{Duration, Instant};
+
+    use super::{
+        cache::{refreshing, Cache},
+        fetch_cached, fetch_image, fetch_json, fetch_text,
+        limit::{set_site_limit, RateLimit},
+    };
+
+    const CHALLENGE: &str = r#"<!DOCTYPE html><html><head><title>Just a moment...</title></head>
+<body><script src="/cdn-cgi/challenge-platform/h/b/orchestrate/chl_page/v1"></script></body></html>"#;
+
+    fn fixture(status: u16, content_type: &str, body: &str, headers: &[(&str, &str)]) -> Fixture {
+        let mut f = Fixture::new(status, content_type, body);
+        f.headers
+            .extend(headers.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        f
+    }
+
+    async fn fetch(server: &FixtureServer, path: &str) -> Result<String, MangaError> {
+        let client = reqwest::Client::new();
+        Ok(fetch_text("a", client.get(format!("{}{}", server.url(), path))).await?)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checks() {
+        set_site_limit(
+            "a",
+            RateLimit {
+                requests_per_sec: 0.0,
+                ..RateLimit::default()
+            },
+        );
+        let server = FixtureServer::start(vec![
+            ("/ok", Fixture::html("<p>ok</p>")),
+            (
+                "/cf",
+                fixture(503, "text/html", CHALLENGE, &[("Server", "cloudflare")]),
+            ),
+            ("/cf-ok", Fixture::html(CHALLENGE)),
+            (
+                "/mitigated",
+                fixture(403, "text/html", "", &[("cf-mitigated", "challenge")]),
+            ),
+            (
+                "/captcha",
+                fixture(403, "text/html", r#"<div class="g-recaptcha"></div>"#, &[]),
+            ),
+            ("/forbidden", fixture(403, "text/html", "forbidden", &[])),
+            (
+                "/limited",
+                fixture(429, "text/plain", "", &[("Retry-After", "1")]),
+            ),
+            ("/image", Fixture::new(200, "image/png", "png")),
+            (
+                "/comments",
+                Fixture::html(r#"<div class="g-recaptcha"></div>"#),
+            ),
+            ("/json", Fixture::json(r#"{"html": "<p></p>"}"#)),
+            ("/not-json", Fixture::json("<html>")),
+        ])
+        .await;
+
+        assert_eq!(fetch(&server, "/ok").await.unwrap(), "<p>ok</p>");
+        for path in ["/cf", "/cf-ok", "/mitigated", "/captcha"] {
+            let err = fetch(&server, path).await.unwrap_err();
+            assert!(
+                matches!(err, MangaError::Blocked { .. }),
+                "{}: {:?}",
+                path,
+                err
+            );
+        }
+        assert!(matches!(
+            fetch(&server, "/forbidden").await.unwrap_err(),
+            MangaError::HttpStatus { status: 403, .. }
+        ));
+        assert!(matches!(
+            fetch(&server, "/image").await.unwrap_err(),
+            MangaError::ParseFailed { .. }
+        ));
+        // a captcha on a normal page isn't a wall
+        assert!(fetch(&server, "/comments").await.is_ok());
+
+        let client = reqwest::Client::new();
+        let json = fetch_json("a", client.get(format!("{}/json", server.url())))
+            .await
+            .unwrap();
+        assert_eq!(json["html"], "<p></p>");
+        let err = fetch_json("a", client.get(format!("{}/not-json", server.url())))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            MangaError::from(err),
+            MangaError::ParseFailed { url, .. } if url.ends_with("/not-json")
+        ));
+
+        assert!(matches!(
+            fetch(&server, "/limited").await.unwrap_err(),
+            MangaError::RateLimited {
+                retry_after_secs: Some(1),
+                ..
+            }
+        ));
+        // the host is left alone as asked
+        let start = Instant::now();
+        assert!(fetch(&server, "/ok").await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cached() {
+        let page = fixture(200, "text/html", "<p>v1</p>", &[("ETag", "\"v1\"")]);
+        let server = FixtureServer::start(vec![
+            ("/manga", page.times(1)),
+            ("/manga", Fixture::new(304, "text/html", "")),
+        ])
+        .await;
+        let dir = std::env::temp_dir().join(format!("fetch-cache-test-{}", std::process::id()));
+        let cache = Cache::new(&dir, Duration::from_secs(60), 0).unwrap();
+        let client = reqwest::Client::new();
+        let url = format!("{}/manga", server.url());
+        let fetch = || fetch_cached(Some(&cache), "a", client.get(&url));
+
+        assert_eq!(fetch().await.unwrap(), "<p>v1</p>");
+        // fresh, no request
+        assert_eq!(fetch().await.unwrap(), "<p>v1</p>");
+        assert_eq!(server.requests().len(), 1);
+        // revalidated, the site answers 304
+        assert_eq!(refreshing(fetch()).await.unwrap(), "<p>v1</p>");
+        assert_eq!(server.requests().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_image() {
+        let server = FixtureServer::start(vec![
+            ("/1.jpg", Fixture::new(200, "image/jpeg", "jpeg")),
+            ("/hotlink.jpg", Fixture::html("<p>no hotlinking</p>")),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+        let get = |path: &str| client.get(format!("{}{}", server.url(), path));
+
+        assert_eq!(fetch_image("a", get("/1.jpg")).await.unwrap(), b"jpeg");
+        let err = MangaError::from(fetch_image("a", get("/hotlink.jpg")).await.unwrap_err());
+        assert!(matches!(err, MangaError::ParseFailed { .. }));
+        let err = MangaError::from(fetch_image("a", get("/2.jpg")).await.unwrap_err());
+        assert!(matches!(err, MangaError::HttpStatus { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries() {
+        let server = FixtureServer::start(vec![
+            ("/flaky", Fixture::new(503, "text/plain", "busy").times(1)),
+            ("/flaky", Fixture::hang_up().times(1)),
+            ("/flaky", Fixture::html("<p>ok</p>")),
+            ("/gone", Fixture::hang_up()),
+            ("/missing", Fixture::new(404, "text/plain", "")),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+
+        let text = fetch_text("retries", client.get(format!("{}/flaky", server.url())))
+            .await
+            .unwrap();
+        assert_eq!(text, "<p>ok</p>");
+        assert_eq!(server.requests(), vec!["/flaky"; 3]);
+
+        // not idempotent, tried once
+        let res = fetch_text("retries", client.post(format!("{}/gone", server.url()))).await;
+        assert!(res.is_err());
+        // not transient
+        let res = fetch_text("retries", client.get(format!("{}/missing", server.url()))).await;
+        assert!(res.is_err());
+        assert_eq!(server.requests()[3..], ["/gone", "/missing"]);
+    }
+}
@@ -0,0 +1,365 @@
+//! Throttling of the requests made to each host.
+//!
+//! Every host gets a token bucket and a semaphore bounding how many requests
+//! are in flight at once, following the strictest limit of the sites making
+//! requests to it. A host answering 429 or 503 with `Retry-After` is left
+//! alone for that long.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Longest `Retry-After` honoured, so a bogus header can't stall a host
+/// for the rest of the session.
+const MAX_PAUSE: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RateLimit {
+    /// Requests per second once the burst is used up. 0 disables the rate
+    /// limit.
+    pub requests_per_sec: f64,
+    /// Requests that can be made at once after the host was left alone.
+    pub burst: u32,
+    /// Requests in flight at once. 0 means no limit.
+    pub max_concurrent: u32,
+}
+
+impl RateLimit {
+    /// The stricter of both limits in each respect.
+    fn strictest(self, other: Self) -> Self {
+        let (requests_per_sec, burst) =
+            match (self.requests_per_sec > 0.0, other.requests_per_sec > 0.0) {
+                (false, _) => (other.requests_per_sec, other.burst),
+                (_, false) => (self.requests_per_sec, self.burst),
+                _ => (
+                    self.requests_per_sec.min(other.requests_per_sec),
+                    self.burst.min(other.burst),
+                ),
+            };
+        let max_concurrent = match (self.max_concurrent, other.max_concurrent) {
+            (0, n) | (n, 0) => n,
+            (a, b) => a.min(b),
+        };
+        Self {
+            requests_per_sec,
+            burst,
+            max_concurrent,
+        }
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_sec: 2.0,
+            burst: 4,
+            max_concurrent: 2,
+        }
+    }
+}
+
+lazy_static! {
+    static ref SITE_LIMITS: Mutex<HashMap<String, RateLimit>> = Mutex::new(HashMap::new());
+    static ref HOSTS: Mutex<HashMap<String, Arc<HostLimiter>>> = Mutex::new(HashMap::new());
+}
+
+/// Sets the limit applied to the hosts site `site_id` makes requests to.
+/// Sites without one get `RateLimit::default()`.
+pub fn set_site_limit(site_id: &str, limit: RateLimit) {
+    SITE_LIMITS
+        .lock()
+        .unwrap()
+        .insert(site_id.to_string(), limit);
+}
+
+fn site_limit(site_id: &str) -> RateLimit {
+    SITE_LIMITS
+        .lock()
+        .unwrap()
+        .get(site_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Host and port of `url`, which limiters are keyed by.
+fn host_key(url: &reqwest::Url) -> Option<String> {
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
+
+/// Limiter of `host`, made to follow `limit` for site `site_id`.
+fn host(host: &str, site_id: &str, limit: RateLimit) -> Arc<HostLimiter> {
+    let limiter = HOSTS
+        .lock()
+        .unwrap()
+        .entry(host.to_string())
+        .or_insert_with(|| Arc::new(HostLimiter::new(limit)))
+        .clone();
+    limiter.set_site_limit(site_id, limit);
+    limiter
+}
+
+/// Waits until a request for site `site_id` may be sent to the host of
+/// `url`. The request counts as in flight until the permit is dropped.
+pub(crate) async fn acquire(site_id: &str, url: &reqwest::Url) -> Option<OwnedSemaphorePermit> {
+    let key = host_key(url)?;
+    let limiter = host(&key, site_id, site_limit(site_id));
+    let semaphore = limiter.semaphore.lock().unwrap().clone();
+    let permit = semaphore.acquire_owned().await.ok()?;
+    limiter.take().await;
+    Some(permit)
+}
+
+/// Holds off every request to the host of `url` for `delay`.
+pub(crate) fn pause(url: &reqwest::Url, delay: Duration) {
+    let Some(key) = host_key(url) else {
+        return;
+    };
+    let limiter = HOSTS.lock().unwrap().get(&key).cloned();
+    if let Some(limiter) = limiter {
+        let until = Instant::now() + delay.min(MAX_PAUSE);
+        let mut bucket = limiter.bucket.lock().unwrap();
+        if bucket.paused_until.is_none_or(|p| p < until) {
+            tracing::info!(host = %key, delay = ?delay, "pausing requests");
+            bucket.paused_until = Some(until);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HostLimiter {
+    /// Limits of the sites making requests to the host, by site id.
+    sites: Mutex<HashMap<String, RateLimit>>,
+    /// Replaced when the concurrency limit changes; requests in flight keep
+    /// their permit of the previous one.
+    semaphore: Mutex<Arc<Semaphore>>,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Strictest limit of the sites.
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl HostLimiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            sites: Mutex::new(HashMap::new()),
+            semaphore: Mutex::new(semaphore(limit)),
+            bucket: Mutex::new(Bucket {
+                limit,
+                tokens: limit.burst.max(1) as f64,
+                updated: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Sets the limit of site `site_id`, then follows the strictest limit of
+    /// the sites.
+    fn set_site_limit(&self, site_id: &str, limit: RateLimit) {
+        let mut sites = self.sites.lock().unwrap();
+        if sites.get(site_id) == Some(&limit) {
+            return;
+        }
+        sites.insert(site_id.to_string(), limit);
+        let strictest = sites
+            .values()
+            .copied()
+            .reduce(RateLimit::strictest)
+            .unwrap_or(limit);
+
+        let mut bucket = self.bucket.lock().unwrap();
+        if bucket.limit.max_concurrent != strictest.max_concurrent {
+            *self.semaphore.lock().unwrap() = semaphore(strictest);
+        }
+        bucket.tokens = bucket.tokens.min(strictest.burst.max(1) as f64);
+        bucket.limit = strictest;
+    }
+
+    /// Takes a token, waiting for one to be refilled if needed.
+    async fn take(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let now = Instant::now();
+                match bucket.paused_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        bucket.paused_until = None;
+                        let limit = bucket.limit;
+                        if limit.requests_per_sec <= 0.0 {
+                            return;
+                        }
+                        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                        bucket.tokens = (bucket.tokens + elapsed * limit.requests_per_sec)
+                            .min(limit.burst.max(1) as f64);
+                        bucket.updated = now;
+                        if bucket.tokens >= 1.0 {
+                            bucket.tokens -= 1.0;
+                            return;
+                        }
+                        Duration::from_secs_f64((1.0 - bucket.tokens) / limit.requests_per_sec)
+                    }
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn semaphore(limit: RateLimit) -> Arc<Semaphore> {
+    let permits = match limit.max_concurrent {
+        0 => Semaphore::MAX_PERMITS,
+        n => n as usize,
+    };
+    Arc::new(Semaphore::new(permits))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use reqwest::Url;
+
+    use super::{acquire, pause, set_site_limit, RateLimit};
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        set_site_limit(
+            "limit-rate",
+            RateLimit {
+                requests_per_sec: 20.0,
+                burst: 2,
+                max_concurrent: 0,
+            },
+        );
+        let url = Url::parse("https://rate.example/a").unwrap();
+        let start = Instant::now();
+        for _ in 0..4 {
+            acquire("limit-rate", &url).await;
+        }
+        // two from the burst, then one every 50ms
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        let start = Instant::now();
+        pause(&url, Duration::from_millis(200));
+        acquire("limit-rate", &url).await;
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        set_site_limit(
+            "limit-concurrency",
+            RateLimit {
+                requests_per_sec: 0.0,
+                burst: 1,
+                max_concurrent: 1,
+            },
+        );
+        let url = Url::parse("https://concurrency.example/a").unwrap();
+        let permit = acquire("limit-concurrency", &url).await;
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            acquire("limit-concurrency", &url),
+        );
+        assert!(second.await.is_err());
+        drop(permit);
+        assert!(acquire("limit-concurrency", &url).await.is_some());
+
+        // another host has its own limiter
+        let other = Url::parse("https://other.concurrency.example/").unwrap();
+        let _permit = acquire("limit-concurrency", &url).await;
+        assert!(acquire("limit-concurrency", &other).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_shared_host() {
+        let unlimited = RateLimit {
+            requests_per_sec: 0.0,
+            burst: 1,
+            max_concurrent: 0,
+        };
+        set_site_limit("limit-shared-a", unlimited);
+        set_site_limit(
+            "limit-shared-b",
+            RateLimit {
+                max_concurrent: 1,
+                ..unlimited
+            },
+        );
+        let url = Url::parse("https://shared.example/a").unwrap();
+        drop(acquire("limit-shared-a", &url).await);
+
+        // the strictest limit holds whichever site the request is for
+        let permit = acquire("limit-shared-b", &url).await;
+        let second =
+            tokio::time::timeout(Duration::from_millis(50), acquire("limit-shared-a", &url));
+        assert!(second.await.is_err());
+        drop(permit);
+
+        // and the host stays paused
+        let start = Instant::now();
+        pause(&url, Duration::from_millis(100));
+        drop(acquire("limit-shared-b", &url).await);
+        drop(acquire("limit-shared-a", &url).await);
+        assert!(start.elapsed() >= Duration::from_millis(90));
+
+        // until the site with the strict limit relaxes it
+        set_site_limit("limit-shared-b", unlimited);
+        let _permit = acquire("limit-shared-b", &url).await;
+        let second =
+            tokio::time::timeout(Duration::from_millis(50), acquire("limit-shared-a", &url));
+        assert!(second.await.is_ok());
+    }
+
+    #[test]
+    fn test_strictest() {
+        let a = RateLimit {
+            requests_per_sec: 2.0,
+            burst: 4,
+            max_concurrent: 0,
+        };
+        let b = RateLimit {
+            requests_per_sec: 0.0,
+            burst: 1,
+            max_concurrent: 3,
+        };
+        let c = RateLimit {
+            requests_per_sec: 1.0,
+            burst: 8,
+            max_concurrent: 2,
+        };
+        assert_eq!(
+            a.strictest(b),
+            RateLimit {
+                requests_per_sec: 2.0,
+                burst: 4,
+                max_concurrent: 3,
+            }
+        );
+        assert_eq!(
+            a.strictest(c),
+            RateLimit {
+                requests_per_sec: 1.0,
+                burst: 4,
+                max_concurrent: 2,
+            }
+        );
+    }
+}
@@ -1,24 +1,342 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
+use scraper::{Html, Selector};
 
+pub mod cache;
+pub mod canonical;
+pub mod client;
+pub mod declarative;
+pub mod diagnose;
+pub mod domain;
+pub mod federated;
+pub mod fetch;
 pub mod jmangaorg;
+pub mod limit;
 pub mod mangatopjp;
+pub mod probe;
+pub mod proxy;
 pub mod spoilerplustv;
 pub mod rawkuronet;
+pub mod registry;
+pub mod retry;
 
-#[derive(Debug)]
+#[cfg(test)]
+pub(crate) mod testing;
+
+pub(crate) use fetch::{fetch_image, fetch_json, fetch_text, parse_failed};
+pub use limit::RateLimit;
+
+pub(crate) const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36";
+
+#[derive(Debug, Clone)]
 pub struct Link {
     pub text: String,
     pub url: String,
     pub image: Option<String>,
 }
 
+/// One page of search results.
+///
+/// `next_cursor` is opaque to callers and is passed back to get the next page.
+#[derive(Debug)]
+pub struct SearchPage {
+    pub links: Vec<Link>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl SearchPage {
+    /// Page of a listing paginated by page number, which is what the cursor holds.
+    pub(crate) fn numbered(links: Vec<Link>, page: u32, has_more: bool) -> Self {
+        Self {
+            links,
+            next_cursor: if has_more {
+                Some((page + 1).to_string())
+            } else {
+                None
+            },
+            has_more,
+        }
+    }
+}
+
+/// Page number held by a cursor from `SearchPage::numbered`, 1 when there is none.
+pub(crate) fn cursor_page(cursor: Option<&str>) -> anyhow::Result<u32> {
+    match cursor {
+        Some(c) => c.parse().map_err(|_| anyhow!("invalid cursor: {}", c)),
+        None => Ok(1),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Listing {
+    /// Recently updated manga.
+    Latest,
+    /// Ranking or most viewed manga.
+    Popular,
+}
+
+/// What a site can browse besides text search.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SiteCapabilities {
+    pub listings: Vec<Listing>,
+    /// Whether listings can be narrowed down to a genre.
+    pub genre_filter: bool,
+}
+
+impl SiteCapabilities {
+    /// Fails when the site can't browse `listing` or filter by `genre`, or
+    /// when `genre` isn't a slug that can go in a url.
+    pub(crate) fn check(
+        &self,
+        site: &str,
+        listing: Listing,
+        genre: Option<&str>,
+    ) -> anyhow::Result<()> {
+        if !self.listings.contains(&listing) {
+            bail!("{} does not support {:?} listing", site, listing);
+        }
+        if let Some(genre) = genre {
+            if !self.genre_filter {
+                bail!("{} does not support browsing by genre", site);
+            }
+            if genre.is_empty()
+                || !genre
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                bail!("invalid genre: {}", genre);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PublicationStatus {
+    #[default]
+    Unknown,
+    Ongoing,
+    Completed,
+    Hiatus,
+}
+
+impl PublicationStatus {
+    /// Guesses the status from the label a site shows.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim().to_lowercase();
+        if ["連載中", "ongoing", "publishing"]
+            .iter()
+            .any(|s| text.contains(s))
+        {
+            Self::Ongoing
+        } else if ["完結", "completed", "finished"]
+            .iter()
+            .any(|s| text.contains(s))
+        {
+            Self::Completed
+        } else if ["休載", "hiatus"].iter().any(|s| text.contains(s)) {
+            Self::Hiatus
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Ongoing => "ongoing",
+            Self::Completed => "completed",
+            Self::Hiatus => "hiatus",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Self {
+        match s {
+            "ongoing" => Self::Ongoing,
+            "completed" => Self::Completed,
+            "hiatus" => Self::Hiatus,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MangaDetails {
+    pub authors: Vec<String>,
+    pub artists: Vec<String>,
+    pub genres: Vec<String>,
+    pub status: PublicationStatus,
+    pub alt_titles: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// One image of a chapter, with what it takes to load it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Page {
+    /// Position in the chapter, from 0.
+    pub index: u32,
+    pub url: String,
+    /// Headers to request `url` with, such as `Referer`.
+    pub headers: HashMap<String, String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Other urls of the same image, to try when `url` fails.
+    pub alternates: Vec<String>,
+    /// Set when the image is served scrambled.
+    pub descramble: Option<Descramble>,
+}
+
+/// How to put a scrambled image back together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Descramble {
+    /// Name of the scheme, which the reader implements.
+    pub scheme: String,
+    /// Parameters of the scheme, such as a key or the tile grid.
+    pub params: HashMap<String, String>,
+}
+
+impl Page {
+    /// Pages of `urls` in order, all requested with `headers`.
+    pub(crate) fn list(urls: Vec<String>, headers: &HashMap<String, String>) -> Vec<Self> {
+        urls.into_iter()
+            .enumerate()
+            .map(|(index, url)| Self {
+                index: index as u32,
+                url,
+                headers: headers.clone(),
+                ..Self::default()
+            })
+            .collect()
+    }
+}
+
+/// Headers to request the images of the chapter at `chapter_url` with: the
+/// site's `request_headers`, and the chapter as `Referer` unless the site
+/// sets one.
+pub(crate) fn image_headers(
+    site: &dyn MangaSite,
+    chapter_url: &str,
+) -> HashMap<String, String> {
+    let mut headers = site.request_headers();
+    if !headers.keys().any(|k| k.eq_ignore_ascii_case("referer")) {
+        headers.insert("Referer".to_string(), chapter_url.to_string());
+    }
+    headers
+}
+
+/// Path of `url` without its trailing slash, `url` itself if it isn't one.
+pub(crate) fn url_path(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(u) => u.path().trim_end_matches('/').to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Trimmed, non-empty texts of every element matching `selector`.
+pub(crate) fn select_texts(doc: &Html, selector: &str) -> Vec<String> {
+    let sel = Selector::parse(selector).unwrap();
+    doc.select(&sel)
+        .map(|e| e.text().collect::<String>().trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+pub(crate) fn select_text(doc: &Html, selector: &str) -> Option<String> {
+    select_texts(doc, selector).into_iter().next()
+}
+
+/// Link to the manga page `doc` fetched from `url`, named `title` or else
+/// by the page's `og:title` or `<title>`, with `cover` or else its
+/// `og:image`. `None` when the page has no title at all.
+pub(crate) fn manga_page_link(
+    doc: &Html,
+    url: &str,
+    title: Option<String>,
+    cover: Option<&str>,
+) -> Option<Link> {
+    let meta = |property: &str| {
+        let sel = Selector::parse(&format!(r#"meta[property="{}"]"#, property)).unwrap();
+        doc.select(&sel)
+            .find_map(|m| m.attr("content"))
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+    };
+    // page titles usually end with ` - <site name>`
+    let page_title = || {
+        select_text(doc, "title").map(|t| match t.rsplit_once(" - ") {
+            Some((title, _)) => title.trim().to_string(),
+            None => t,
+        })
+    };
+    let title = title.or_else(|| meta("og:title")).or_else(page_title)?;
+    let cover = cover.map(String::from).or_else(|| meta("og:image"));
+    Some(Link {
+        text: title,
+        url: url.to_string(),
+        image: cover.and_then(|c| canonical::resolve(url, &c)),
+    })
+}
+
+/// Splits a list of names such as alternative titles written in one line.
+pub(crate) fn split_names(text: &str) -> Vec<String> {
+    text.split([',', ';', '、', '/'])
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 #[async_trait]
 pub trait MangaSite: Send + Sync {
+    /// Stable identifier used to persist per-site settings.
+    fn id(&self) -> String;
+
     fn name(&self) -> String;
 
-    async fn search(&self, text: String) -> anyhow::Result<Vec<Link>>;
+    /// Url the site is served from, e.g. `https://rawkuro.net`.
+    fn base_url(&self) -> String {
+        String::new()
+    }
+
+    /// Host images are served from, for sites using a separate one.
+    fn cdn_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Copy of the site served from other hosts, for when it moved domains.
+    /// `None` for `cdn_url` keeps the default one.
+    ///
+    /// Returns `None` when the site can't be moved.
+    fn relocate(&self, _base_url: &str, _cdn_url: Option<&str>) -> Option<Arc<dyn MangaSite>> {
+        None
+    }
+
+    /// How hard the site can be hit, unless `Config::rate_limits` says
+    /// otherwise.
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit::default()
+    }
+
+    async fn search(&self, text: String, cursor: Option<String>) -> anyhow::Result<SearchPage>;
+
+    fn capabilities(&self) -> SiteCapabilities {
+        SiteCapabilities::default()
+    }
+
+    /// One page of a listing, narrowed to `genre` when given.
+    ///
+    /// `genre` is the slug the site uses in its genre urls, e.g. `fantasy`
+    /// for `/genre/fantasy`. Only what `capabilities` reports is supported.
+    async fn browse(
+        &self,
+        listing: Listing,
+        _genre: Option<String>,
+        _cursor: Option<String>,
+    ) -> anyhow::Result<SearchPage> {
+        bail!("{} does not support {:?} listing", self.name(), listing)
+    }
 
     fn can_handle_chapters(&self, _url: &str) -> bool {
         false
@@ -26,11 +344,43 @@ pub trait MangaSite: Send + Sync {
     async fn chapters(&self, _url: &str) -> anyhow::Result<Vec<Link>> {
         Ok(vec![])
     }
+    /// Identifies the chapter at `url` across title changes. The path of
+    /// `url` unless the site has its own chapter ids.
+    fn chapter_key(&self, url: &str) -> String {
+        url_path(url)
+    }
+
+    /// Details of a manga page, the url `can_handle_chapters` accepts.
+    async fn details(&self, _url: &str) -> anyhow::Result<MangaDetails> {
+        Ok(MangaDetails::default())
+    }
+
+    /// Title and cover of the manga page at `url`, for a manga opened from a
+    /// link rather than found through the site.
+    async fn manga_link(&self, url: &str) -> anyhow::Result<Link> {
+        bail!("{} can't read the manga page {}", self.name(), url)
+    }
+
+    /// Manga page the chapter at `url` belongs to: by default the parent
+    /// path of the chapter, if it's a manga page of the site.
+    fn chapter_manga_url(&self, url: &str) -> Option<String> {
+        let mut parent = reqwest::Url::parse(url).ok()?;
+        parent.set_query(None);
+        parent.set_fragment(None);
+        let path = parent.path().trim_end_matches('/');
+        let path = path[..path.rfind('/')?].to_string();
+        [format!("{}/", path), path].into_iter().find_map(|p| {
+            parent.set_path(&p);
+            let url = parent.to_string();
+            self.can_handle_chapters(&url).then_some(url)
+        })
+    }
 
     fn can_handle_images(&self, _url: &str) -> bool {
         false
     }
-    async fn images(&self, _url: &str) -> anyhow::Result<Vec<String>> {
+    /// Pages of the chapter at `url`, in reading order.
+    async fn images(&self, _url: &str) -> anyhow::Result<Vec<Page>> {
         Ok(vec![])
     }
 
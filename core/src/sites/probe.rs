@@ -0,0 +1,162 @@
+//! Records what scrapers fetch and match while a site is being diagnosed.
+//!
+//! Scrapers report through the free functions here, which do nothing unless
+//! they run inside `Probe::scope`. Work moved to another task has to be
+//! wrapped in `inherit` to keep reporting.
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestReport {
+    pub url: String,
+    pub status: u16,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorReport {
+    pub selector: String,
+    pub matches: u64,
+}
+
+/// Elements matched by `selector` that lacked `attr`, so they were dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingAttr {
+    pub selector: String,
+    pub attr: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Recorded {
+    pub requests: Vec<RequestReport>,
+    pub selectors: Vec<SelectorReport>,
+    pub missing: Vec<MissingAttr>,
+}
+
+#[derive(Debug, Default)]
+pub struct Probe {
+    recorded: Mutex<Recorded>,
+}
+
+tokio::task_local! {
+    static PROBE: Arc<Probe>;
+}
+
+impl Probe {
+    pub async fn scope<F: Future>(self: &Arc<Self>, f: F) -> F::Output {
+        PROBE.scope(self.clone(), f).await
+    }
+
+    /// Everything recorded so far, leaving the probe empty.
+    pub fn take(&self) -> Recorded {
+        std::mem::take(&mut *self.recorded.lock().unwrap())
+    }
+}
+
+/// Keeps the current probe, if any, for `f` run on another task.
+pub(crate) fn inherit<F: Future>(f: F) -> impl Future<Output = F::Output> {
+    let probe = PROBE.try_with(|p| p.clone()).ok();
+    async move {
+        match probe {
+            Some(probe) => PROBE.scope(probe, f).await,
+            None => f.await,
+        }
+    }
+}
+
+/// Whether a diagnosis is running, which wants to see the real requests.
+pub(crate) fn active() -> bool {
+    PROBE.try_with(|_| ()).is_ok()
+}
+
+fn record(f: impl FnOnce(&mut Recorded)) {
+    let _ = PROBE.try_with(|p| f(&mut p.recorded.lock().unwrap()));
+}
+
+pub(crate) fn request(url: &str, status: u16, bytes: usize, elapsed: Duration) {
+    record(|r| {
+        r.requests.push(RequestReport {
+            url: url.to_string(),
+            status,
+            bytes: bytes as u64,
+            elapsed_ms: elapsed.as_millis() as u64,
+        })
+    });
+}
+
+/// Records how many elements `selector` matched. `count` only runs while
+/// diagnosing.
+pub(crate) fn matches(selector: &str, count: impl FnOnce() -> usize) {
+    record(|r| {
+        let matches = count() as u64;
+        match r.selectors.iter_mut().find(|s| s.selector == selector) {
+            Some(s) => s.matches += matches,
+            None => r.selectors.push(SelectorReport {
+                selector: selector.to_string(),
+                matches,
+            }),
+        }
+    });
+}
+
+/// Passes `value` through, recording `attr` as missing on `selector` when
+/// it is `None`.
+pub(crate) fn require<T>(selector: &str, attr: &str, value: Option<T>) -> Option<T> {
+    if value.is_none() {
+        record(|r| {
+            match r
+                .missing
+                .iter_mut()
+                .find(|m| m.selector == selector && m.attr == attr)
+            {
+                Some(m) => m.count += 1,
+                None => r.missing.push(MissingAttr {
+                    selector: selector.to_string(),
+                    attr: attr.to_string(),
+                    count: 1,
+                }),
+            }
+        });
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{inherit, matches, request, require, Probe};
+
+    #[tokio::test]
+    async fn test_probe() {
+        // nothing is recorded outside a scope
+        matches("a", || panic!("counted outside a scope"));
+
+        let probe = Arc::new(Probe::default());
+        probe
+            .scope(async {
+                request("https://a/", 200, 10, Duration::from_millis(5));
+                matches("a", || 2);
+                matches("a", || 1);
+                assert_eq!(require("a", "href", None::<&str>), None);
+                assert_eq!(require("a", "href", Some("x")), Some("x"));
+                tokio::spawn(inherit(async { require("a", "href", None::<&str>) }))
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        let recorded = probe.take();
+        assert_eq!(recorded.requests.len(), 1);
+        assert_eq!(recorded.requests[0].bytes, 10);
+        assert_eq!(recorded.selectors[0].matches, 3);
+        assert_eq!(recorded.missing[0].count, 2);
+        assert!(probe.take().requests.is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::anyhow;
+
+use crate::{db::SiteSettingDb, error::MangaError};
+
+use super::MangaSite;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceInfo {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub position: i64,
+    pub base_url: String,
+    pub cdn_url: Option<String>,
+    /// Whether the urls were set by the user rather than the site's own.
+    pub urls_overridden: bool,
+}
+
+struct Entry {
+    site: Arc<dyn MangaSite>,
+    /// The site as registered, before any url override.
+    default: Arc<dyn MangaSite>,
+    enabled: bool,
+    base_url: Option<String>,
+    cdn_url: Option<String>,
+}
+
+impl Entry {
+    fn new(site: Arc<dyn MangaSite>) -> Self {
+        Self {
+            default: site.clone(),
+            site,
+            enabled: true,
+            base_url: None,
+            cdn_url: None,
+        }
+    }
+
+    /// Rebuilds `site` from the default one and the overrides.
+    fn relocate(
+        &mut self,
+        base_url: Option<String>,
+        cdn_url: Option<String>,
+    ) -> anyhow::Result<()> {
+        self.site = if base_url.is_none() && cdn_url.is_none() {
+            self.default.clone()
+        } else {
+            let base = base_url.clone().unwrap_or_else(|| self.default.base_url());
+            self.default
+                .relocate(
+                    base.trim_end_matches('/'),
+                    cdn_url.as_deref().map(|u| u.trim_end_matches('/')),
+                )
+                .ok_or_else(|| anyhow!("site {} can't change its urls", self.default.id()))?
+        };
+        self.base_url = base_url;
+        self.cdn_url = cdn_url;
+        Ok(())
+    }
+}
+
+/// Owns every site instance and the user's enable/order settings.
+///
+/// Disabled sites are hidden from listing and searching, but still route
+/// urls so that manga already in the library keep working.
+pub struct SiteRegistry {
+    entries: RwLock<Vec<Entry>>,
+}
+
+impl Debug for SiteRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.sources().iter().map(|s| &s.id))
+            .finish()
+    }
+}
+
+impl SiteRegistry {
+    /// A site whose id is already registered replaces the earlier one in place.
+    pub fn new(sites: Vec<Arc<dyn MangaSite>>) -> Self {
+        let mut entries: Vec<Entry> = vec![];
+        for site in sites.into_iter() {
+            match entries.iter().position(|e| e.site.id() == site.id()) {
+                Some(i) => entries[i] = Entry::new(site),
+                None => entries.push(Entry::new(site)),
+            }
+        }
+        Self {
+            entries: RwLock::new(entries),
+        }
+    }
+
+    /// Enabled sites in the user's order.
+    pub fn enabled(&self) -> Vec<Arc<dyn MangaSite>> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|e| e.enabled)
+            .map(|e| e.site.clone())
+            .collect()
+    }
+
+    /// Looks a site up by id, whether it is enabled or not.
+    pub fn get(&self, id: &str) -> Option<Arc<dyn MangaSite>> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find(|e| e.site.id() == id)
+            .map(|e| e.site.clone())
+    }
+
+    pub fn find_for_chapters(&self, url: &str) -> Option<Arc<dyn MangaSite>> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find(|e| e.site.can_handle_chapters(url))
+            .map(|e| e.site.clone())
+    }
+
+    pub fn find_for_images(&self, url: &str) -> Option<Arc<dyn MangaSite>> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find(|e| e.site.can_handle_images(url))
+            .map(|e| e.site.clone())
+    }
+
+    pub fn sources(&self) -> Vec<SourceInfo> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, e)| SourceInfo {
+                id: e.site.id(),
+                name: e.site.name(),
+                enabled: e.enabled,
+                position: i as i64,
+                base_url: e.site.base_url(),
+                cdn_url: e.site.cdn_url(),
+                urls_overridden: e.base_url.is_some() || e.cdn_url.is_some(),
+            })
+            .collect()
+    }
+
+    /// Applies persisted settings. Sites without a setting keep their
+    /// relative order after the known ones and stay enabled. Url overrides
+    /// a site can't take are logged and dropped.
+    pub fn apply(&self, settings: &[SiteSettingDb]) {
+        let mut entries = self.entries.write().unwrap();
+        for entry in entries.iter_mut() {
+            if let Some(s) = settings.iter().find(|s| s.id == entry.site.id()) {
+                entry.enabled = s.enabled != 0;
+                if let Err(e) = entry.relocate(s.base_url.clone(), s.cdn_url.clone()) {
+                    tracing::warn!(error = %e, "ignoring saved site urls");
+                }
+            }
+        }
+        entries.sort_by_key(|e| {
+            settings
+                .iter()
+                .find(|s| s.id == e.site.id())
+                .map(|s| s.position)
+                .unwrap_or(i64::MAX)
+        });
+    }
+
+    /// Returns false when no site has the id.
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> bool {
+        let mut entries = self.entries.write().unwrap();
+        match entries.iter_mut().find(|e| e.site.id() == id) {
+            Some(e) => {
+                e.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Serves the site from other hosts. `None` restores the site's own url.
+    pub fn set_urls(
+        &self,
+        id: &str,
+        base_url: Option<String>,
+        cdn_url: Option<String>,
+    ) -> anyhow::Result<()> {
+        let mut entries = self.entries.write().unwrap();
+        match entries.iter_mut().find(|e| e.site.id() == id) {
+            Some(e) => e.relocate(base_url, cdn_url),
+            None => Err(MangaError::UnknownSite {
+                site_id: id.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    /// Url overrides of a site, `None` when no site has the id.
+    pub fn url_overrides(&self, id: &str) -> Option<(Option<String>, Option<String>)> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .find(|e| e.site.id() == id)
+            .map(|e| (e.base_url.clone(), e.cdn_url.clone()))
+    }
+
+    /// Moves the sites in `ids` to the front in the given order. Unknown ids
+    /// are ignored and unlisted sites keep their relative order.
+    pub fn reorder(&self, ids: &[String]) {
+        let mut entries = self.entries.write().unwrap();
+        entries.sort_by_key(|e| {
+            let id = e.site.id();
+            ids.iter().position(|i| *i == id).unwrap_or(usize::MAX)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use crate::{
+        db::SiteSettingDb,
+        sites::{rawkuronet::RawkuroNet, MangaSite, SearchPage},
+    };
+
+    use super::SiteRegistry;
+
+    struct Dummy(&'static str, &'static str);
+
+    #[async_trait]
+    impl MangaSite for Dummy {
+        fn id(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn name(&self) -> String {
+            self.1.to_string()
+        }
+
+        async fn search(
+            &self,
+            _text: String,
+            _cursor: Option<String>,
+        ) -> anyhow::Result<SearchPage> {
+            Ok(SearchPage::numbered(vec![], 1, false))
+        }
+
+        fn can_handle_chapters(&self, url: &str) -> bool {
+            url.starts_with(&format!("https://{}/", self.0))
+        }
+    }
+
+    fn registry() -> SiteRegistry {
+        SiteRegistry::new(vec![
+            Arc::new(Dummy("a", "A")),
+            Arc::new(Dummy("b", "B")),
+            Arc::new(Dummy("c", "C")),
+            Arc::new(Dummy("b", "B2")),
+        ])
+    }
+
+    fn ids(r: &SiteRegistry) -> Vec<String> {
+        r.enabled().iter().map(|s| s.id()).collect()
+    }
+
+    #[test]
+    fn test_registry_replace() {
+        let r = registry();
+        assert_eq!(ids(&r), vec!["a", "b", "c"]);
+        assert_eq!(r.sources()[1].name, "B2");
+    }
+
+    #[test]
+    fn test_registry_enable_reorder() {
+        let r = registry();
+
+        assert!(r.set_enabled("b", false));
+        assert!(!r.set_enabled("x", false));
+        assert_eq!(ids(&r), vec!["a", "c"]);
+        // disabled sites still route urls
+        assert_eq!(r.find_for_chapters("https://b/foo").unwrap().id(), "b");
+
+        r.reorder(&["c".to_string(), "x".to_string(), "b".to_string()]);
+        assert_eq!(ids(&r), vec!["c", "a"]);
+
+        let sources = r.sources();
+        assert_eq!(sources[0].id, "c");
+        assert_eq!(sources[1].id, "b");
+        assert!(!sources[1].enabled);
+        assert_eq!(sources[2].id, "a");
+        assert_eq!(sources[2].position, 2);
+    }
+
+    #[test]
+    fn test_registry_apply() {
+        let r = registry();
+        r.apply(&[
+            SiteSettingDb {
+                id: "c".into(),
+                enabled: 1,
+                position: 0,
+                base_url: None,
+                cdn_url: None,
+            },
+            SiteSettingDb {
+                id: "a".into(),
+                enabled: 0,
+                position: 1,
+                base_url: None,
+                cdn_url: None,
+            },
+        ]);
+
+        let sources = r.sources();
+        let order: Vec<_> = sources.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, vec!["c", "a", "b"]);
+        assert_eq!(ids(&r), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn test_registry_set_urls() {
+        let r = SiteRegistry::new(vec![
+            Arc::new(Dummy("a", "A")),
+            Arc::new(RawkuroNet::new(
+                reqwest::Client::new(),
+                "https://rawkuro.net",
+            )),
+        ]);
+
+        r.set_urls("rawkuronet", Some("https://rawkuro.com/".into()), None)
+            .unwrap();
+        let site = r
+            .find_for_chapters("https://rawkuro.com/manga/foo")
+            .unwrap();
+        assert_eq!(site.id(), "rawkuronet");
+        assert!(r
+            .find_for_chapters("https://rawkuro.net/manga/foo")
+            .is_none());
+        let sources = r.sources();
+        assert_eq!(sources[1].base_url, "https://rawkuro.com");
+        assert!(sources[1].urls_overridden);
+
+        // sites that can't move and unknown ones are refused
+        assert!(r.set_urls("a", Some("https://b".into()), None).is_err());
+        assert!(r.set_urls("x", Some("https://b".into()), None).is_err());
+
+        r.set_urls("rawkuronet", None, None).unwrap();
+        assert!(r
+            .find_for_chapters("https://rawkuro.net/manga/foo")
+            .is_some());
+        assert!(!r.sources()[1].urls_overridden);
+
+        r.apply(&[SiteSettingDb {
+            id: "rawkuronet".into(),
+            enabled: 1,
+            position: 0,
+            base_url: Some("https://rawkuro.org".into()),
+            cdn_url: None,
+        }]);
+        assert_eq!(r.sources()[0].base_url, "https://rawkuro.org");
+    }
+}
@@ -0,0 +1,155 @@
+//! When to try a failed request again.
+//!
+//! Only failures likely to go away by themselves are retried: the
+//! connection dropping or timing out, the server being overloaded or
+//! asking to slow down. Each retry waits longer than the previous one,
+//! and no retry is made past the deadline.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::RwLock,
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+
+use crate::error::MangaError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included. 1 disables retries.
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each one after it.
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Time a request may take, retries and waits included.
+    pub deadline_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+            deadline_secs: 60,
+        }
+    }
+}
+
+lazy_static! {
+    static ref POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::default());
+}
+
+/// Sets the policy of every request made by the sites.
+pub fn set_policy(policy: RetryPolicy) {
+    *POLICY.write().unwrap() = policy;
+}
+
+pub(crate) fn policy() -> RetryPolicy {
+    *POLICY.read().unwrap()
+}
+
+impl RetryPolicy {
+    pub(crate) fn deadline(&self) -> Duration {
+        Duration::from_secs(self.deadline_secs)
+    }
+
+    /// How long to wait before trying again after attempt `attempt`, the
+    /// first one being 1, failed with `err`. `None` when it shouldn't be
+    /// retried.
+    pub(crate) fn delay(&self, attempt: u32, err: &anyhow::Error) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let retry_after = match retryable(err)? {
+            Some(secs) => Duration::from_secs(secs),
+            None => Duration::ZERO,
+        };
+        let backoff = self
+            .initial_backoff_ms
+            .saturating_mul(1 << (attempt - 1).min(32))
+            .min(self.max_backoff_ms);
+        // anywhere between half the backoff and all of it, so that clients
+        // failing together don't all come back at once
+        let jittered = backoff / 2 + random() % (backoff / 2 + 1);
+        Some(Duration::from_millis(jittered).max(retry_after))
+    }
+}
+
+/// Whether `err` is transient, with the seconds the server asked to wait.
+fn retryable(err: &anyhow::Error) -> Option<Option<u64>> {
+    if let Some(e) = err.downcast_ref::<reqwest::Error>() {
+        return (!e.is_builder() && !e.is_redirect()).then_some(None);
+    }
+    match err.downcast_ref::<MangaError>()? {
+        MangaError::RateLimited {
+            retry_after_secs, ..
+        } => Some(*retry_after_secs),
+        MangaError::HttpStatus { status, .. } => {
+            matches!(status, 408 | 500 | 502 | 503 | 504).then_some(None)
+        }
+        _ => None,
+    }
+}
+
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::MangaError;
+
+    use super::RetryPolicy;
+
+    fn status(status: u16) -> anyhow::Error {
+        MangaError::HttpStatus {
+            site_id: "a".into(),
+            url: "https://a.example/".into(),
+            status,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 300,
+            deadline_secs: 60,
+        };
+        let ms = |attempt, err| policy.delay(attempt, &err).map(|d| d.as_millis());
+
+        let first = ms(1, status(503)).unwrap();
+        assert!((50..=100).contains(&first), "{}", first);
+        let second = ms(2, status(502)).unwrap();
+        assert!((100..=200).contains(&second), "{}", second);
+        let third = ms(3, status(500)).unwrap();
+        assert!((150..=300).contains(&third), "{}", third);
+        assert_eq!(ms(4, status(503)), None);
+
+        assert_eq!(ms(1, status(404)), None);
+        assert_eq!(ms(1, anyhow::anyhow!("no chapters")), None);
+        let blocked = MangaError::Blocked {
+            site_id: "a".into(),
+            url: "https://a.example/".into(),
+            status: 503,
+        };
+        assert_eq!(ms(1, blocked.into()), None);
+
+        let limited = MangaError::RateLimited {
+            site_id: "a".into(),
+            url: "https://a.example/".into(),
+            retry_after_secs: Some(2),
+        };
+        assert_eq!(
+            policy.delay(1, &limited.into()),
+            Some(Duration::from_secs(2))
+        );
+    }
+}
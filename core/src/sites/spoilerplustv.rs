@@ -1,69 +1,176 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use lazy_static::lazy_static;
 use regex::Regex;
 use reqwest::header::HeaderMap;
 use scraper::{Html, Selector};
 
 use crate::rt;
 
-use super::{Link, MangaSite};
+use super::{
+    canonical::resolve, client::{self, SiteClient}, cursor_page, fetch_text, image_headers, manga_page_link, parse_failed, probe, select_text, select_texts, split_names, Link, Listing, MangaDetails, MangaSite,
+    Page, PublicationStatus, SearchPage, SiteCapabilities,
+};
+
+const ID: &str = "spoilerplustv";
+const BASE_URL: &str = "https://spoilerplus.tv";
+const CDN_URL: &str = "https://cdn1.mangarawspoiler.co";
 
 #[derive(Debug)]
 pub struct Spoilerplustv {
-    client: reqwest::Client,
+    client: SiteClient,
+    base_url: String,
+    cdn_url: String,
+    chapters_re: Regex,
+    images_re: Regex,
 }
 
 impl Default for Spoilerplustv {
     fn default() -> Self {
-        let mut headers = HeaderMap::new();
-        headers.insert("Referer", "https://spoilerplus.tv/".parse().unwrap());
-        let client = reqwest::ClientBuilder::new()
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36")
-            .default_headers(headers)
-            .build().expect("failed to initialize http client");
-        Self { client }
+        Self::new(client(BASE_URL), BASE_URL)
+    }
+}
+
+/// The site checks the referer, so it's sent with every request.
+fn client(base_url: &str) -> SiteClient {
+    let mut headers = HeaderMap::new();
+    headers.insert("Referer", format!("{}/", base_url).parse().unwrap());
+    SiteClient::new(move || client::builder().default_headers(headers.clone()))
+        .expect("failed to initialize http client")
+}
+
+impl Spoilerplustv {
+    pub fn new(client: impl Into<SiteClient>, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let escaped = regex::escape(&base_url);
+        Self {
+            client: client.into(),
+            chapters_re: Regex::new(&format!(r"^{}/[^/]+/$", escaped)).unwrap(),
+            images_re: Regex::new(&format!(r"^{}/[^/]+/[^/]+/$", escaped)).unwrap(),
+            base_url,
+            cdn_url: CDN_URL.to_string(),
+        }
     }
+
+    pub fn with_cdn_url(mut self, cdn_url: &str) -> Self {
+        self.cdn_url = cdn_url.trim_end_matches('/').to_string();
+        self
+    }
+}
+
+/// Fetches a page of manga cards, the markup search and listings share.
+///
+/// `path` is the listing path without the page part, e.g. `/manga/`.
+async fn list(
+    client: SiteClient,
+    base_url: String,
+    path: &str,
+    query: Vec<(&'static str, String)>,
+    page: u32,
+) -> anyhow::Result<SearchPage> {
+    let url = if page > 1 {
+        format!("{}{}page/{}/", base_url, path, page)
+    } else {
+        format!("{}{}", base_url, path)
+    };
+    let res = fetch_text(ID, client.get(&url).query(&query)).await?;
+
+    let doc = Html::parse_document(&res);
+    let selector =
+        Selector::parse("article.item").map_err(|e| anyhow!(format!("error: {:?}", e)))?;
+    let caption = Selector::parse("figcaption h3 a")
+        .map_err(|e| anyhow!(format!("error: {:?}", e)))?;
+    let image =
+        Selector::parse(".image img").map_err(|e| anyhow!(format!("error: {:?}", e)))?;
+    let next = Selector::parse("a.next.page-numbers")
+        .map_err(|e| anyhow!(format!("error: {:?}", e)))?;
+    probe::matches("article.item", || doc.select(&selector).count());
+
+    let results: Vec<Link> = doc
+        .select(&selector)
+        .filter_map(|element| {
+            let caption = element.select(&caption).next();
+            let image = element.select(&image).next();
+
+            probe::require("article.item", "figcaption h3 a", caption)
+                .map(|caption| (caption, image))
+        })
+        .filter_map(|(caption, image)| {
+            let title = probe::require("figcaption h3 a", "text", caption.text().next());
+            let href = probe::require("figcaption h3 a", "href", caption.attr("href"))
+                .and_then(|href| resolve(&url, href));
+
+            if let (Some(title), Some(href)) = (title, href) {
+                Some(Link {
+                    text: title.to_string(),
+                    url: href,
+                    image: image
+                        .and_then(|image| image.attr("data-src"))
+                        .and_then(|src| resolve(&url, src)),
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+    let has_more = doc.select(&next).next().is_some();
+
+    Ok(SearchPage::numbered(results, page, has_more))
 }
 
 #[async_trait]
 impl MangaSite for Spoilerplustv {
+    fn id(&self) -> String {
+        ID.to_string()
+    }
+
     fn name(&self) -> String {
         "spoilerplus.tv".into()
     }
 
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn cdn_url(&self) -> Option<String> {
+        Some(self.cdn_url.clone())
+    }
+
+    fn relocate(&self, base_url: &str, cdn_url: Option<&str>) -> Option<Arc<dyn MangaSite>> {
+        let site = Self::new(client(base_url), base_url)
+            .with_cdn_url(cdn_url.unwrap_or(CDN_URL));
+        Some(Arc::new(site))
+    }
+
     fn request_headers(&self) -> HashMap<String, String> {
         let mut h = HashMap::new();
-        h.insert("Referer".to_string(), "https://spoilerplus.tv/".to_string());
+        h.insert("Referer".to_string(), format!("{}/", self.base_url));
         h
     }
 
     fn can_handle_chapters(&self, url: &str) -> bool {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^https://spoilerplus\.tv/[^/]+/$").unwrap();
-        }
-        RE.is_match(url)
+        self.chapters_re.is_match(url)
     }
 
     async fn chapters(&self, url: &str) -> anyhow::Result<Vec<Link>> {
-        let res = self.client.get(url).send().await?;
-        let res = res.text().await?;
+        let res = fetch_text(ID, self.client.get(url)).await?;
 
         let doc = Html::parse_document(&res);
         let selector = Selector::parse(".list-chapter .chapter > a").unwrap();
+        probe::matches(".list-chapter .chapter > a", || doc.select(&selector).count());
 
         let results: Vec<Link> = doc
             .select(&selector)
             .filter_map(|e| {
-                let text = e.text().next();
-                let link = e.attr("href");
+                let text = probe::require(".list-chapter .chapter > a", "text", e.text().next());
+                let link = probe::require(".list-chapter .chapter > a", "href", e.attr("href"))
+                    .and_then(|link| resolve(url, link));
 
                 if let (Some(text), Some(link)) = (text, link) {
                     Some(Link {
                         text: text.trim().to_string(),
-                        url: format!("https://spoilerplus.tv{}", link),
+                        url: link,
                         image: None,
                     })
                 } else {
@@ -75,86 +182,90 @@ impl MangaSite for Spoilerplustv {
         Ok(results)
     }
 
+    async fn details(&self, url: &str) -> anyhow::Result<MangaDetails> {
+        let res = fetch_text(ID, self.client.get(url)).await?;
+
+        let doc = Html::parse_document(&res);
+        Ok(MangaDetails {
+            authors: select_texts(&doc, ".author-content a"),
+            artists: select_texts(&doc, ".artist-content a"),
+            genres: select_texts(&doc, ".genres-content a"),
+            status: select_text(&doc, ".post-status .summary-content")
+                .map(|s| PublicationStatus::parse(&s))
+                .unwrap_or_default(),
+            alt_titles: select_text(&doc, ".post-alternative .summary-content")
+                .map(|s| split_names(&s))
+                .unwrap_or_default(),
+            description: select_text(&doc, ".summary__content"),
+        })
+    }
+
+    async fn manga_link(&self, url: &str) -> anyhow::Result<Link> {
+        let res = fetch_text(ID, self.client.get(url)).await?;
+
+        let doc = Html::parse_document(&res);
+        manga_page_link(&doc, url, select_text(&doc, ".post-title h1"), None)
+            .ok_or_else(|| parse_failed(ID, url, "no title"))
+    }
+
     fn can_handle_images(&self, url: &str) -> bool {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^https://spoilerplus\.tv/[^/]+/[^/]+/$").unwrap();
-        }
-        RE.is_match(url)
+        self.images_re.is_match(url)
     }
 
-    async fn images(&self, url: &str) -> anyhow::Result<Vec<String>> {
-        let res = self.client.get(url).send().await?;
-        let res = res.text().await?;
+    async fn images(&self, url: &str) -> anyhow::Result<Vec<Page>> {
+        let res = fetch_text(ID, self.client.get(url)).await?;
 
         let doc = Html::parse_document(&res);
         let selector = Selector::parse("#post-comic .ct").unwrap();
+        probe::matches("#post-comic .ct", || doc.select(&selector).count());
 
         let results: Vec<String> = doc
             .select(&selector)
-            .filter_map(|e| e.attr("data-z"))
-            .map(|t| format!("https://cdn1.mangarawspoiler.co{}", t))
+            .filter_map(|e| probe::require("#post-comic .ct", "data-z", e.attr("data-z")))
+            .map(|t| format!("{}{}", self.cdn_url, t))
             .collect();
-        Ok(results)
+        Ok(Page::list(results, &image_headers(self, url)))
     }
 
-    async fn search(&self, text: String) -> anyhow::Result<Vec<Link>> {
+    async fn search(&self, text: String, cursor: Option<String>) -> anyhow::Result<SearchPage> {
         let client = self.client.clone();
-        rt().spawn(async move {
+        let base_url = self.base_url.clone();
+        let page = cursor_page(cursor.as_deref())?;
+        rt().spawn(probe::inherit(async move {
             tracing::info!("search spoilerplus");
-            let mut query = HashMap::new();
-            query.insert("s", text);
-
-            let res = client
-                .get("https://spoilerplus.tv/")
-                .query(&query)
-                .send()
-                .await?;
-            let res = res.text().await?;
-
-            let doc = Html::parse_document(&res);
-            let selector =
-                Selector::parse("article.item").map_err(|e| anyhow!(format!("error: {:?}", e)))?;
-            let caption = Selector::parse("figcaption h3 a")
-                .map_err(|e| anyhow!(format!("error: {:?}", e)))?;
-            let image =
-                Selector::parse(".image img").map_err(|e| anyhow!(format!("error: {:?}", e)))?;
-
-            let results: Vec<Link> = doc
-                .select(&selector)
-                .filter_map(|element| {
-                    let caption = element.select(&caption).next();
-                    let image = element.select(&image).next();
-
-                    if let Some(caption) = caption {
-                        Some((caption, image))
-                    } else {
-                        None
-                    }
-                })
-                .filter_map(|(caption, image)| {
-                    let title = caption.text().next();
-                    let link_path = caption.attr("href");
-
-                    if title.is_some() && link_path.is_some() {
-                        let mut link = Link {
-                            text: title.unwrap().to_string(),
-                            url: format!("https://spoilerplus.tv{}", link_path.unwrap()),
-                            image: None,
-                        };
-                        if let Some(image) = image {
-                            if let Some(src) = image.attr("data-src") {
-                                link.image = Some(format!("https://spoilerplus.tv{}", src));
-                            }
-                        }
-                        Some(link)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+            list(client, base_url, "/", vec![("s", text)], page).await
+        }))
+        .await?
+    }
 
-            Ok(results)
-        })
+    fn capabilities(&self) -> SiteCapabilities {
+        SiteCapabilities {
+            listings: vec![Listing::Latest, Listing::Popular],
+            genre_filter: true,
+        }
+    }
+
+    async fn browse(
+        &self,
+        listing: Listing,
+        genre: Option<String>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<SearchPage> {
+        self.capabilities().check(&self.name(), listing, genre.as_deref())?;
+        let client = self.client.clone();
+        let base_url = self.base_url.clone();
+        let page = cursor_page(cursor.as_deref())?;
+        rt().spawn(probe::inherit(async move {
+            let orderby = match listing {
+                Listing::Latest => "latest",
+                Listing::Popular => "views",
+            };
+            let path = match genre {
+                Some(genre) => format!("/manga-genre/{}/", genre),
+                None => "/manga/".to_string(),
+            };
+            list(client, base_url, &path, vec![("m_orderby", orderby.to_string())], page).await
+        }))
         .await?
     }
 }
@@ -163,15 +274,153 @@ impl MangaSite for Spoilerplustv {
 mod tests {
     use std::sync::Arc;
 
-    use crate::sites::MangaSite;
+    use crate::sites::{
+        testing::{Fixture, FixtureServer},
+        Listing, MangaSite, PublicationStatus,
+    };
+
+    use super::Spoilerplustv;
+
+    async fn fixture_site() -> (FixtureServer, Spoilerplustv) {
+        let server = FixtureServer::start(vec![
+            ("/", Fixture::html(include_str!("../../fixtures/spoilerplustv/search.html"))),
+            (
+                "/page/2/",
+                Fixture::html(include_str!("../../fixtures/spoilerplustv/search_page2.html")),
+            ),
+            ("/manga/", Fixture::html(include_str!("../../fixtures/spoilerplustv/search.html"))),
+            (
+                "/manga-genre/action/page/2/",
+                Fixture::html(include_str!("../../fixtures/spoilerplustv/search_page2.html")),
+            ),
+            (
+                "/one-punch-man/",
+                Fixture::html(include_str!("../../fixtures/spoilerplustv/manga.html")),
+            ),
+            (
+                "/one-punch-man/chapter-200/",
+                Fixture::html(include_str!("../../fixtures/spoilerplustv/chapter.html")),
+            ),
+        ])
+        .await;
+        let site = Spoilerplustv::new(reqwest::Client::new(), &server.url());
+        (server, site)
+    }
+
+    #[tokio::test]
+    async fn test_search_fixture() {
+        let (server, s) = fixture_site().await;
+
+        let page = s.search("one".into(), None).await.unwrap();
+        assert!(page.has_more);
+        let res = page.links;
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].text, "ワンパンマン");
+        assert_eq!(res[0].url, format!("{}/one-punch-man/", server.url()));
+        assert_eq!(
+            res[0].image,
+            Some(format!("{}/wp-content/uploads/one-punch-man.jpg", server.url()))
+        );
+        assert_eq!(res[1].image, None);
+        assert!(s.can_handle_chapters(&res[0].url));
+
+        let page = s.search("one".into(), page.next_cursor).await.unwrap();
+        assert!(!page.has_more);
+        assert_eq!(page.links.len(), 1);
+        assert_eq!(page.links[0].text, "ワンルームエンジェル");
+
+        assert_eq!(server.requests(), vec!["/?s=one", "/page/2/?s=one"]);
+    }
+
+    #[tokio::test]
+    async fn test_browse_fixture() {
+        let (server, s) = fixture_site().await;
+
+        let page = s.browse(Listing::Latest, None, None).await.unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.links.len(), 2);
+        assert_eq!(page.links[0].url, format!("{}/one-punch-man/", server.url()));
+
+        let page = s
+            .browse(Listing::Popular, Some("action".into()), page.next_cursor)
+            .await
+            .unwrap();
+        assert!(!page.has_more);
+        assert_eq!(page.links[0].text, "ワンルームエンジェル");
+
+        assert_eq!(
+            server.requests(),
+            vec![
+                "/manga/?m_orderby=latest",
+                "/manga-genre/action/page/2/?m_orderby=views",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chapters_fixture() {
+        let (server, s) = fixture_site().await;
+        let url = format!("{}/one-punch-man/", server.url());
+
+        let res = s.chapters(&url).await.unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].text, "第200話");
+        assert_eq!(res[0].url, format!("{}chapter-200/", url));
+        assert!(s.can_handle_images(&res[0].url));
+    }
+
+    #[tokio::test]
+    async fn test_details_fixture() {
+        let (server, s) = fixture_site().await;
+        let url = format!("{}/one-punch-man/", server.url());
+
+        let res = s.details(&url).await.unwrap();
+        assert_eq!(res.authors, vec!["ONE"]);
+        assert_eq!(res.artists, vec!["村田雄介"]);
+        assert_eq!(res.genres, vec!["アクション", "コメディ"]);
+        assert_eq!(res.status, PublicationStatus::Ongoing);
+        assert_eq!(res.alt_titles, vec!["One Punch-Man", "ONE PUNCH-MAN"]);
+        assert_eq!(
+            res.description.as_deref(),
+            Some("趣味でヒーローをやっている男・サイタマ。")
+        );
+
+        // no heading on the page, so the title comes from `<title>`
+        let link = s.manga_link(&url).await.unwrap();
+        assert_eq!(link.text, "ワンパンマン");
+        assert_eq!(link.image, None);
+    }
+
+    #[tokio::test]
+    async fn test_images_fixture() {
+        let (server, s) = fixture_site().await;
+        let url = format!("{}/one-punch-man/chapter-200/", server.url());
+
+        let res = s.images(&url).await.unwrap();
+        assert_eq!(
+            res.iter().map(|p| p.url.as_str()).collect::<Vec<_>>(),
+            vec![
+                "https://cdn1.mangarawspoiler.co/one-punch-man/200/01.jpg",
+                "https://cdn1.mangarawspoiler.co/one-punch-man/200/02.jpg",
+            ]
+        );
+        assert_eq!(
+            s.request_headers().get("Referer"),
+            Some(&format!("{}/", server.url()))
+        );
+        // the site's referer is kept over the chapter's
+        assert_eq!(res[1].index, 1);
+        assert_eq!(res[1].headers["Referer"], format!("{}/", server.url()));
+    }
 
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_search() {
         let s: Arc<dyn MangaSite> = Arc::new(super::Spoilerplustv::default());
-        let res = s.search("one".into()).await;
+        let res = s.search("one".into(), None).await;
 
         assert!(res.is_ok());
-        let res = res.unwrap();
+        let res = res.unwrap().links;
 
         assert!(res.len() > 0);
         println!("res[0]: {:?}", res[0]);
@@ -0,0 +1,171 @@
+//! Local stand-in server serving recorded fixtures, so site scrapers can be
+//! tested without hitting the live sites.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// 0 closes the connection without answering.
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How many requests the fixture answers before the next one listed
+    /// for the same route takes over. `None` answers them all.
+    pub times: Option<usize>,
+}
+
+impl Fixture {
+    pub fn html(body: &str) -> Self {
+        Self::new(200, "text/html; charset=UTF-8", body)
+    }
+
+    pub fn json(body: &str) -> Self {
+        Self::new(200, "application/json", body)
+    }
+
+    pub fn new(status: u16, content_type: &str, body: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body: body.to_string(),
+            times: None,
+        }
+    }
+
+    /// Closes the connection as soon as the request is read.
+    pub fn hang_up() -> Self {
+        Self::new(0, "text/plain", "")
+    }
+
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+type Routes = Mutex<HashMap<String, Vec<Fixture>>>;
+
+#[derive(Debug)]
+pub struct FixtureServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FixtureServer {
+    /// Starts serving `routes` on a random local port.
+    ///
+    /// A route matches either the full request target (`/path?query`) or
+    /// just the path. A route listed several times is answered by each
+    /// fixture in turn, see `Fixture::times`. `{base_url}` in fixture bodies
+    /// is replaced with the server's own url, so recorded absolute links
+    /// point back to it.
+    pub async fn start(routes: Vec<(&str, Fixture)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let base_url = format!("http://{}", addr);
+
+        let mut map: HashMap<String, Vec<Fixture>> = HashMap::new();
+        for (path, mut fixture) in routes.into_iter() {
+            fixture.body = fixture.body.replace("{base_url}", &base_url);
+            map.entry(path.to_string()).or_default().push(fixture);
+        }
+        let routes = Arc::new(Mutex::new(map));
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let log = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let routes = routes.clone();
+                let log = log.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, &routes, &log).await;
+                });
+            }
+        });
+
+        Self { addr, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Request targets received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Fixture answering `target`, used up when its `times` runs out.
+fn next_fixture(routes: &Routes, target: &str) -> Option<Fixture> {
+    let mut routes = routes.lock().unwrap();
+    let path = target.split('?').next().unwrap_or("/");
+    let key = if routes.contains_key(target) {
+        target
+    } else {
+        path
+    };
+    let fixtures = routes.get_mut(key)?;
+    let fixture = fixtures.first_mut()?;
+    let answer = fixture.clone();
+    if let Some(times) = fixture.times.as_mut() {
+        *times = times.saturating_sub(1);
+        if *times == 0 {
+            fixtures.remove(0);
+        }
+    }
+    Some(answer)
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    routes: &Routes,
+    log: &Mutex<Vec<String>>,
+) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let target = request
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_string();
+    log.lock().unwrap().push(target.clone());
+
+    let fixture = next_fixture(routes, &target)
+        .unwrap_or_else(|| Fixture::new(404, "text/plain", "not found"));
+    if fixture.status == 0 {
+        return Ok(());
+    }
+
+    let mut response = format!("HTTP/1.1 {} Fixture\r\n", fixture.status);
+    for (k, v) in fixture.headers.iter() {
+        response.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    response.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        fixture.body.len()
+    ));
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.write_all(fixture.body.as_bytes()).await?;
+    stream.shutdown().await
+}
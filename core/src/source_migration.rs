@@ -0,0 +1,174 @@
+//! Moving a manga to another site while keeping what has been read.
+
+use std::collections::HashSet;
+
+use crate::{chapter_number, db::ChapterDb, sites::Link};
+
+/// A chapter of the new site paired with the stored chapter of the same number.
+#[derive(Debug)]
+pub struct ChapterMatch {
+    /// Chapter number parsed from the title, `None` when it has none.
+    pub number: Option<f64>,
+    /// Stored chapter whose read state, reading progress and timestamps are
+    /// carried over.
+    pub old: Option<ChapterDb>,
+    /// Chapter on the new site. `None` when only the stored one exists, in
+    /// which case its read state is lost by the migration.
+    pub new: Option<Link>,
+}
+
+#[derive(Debug)]
+pub struct SourceMigration {
+    pub manga_id: i64,
+    /// New chapters in the new site's order, then stored chapters that
+    /// found no match.
+    pub chapters: Vec<ChapterMatch>,
+    /// Chapters of the new site that aren't stored because an earlier one
+    /// has the same key.
+    pub skipped: Vec<Link>,
+    /// False for a dry run.
+    pub applied: bool,
+}
+
+impl SourceMigration {
+    /// Stored chapters marked read whose read state can't be carried over.
+    pub fn lost_reads(&self) -> usize {
+        self.chapters
+            .iter()
+            .filter(|m| m.new.is_none() && m.old.as_ref().is_some_and(|c| c.is_read != 0))
+            .count()
+    }
+}
+
+/// Pairs chapters by parsed number. Each stored chapter is used at most once,
+/// so a number listed twice on the new site matches only one of them.
+pub fn match_chapters(old: Vec<ChapterDb>, new: Vec<Link>) -> Vec<ChapterMatch> {
+    let mut old: Vec<Option<ChapterDb>> = old.into_iter().map(Some).collect();
+
+    let mut matches: Vec<ChapterMatch> = new
+        .into_iter()
+        .map(|link| {
+            let number = chapter_number::parse(&link.text).chapter;
+            let old = number.and_then(|n| {
+                old.iter_mut()
+                    .find(|c| c.as_ref().is_some_and(|c| same_number(c.title_number, n)))
+                    .and_then(Option::take)
+            });
+            ChapterMatch {
+                number,
+                old,
+                new: Some(link),
+            }
+        })
+        .collect();
+
+    matches.extend(old.into_iter().flatten().map(|c| ChapterMatch {
+        number: Some(c.title_number),
+        old: Some(c),
+        new: None,
+    }));
+    matches
+}
+
+/// New chapters whose key, as given by `key`, an earlier chapter in
+/// `chapters` already has.
+pub fn duplicates(chapters: &[ChapterMatch], key: impl Fn(&str) -> String) -> Vec<Link> {
+    let mut keys = HashSet::new();
+    chapters
+        .iter()
+        .filter_map(|m| m.new.as_ref())
+        .filter(|link| !keys.insert(key(&link.url)))
+        .cloned()
+        .collect()
+}
+
+fn same_number(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{db::ChapterDb, sites::Link};
+
+    use super::{duplicates, match_chapters, SourceMigration};
+
+    fn stored(title: &str, number: f64, is_read: bool) -> ChapterDb {
+        ChapterDb {
+            id: number as i64,
+            manga: 1,
+            title: title.to_string(),
+            title_number: number,
+            url: format!("https://old/{}", number),
+            is_read: if is_read { 1 } else { 0 },
+            created_at: 100,
+            updated_at: 200,
+            chapter_key: None,
+            last_page: None,
+            total_pages: None,
+            read_at: None,
+        }
+    }
+
+    fn link(title: &str) -> Link {
+        Link {
+            text: title.to_string(),
+            url: format!("https://new/{}", title),
+            image: None,
+        }
+    }
+
+    #[test]
+    fn test_match_chapters() {
+        let old = vec![
+            stored("第3話", 3.0, false),
+            stored("第2.5話", 2.5, true),
+            stored("第2話", 2.0, true),
+            stored("第1話", 1.0, true),
+        ];
+        let new = vec![
+            link("Chapter 4"),
+            link("Chapter 3"),
+            link("Chapter 2.5"),
+            link("Chapter 2.5"),
+            link("Chapter 2"),
+            link("Special"),
+        ];
+
+        let matches = match_chapters(old, new);
+        let pairs: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.new.as_ref().map(|l| l.text.as_str()),
+                    m.old.as_ref().map(|c| c.title.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Some("Chapter 4"), None),
+                (Some("Chapter 3"), Some("第3話")),
+                (Some("Chapter 2.5"), Some("第2.5話")),
+                (Some("Chapter 2.5"), None),
+                (Some("Chapter 2"), Some("第2話")),
+                (Some("Special"), None),
+                (None, Some("第1話")),
+            ]
+        );
+        assert_eq!(matches[0].number, Some(4.0));
+        assert_eq!(matches[5].number, None);
+
+        let skipped = duplicates(&matches, |url| url.to_string());
+        let skipped: Vec<_> = skipped.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(skipped, vec!["Chapter 2.5"]);
+
+        let migration = SourceMigration {
+            manga_id: 1,
+            chapters: matches,
+            skipped: vec![],
+            applied: false,
+        };
+        assert_eq!(migration.lost_reads(), 1);
+    }
+}